@@ -0,0 +1,388 @@
+use super::{
+    BidOffer, Increment, InstrumentSpec, L2FullAmountMarketData, MarketDataError, MarketSide,
+    UpdateAction,
+};
+use std::{
+    collections::{btree_map::Entry, BTreeMap, BTreeSet},
+    ops::{Add, Div},
+};
+
+/// The rules deciding which liquidity providers a client can be priced from
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub enum LpFilter<L>
+where
+    L: Ord,
+{
+    /// Price from every liquidity provider
+    #[default]
+    All,
+    /// Only price from the listed liquidity providers
+    Include(BTreeSet<L>),
+    /// Price from every liquidity provider except those listed
+    Exclude(BTreeSet<L>),
+}
+
+impl<L> LpFilter<L>
+where
+    L: Ord,
+{
+    /// Returns true if the liquidity provider can be used
+    ///
+    /// # Parameters
+    ///
+    /// * `lp` - The liquidity provider
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::LpFilter;
+    ///
+    /// let filter = LpFilter::Exclude(["LP2"].into_iter().collect());
+    ///
+    /// assert!(filter.is_allowed(&"LP1"));
+    /// assert!(!filter.is_allowed(&"LP2"));
+    /// ```
+    pub fn is_allowed(&self, lp: &L) -> bool {
+        match self {
+            LpFilter::All => true,
+            LpFilter::Include(lps) => lps.contains(lp),
+            LpFilter::Exclude(lps) => !lps.contains(lp),
+        }
+    }
+}
+
+/// A structure to hold Full Amount L2 pricing from multiple liquidity providers.  Full amount quotes cannot be swept
+/// across providers, so the price for a size is the best price any single provider quotes for it.
+///
+/// # Generic Parameters
+///
+/// * `L` - The liquidity provider type that should be used.
+/// * `P` - The price type that should be used.
+/// * `A` - The amount type that should be used.
+pub struct AggregatedFullAmountMarketData<L, P, A>
+where
+    L: Ord + Clone,
+    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<u8> + Increment,
+    A: Copy + Ord + PartialOrd + Add<Output = A> + Div<Output = A> + From<u8> + Increment,
+{
+    lps: BTreeMap<L, L2FullAmountMarketData<P, A>>,
+    spec: Option<InstrumentSpec<P, A>>,
+    client_filters: BTreeMap<String, LpFilter<L>>,
+}
+
+impl<L, P, A> AggregatedFullAmountMarketData<L, P, A>
+where
+    L: Ord + Clone,
+    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<u8> + Increment,
+    A: Copy + Ord + PartialOrd + Add<Output = A> + Div<Output = A> + From<u8> + Increment,
+{
+    /// Use the new function to create a new AggregatedFullAmountMarketData with no pricing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{AggregatedFullAmountMarketData, BidOffer};
+    ///
+    /// let market_data = AggregatedFullAmountMarketData::<&str, i32, i32>::new();
+    ///
+    /// assert_eq!(market_data.get_price(1), BidOffer::new());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            lps: BTreeMap::new(),
+            spec: None,
+            client_filters: BTreeMap::new(),
+        }
+    }
+
+    /// Update the full amount pricing of a liquidity provider.  The book for the provider is created on its first Add, and
+    /// only if that Add is accepted.
+    ///
+    /// # Parameters
+    ///
+    /// * `lp` - The liquidity provider the update is from
+    /// * `action` - The update action
+    /// * `side` - The side of the market
+    /// * `price` - The price for the size
+    /// * `size` - The size tier
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{AggregatedFullAmountMarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = AggregatedFullAmountMarketData::<&str, i32, i32>::new();
+    ///
+    /// market_data.update("LP1", UpdateAction::Add, MarketSide::Offer, 101, 1_000_000).unwrap();
+    /// market_data.update("LP1", UpdateAction::Add, MarketSide::Offer, 103, 5_000_000).unwrap();
+    /// market_data.update("LP2", UpdateAction::Add, MarketSide::Offer, 102, 5_000_000).unwrap();
+    ///
+    /// assert_eq!(market_data.get_best_quote(MarketSide::Offer, 1_000_000), Some(("LP1", 101)));
+    /// assert_eq!(market_data.get_best_quote(MarketSide::Offer, 2_000_000), Some(("LP2", 102)));
+    /// assert_eq!(market_data.get_best_quote(MarketSide::Offer, 6_000_000), None);
+    /// ```
+    pub fn update(
+        &mut self,
+        lp: L,
+        action: UpdateAction,
+        side: MarketSide,
+        price: P,
+        size: A,
+    ) -> Result<(), MarketDataError> {
+        match action {
+            UpdateAction::Add | UpdateAction::Replace | UpdateAction::Snapshot => {
+                match self.lps.entry(lp) {
+                    Entry::Occupied(entry) => entry.into_mut().update(action, side, price, size),
+                    Entry::Vacant(entry) => {
+                        let mut book = L2FullAmountMarketData::new();
+                        book.set_instrument_spec(self.spec.clone());
+                        book.update(action, side, price, size)?;
+                        entry.insert(book);
+
+                        Ok(())
+                    }
+                }
+            }
+            _ => match self.lps.get_mut(&lp) {
+                Some(book) => book.update(action, side, price, size),
+                None => Ok(()),
+            },
+        }
+    }
+
+    /// Set the instrument specification that prices and sizes from every liquidity provider are validated against
+    pub fn set_instrument_spec(&mut self, spec: Option<InstrumentSpec<P, A>>) {
+        for book in self.lps.values_mut() {
+            book.set_instrument_spec(spec.clone());
+        }
+
+        self.spec = spec;
+    }
+
+    /// Get the instrument specification prices and sizes are validated against
+    pub fn get_instrument_spec(&self) -> &Option<InstrumentSpec<P, A>> {
+        &self.spec
+    }
+
+    /// Get the book of a single liquidity provider
+    pub fn get_lp(&self, lp: &L) -> Option<&L2FullAmountMarketData<P, A>> {
+        self.lps.get(lp)
+    }
+
+    /// Remove all the pricing from a liquidity provider, for example if the connection to it is lost
+    pub fn remove_lp(&mut self, lp: &L) {
+        self.lps.remove(lp);
+    }
+
+    pub fn clear(&mut self) {
+        self.lps.clear();
+    }
+
+    /// Set the rules for which liquidity providers a client can be priced from.  Clients without rules are priced from
+    /// every liquidity provider.
+    ///
+    /// # Parameters
+    ///
+    /// * `client` - The client
+    /// * `filter` - The liquidity provider rules for the client
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{AggregatedFullAmountMarketData, BidOffer, LpFilter, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = AggregatedFullAmountMarketData::<&str, i32, i32>::new();
+    ///
+    /// market_data.update("LP1", UpdateAction::Add, MarketSide::Bid, 100, 1_000_000).unwrap();
+    /// market_data.update("LP2", UpdateAction::Add, MarketSide::Bid, 99, 1_000_000).unwrap();
+    /// market_data.set_client_filter("ClientA", LpFilter::Exclude(["LP1"].into_iter().collect()));
+    ///
+    /// assert_eq!(market_data.get_client_price("ClientA", 1_000_000), BidOffer::new_with_price(Some(99), None));
+    /// assert_eq!(market_data.get_client_price("ClientB", 1_000_000), BidOffer::new_with_price(Some(100), None));
+    /// ```
+    pub fn set_client_filter(&mut self, client: &str, filter: LpFilter<L>) {
+        self.client_filters.insert(client.to_string(), filter);
+    }
+
+    /// Remove the liquidity provider rules for a client
+    pub fn remove_client_filter(&mut self, client: &str) {
+        self.client_filters.remove(client);
+    }
+
+    /// Get the liquidity provider rules for a client
+    pub fn get_client_filter(&self, client: &str) -> Option<&LpFilter<L>> {
+        self.client_filters.get(client)
+    }
+
+    /// Get the best price any liquidity provider quotes for a size
+    pub fn get_price(&self, size: A) -> BidOffer<P> {
+        self.get_filtered_price(size, &LpFilter::All)
+    }
+
+    /// Get the best price any liquidity provider a client can be priced from quotes for a size
+    pub fn get_client_price(&self, client: &str, size: A) -> BidOffer<P> {
+        match self.client_filters.get(client) {
+            Some(filter) => self.get_filtered_price(size, filter),
+            None => self.get_price(size),
+        }
+    }
+
+    /// Get the best price any liquidity provider allowed by the filter quotes for a size
+    pub fn get_filtered_price(&self, size: A, filter: &LpFilter<L>) -> BidOffer<P> {
+        BidOffer::new_with_price(
+            self.get_filtered_quote(MarketSide::Bid, size, filter)
+                .map(|(_, price)| price),
+            self.get_filtered_quote(MarketSide::Offer, size, filter)
+                .map(|(_, price)| price),
+        )
+    }
+
+    /// Get the best price for a size on one side of the market, along with the liquidity provider quoting it.  If
+    /// providers quote the same price the first in liquidity provider order is returned.
+    pub fn get_best_quote(&self, side: MarketSide, size: A) -> Option<(L, P)> {
+        self.get_filtered_quote(side, size, &LpFilter::All)
+    }
+
+    /// Get the best price for a size on one side of the market from the liquidity providers a client can be priced from,
+    /// along with the liquidity provider quoting it.
+    pub fn get_client_quote(&self, client: &str, side: MarketSide, size: A) -> Option<(L, P)> {
+        match self.client_filters.get(client) {
+            Some(filter) => self.get_filtered_quote(side, size, filter),
+            None => self.get_best_quote(side, size),
+        }
+    }
+
+    /// Get the best price for a size on one side of the market from the liquidity providers allowed by the filter, along
+    /// with the liquidity provider quoting it.
+    pub fn get_filtered_quote(
+        &self,
+        side: MarketSide,
+        size: A,
+        filter: &LpFilter<L>,
+    ) -> Option<(L, P)> {
+        let mut best: Option<(&L, P)> = None;
+
+        for (lp, book) in self.lps.iter().filter(|(lp, _)| filter.is_allowed(lp)) {
+            if let Some(price) = *book.get_price(size).get(side) {
+                let is_better =
+                    best.is_none_or(|(_, best_price)| side.is_better(price, best_price));
+
+                if is_better {
+                    best = Some((lp, price));
+                }
+            }
+        }
+
+        best.map(|(lp, price)| (lp.clone(), price))
+    }
+}
+
+impl<L, P, A> Default for AggregatedFullAmountMarketData<L, P, A>
+where
+    L: Ord + Clone,
+    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<u8> + Increment,
+    A: Copy + Ord + PartialOrd + Add<Output = A> + Div<Output = A> + From<u8> + Increment,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market_data::SpecPolicy;
+
+    #[test]
+    fn best_quote_by_size() {
+        let mut test = AggregatedFullAmountMarketData::new();
+
+        test.update(1, UpdateAction::Add, MarketSide::Bid, 100, 10)
+            .unwrap();
+        test.update(1, UpdateAction::Add, MarketSide::Bid, 95, 50)
+            .unwrap();
+        test.update(2, UpdateAction::Add, MarketSide::Bid, 99, 10)
+            .unwrap();
+        test.update(2, UpdateAction::Add, MarketSide::Bid, 97, 50)
+            .unwrap();
+        test.update(3, UpdateAction::Add, MarketSide::Bid, 100, 10)
+            .unwrap();
+
+        assert_eq!(test.get_best_quote(MarketSide::Bid, 5), Some((1, 100)));
+        assert_eq!(test.get_best_quote(MarketSide::Bid, 20), Some((2, 97)));
+        assert_eq!(test.get_best_quote(MarketSide::Bid, 60), None);
+        assert_eq!(test.get_best_quote(MarketSide::Offer, 5), None);
+
+        test.remove_lp(&2);
+        assert_eq!(test.get_best_quote(MarketSide::Bid, 20), Some((1, 95)));
+
+        test.update(1, UpdateAction::Update, MarketSide::Bid, 101, 10)
+            .unwrap();
+        test.update(4, UpdateAction::Update, MarketSide::Bid, 102, 10)
+            .unwrap();
+        assert_eq!(
+            test.get_price(10),
+            BidOffer::new_with_price(Some(101), None)
+        );
+        assert!(test.get_lp(&4).is_none());
+
+        test.clear();
+        assert_eq!(test.get_price(10), BidOffer::new());
+    }
+
+    #[test]
+    fn client_filters() {
+        let mut test = AggregatedFullAmountMarketData::new();
+
+        test.update("LP1", UpdateAction::Add, MarketSide::Offer, 101, 10)
+            .unwrap();
+        test.update("LP2", UpdateAction::Add, MarketSide::Offer, 102, 10)
+            .unwrap();
+        test.update("LP3", UpdateAction::Add, MarketSide::Offer, 103, 10)
+            .unwrap();
+
+        test.set_client_filter("A", LpFilter::Include(["LP2", "LP3"].into_iter().collect()));
+        test.set_client_filter("B", LpFilter::Exclude(["LP1", "LP2"].into_iter().collect()));
+
+        assert_eq!(
+            test.get_client_quote("A", MarketSide::Offer, 10),
+            Some(("LP2", 102))
+        );
+        assert_eq!(
+            test.get_client_quote("B", MarketSide::Offer, 10),
+            Some(("LP3", 103))
+        );
+        assert_eq!(
+            test.get_client_quote("C", MarketSide::Offer, 10),
+            Some(("LP1", 101))
+        );
+
+        test.remove_client_filter("A");
+        assert_eq!(test.get_client_filter("A"), None);
+        assert_eq!(
+            test.get_client_price("A", 10),
+            BidOffer::new_with_price(None, Some(101))
+        );
+    }
+
+    #[test]
+    fn instrument_spec() {
+        let mut test = AggregatedFullAmountMarketData::<u8, i32, i32>::new();
+
+        test.update(1, UpdateAction::Add, MarketSide::Bid, 100, 15)
+            .unwrap();
+        test.set_instrument_spec(Some(
+            InstrumentSpec::new(SpecPolicy::Reject).with_lot_size(10),
+        ));
+
+        assert_eq!(
+            test.update(1, UpdateAction::Add, MarketSide::Bid, 100, 25),
+            Err(MarketDataError::OffLotSize)
+        );
+        assert_eq!(
+            test.update(2, UpdateAction::Add, MarketSide::Bid, 100, 25),
+            Err(MarketDataError::OffLotSize)
+        );
+        assert!(test.get_lp(&2).is_none());
+    }
+}
@@ -1,4 +1,5 @@
-use std::ops::{Add, Div};
+use super::{CrossStatus, FloatConvert, MarketSide};
+use std::ops::{Add, Div, Neg, Sub};
 
 /// A structure to hold the pricing for a specific size in the market.  Values are options as there may not be a price for the requested size.
 ///
@@ -8,7 +9,7 @@ use std::ops::{Add, Div};
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub struct BidOffer<P>
 where
-    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<i32>,
+    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<u8>,
 {
     /// Store the bid price
     bid: Option<P>,
@@ -18,7 +19,7 @@ where
 
 impl<P> BidOffer<P>
 where
-    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<i32>,
+    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<u8>,
 {
     /// Use the new function to create a new BidOffer which has no pricing
     ///
@@ -107,11 +108,268 @@ where
             self.offer
         }
     }
+
+    /// Get whether the bid and offer overlap, which is only possible when there are both
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, CrossStatus};
+    ///
+    /// assert_eq!(BidOffer::new_with_price(Some(10), Some(20)).get_cross_status(), CrossStatus::Normal);
+    /// assert_eq!(BidOffer::new_with_price(Some(20), Some(20)).get_cross_status(), CrossStatus::Locked);
+    /// assert_eq!(BidOffer::new_with_price(Some(21), Some(20)).get_cross_status(), CrossStatus::Crossed);
+    /// assert_eq!(BidOffer::new_with_price(Some(21), None).get_cross_status(), CrossStatus::Normal);
+    /// ```
+    pub fn get_cross_status(&self) -> CrossStatus {
+        match (self.bid, self.offer) {
+            (Some(bid), Some(offer)) if bid > offer => CrossStatus::Crossed,
+            (Some(bid), Some(offer)) if bid == offer => CrossStatus::Locked,
+            _ => CrossStatus::Normal,
+        }
+    }
+
+    /// Returns true if the bid is above the offer
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::BidOffer;
+    ///
+    /// assert!(BidOffer::new_with_price(Some(21), Some(20)).is_crossed());
+    /// assert!(!BidOffer::new_with_price(Some(20), Some(20)).is_crossed());
+    /// ```
+    pub fn is_crossed(&self) -> bool {
+        self.get_cross_status() == CrossStatus::Crossed
+    }
+
+    /// Returns true if the bid is equal to the offer
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::BidOffer;
+    ///
+    /// assert!(BidOffer::new_with_price(Some(20), Some(20)).is_locked());
+    /// assert!(!BidOffer::new_with_price(Some(21), Some(20)).is_locked());
+    /// ```
+    pub fn is_locked(&self) -> bool {
+        self.get_cross_status() == CrossStatus::Locked
+    }
+
+    /// Returns true if there is both a bid and an offer
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::BidOffer;
+    ///
+    /// assert!(BidOffer::new_with_price(Some(10), Some(20)).is_two_sided());
+    /// assert!(!BidOffer::new_with_price(Some(10), None).is_two_sided());
+    /// ```
+    pub fn is_two_sided(&self) -> bool {
+        self.bid.is_some() && self.offer.is_some()
+    }
+
+    /// Get the price on a side
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the market
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, MarketSide};
+    ///
+    /// let bid_offer = BidOffer::new_with_price(Some(10), None);
+    ///
+    /// assert_eq!(*bid_offer.get(MarketSide::Bid), Some(10));
+    /// assert_eq!(*bid_offer.get(MarketSide::Offer), None);
+    /// ```
+    pub fn get(&self, side: MarketSide) -> &Option<P> {
+        match side {
+            MarketSide::Bid => &self.bid,
+            MarketSide::Offer => &self.offer,
+        }
+    }
+
+    /// Apply a function to the price on each side
+    ///
+    /// # Parameters
+    ///
+    /// * `f` - The function to apply
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::BidOffer;
+    ///
+    /// let bid_offer = BidOffer::new_with_price(Some(10), None);
+    ///
+    /// assert_eq!(bid_offer.map(|price| f64::from(price) / 4.0), BidOffer::new_with_price(Some(2.5), None));
+    /// ```
+    pub fn map<Q, F>(&self, f: F) -> BidOffer<Q>
+    where
+        Q: Copy + PartialOrd + Add<Output = Q> + Div<Output = Q> + From<u8>,
+        F: Fn(P) -> Q,
+    {
+        BidOffer::new_with_price(self.bid.map(&f), self.offer.map(&f))
+    }
+
+    /// Combine the prices on each side with those of another price, giving no price on a side unless both have one
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The price to combine with
+    /// * `f` - The function to combine the prices on a side
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::BidOffer;
+    ///
+    /// let spot = BidOffer::new_with_price(Some(100), Some(102));
+    /// let points = BidOffer::new_with_price(Some(5), None);
+    ///
+    /// assert_eq!(spot.zip_with(&points, |spot, points| spot + points), BidOffer::new_with_price(Some(105), None));
+    /// ```
+    pub fn zip_with<Q, R, F>(&self, other: &BidOffer<Q>, f: F) -> BidOffer<R>
+    where
+        Q: Copy + PartialOrd + Add<Output = Q> + Div<Output = Q> + From<u8>,
+        R: Copy + PartialOrd + Add<Output = R> + Div<Output = R> + From<u8>,
+        F: Fn(P, Q) -> R,
+    {
+        BidOffer::new_with_price(
+            self.bid
+                .zip(other.bid)
+                .map(|(price, other)| f(price, other)),
+            self.offer
+                .zip(other.offer)
+                .map(|(price, other)| f(price, other)),
+        )
+    }
+
+    /// Get the offer less the bid, which is None unless there are both and negative if the price is crossed.  Only
+    /// available for signed or float prices, as an unsigned price cannot hold the spread of a crossed price.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::BidOffer;
+    ///
+    /// assert_eq!(BidOffer::new_with_price(Some(10), Some(12)).spread(), Some(2));
+    /// assert_eq!(BidOffer::new_with_price(Some(12), Some(10)).spread(), Some(-2));
+    /// assert_eq!(BidOffer::new_with_price(Some(10), None).spread(), None);
+    /// ```
+    pub fn spread(&self) -> Option<P>
+    where
+        P: Sub<Output = P> + Neg<Output = P>,
+    {
+        Some(self.offer? - self.bid?)
+    }
+
+    /// Move the bid down and the offer up by an amount.  Only available for signed or float prices, as the bid may be
+    /// moved below zero.
+    ///
+    /// # Parameters
+    ///
+    /// * `by` - The amount to move each side away from the mid
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::BidOffer;
+    ///
+    /// assert_eq!(BidOffer::new_with_price(Some(10), Some(12)).widen(1), BidOffer::new_with_price(Some(9), Some(13)));
+    /// assert_eq!(BidOffer::new_with_price(Some(1), Some(8)).widen(2), BidOffer::new_with_price(Some(-1), Some(10)));
+    /// ```
+    pub fn widen(&self, by: P) -> Self
+    where
+        P: Sub<Output = P> + Neg<Output = P>,
+    {
+        Self::new_with_price(
+            self.bid.map(|bid| bid - by),
+            self.offer.map(|offer| offer + by),
+        )
+    }
+
+    /// Move both sides by an amount, keeping the spread
+    ///
+    /// # Parameters
+    ///
+    /// * `by` - The amount to add to each side
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::BidOffer;
+    ///
+    /// assert_eq!(BidOffer::new_with_price(Some(10), Some(12)).shift(-3), BidOffer::new_with_price(Some(7), Some(9)));
+    /// ```
+    pub fn shift(&self, by: P) -> Self {
+        Self::new_with_price(
+            self.bid.map(|bid| bid + by),
+            self.offer.map(|offer| offer + by),
+        )
+    }
+
+    /// Move both sides by a fraction of the spread, keeping the spread.  A positive skew moves the price up, so the bid is
+    /// more likely to be hit and the offer less likely to be lifted.  The price is unchanged unless there are both sides.
+    /// Only available for signed or float prices, as a negative skew or crossed spread moves the price down.
+    ///
+    /// # Parameters
+    ///
+    /// * `by` - The fraction of the spread to move by, with an integer price rounded to the nearest whole amount
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::BidOffer;
+    ///
+    /// let bid_offer = BidOffer::new_with_price(Some(1.10_f64), Some(1.12));
+    ///
+    /// let skewed = bid_offer.skew(-0.5);
+    ///
+    /// assert!((skewed.get_bid().unwrap() - 1.09).abs() < 1e-12);
+    /// assert!((skewed.get_offer().unwrap() - 1.11).abs() < 1e-12);
+    /// ```
+    pub fn skew(&self, by: f64) -> Self
+    where
+        P: Sub<Output = P> + Neg<Output = P> + FloatConvert,
+    {
+        match self.spread() {
+            Some(spread) => self.shift(P::from_f64(spread.to_f64() * by)),
+            None => *self,
+        }
+    }
+
+    /// Get the price of the inverse quote, for example USD/EUR from EUR/USD, where each side is one over the opposite side.
+    /// This is only meaningful for prices which can hold fractions, and a side with a price of zero has no inverse so the
+    /// opposite side is None.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::BidOffer;
+    ///
+    /// let bid_offer = BidOffer::new_with_price(Some(1.25), Some(2.0));
+    ///
+    /// assert_eq!(bid_offer.invert(), BidOffer::new_with_price(Some(0.5), Some(0.8)));
+    /// assert_eq!(BidOffer::new_with_price(Some(0.0), Some(2.0)).invert(), BidOffer::new_with_price(Some(0.5), None));
+    /// ```
+    pub fn invert(&self) -> Self {
+        let zero: P = 0.into();
+        let one: P = 1.into();
+        let inverse = |price: P| (price != zero).then(|| one / price);
+
+        Self::new_with_price(self.offer.and_then(inverse), self.bid.and_then(inverse))
+    }
 }
 
 impl<P> Default for BidOffer<P>
 where
-    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<i32>,
+    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<u8>,
 {
     fn default() -> Self {
         Self::new()
@@ -137,7 +395,7 @@ mod tests {
 
         assert_eq!(*bid_offer.get_bid(), Some(1.2));
         assert_eq!(*bid_offer.get_offer(), Some(2.4));
-        assert!(((1.2 + 2.4) / 2.0 - bid_offer.get_mid().unwrap()).abs() <= f64::EPSILON);
+        assert!(((1.2_f64 + 2.4) / 2.0 - bid_offer.get_mid().unwrap()).abs() <= f64::EPSILON);
     }
 
     #[test]
@@ -160,4 +418,56 @@ mod tests {
         let bid_offer: BidOffer<i32> = BidOffer::new_with_price(None, None);
         assert_eq!(bid_offer.get_mid(), None);
     }
+
+    #[test]
+    fn combinators() {
+        let bid_offer = BidOffer::new_with_price(Some(10), Some(14));
+
+        assert!(bid_offer.is_two_sided());
+        assert!(!BidOffer::new_with_price(None, Some(14)).is_two_sided());
+        assert_eq!(
+            bid_offer.widen(2).shift(1),
+            BidOffer::new_with_price(Some(9), Some(17))
+        );
+        assert_eq!(
+            bid_offer.skew(2.0),
+            BidOffer::new_with_price(Some(18), Some(22))
+        );
+        assert_eq!(
+            bid_offer.skew(-0.3),
+            BidOffer::new_with_price(Some(9), Some(13))
+        );
+        // A crossed price skews by a negative spread, and widening may take the bid below zero
+        let crossed = BidOffer::new_with_price(Some(14), Some(10));
+        assert_eq!(crossed.spread(), Some(-4));
+        assert_eq!(
+            crossed.skew(0.5),
+            BidOffer::new_with_price(Some(12), Some(8))
+        );
+        assert_eq!(
+            BidOffer::new_with_price(Some(1), Some(8)).widen(2),
+            BidOffer::new_with_price(Some(-1), Some(10))
+        );
+        assert_eq!(
+            BidOffer::new_with_price(Some(10), None).skew(2.0),
+            BidOffer::new_with_price(Some(10), None)
+        );
+        assert_eq!(
+            bid_offer.zip_with(&BidOffer::new_with_price(None, Some(2)), |price, by| price
+                * by),
+            BidOffer::new_with_price(None, Some(28))
+        );
+        assert_eq!(
+            BidOffer::new_with_price(Some(4.0), None).invert(),
+            BidOffer::new_with_price(None, Some(0.25))
+        );
+        assert_eq!(
+            BidOffer::new_with_price(Some(0), Some(0)).invert(),
+            BidOffer::new()
+        );
+        assert_eq!(
+            BidOffer::new_with_price(Some(0.0), Some(4.0)).invert(),
+            BidOffer::new_with_price(Some(0.25), None)
+        );
+    }
 }
@@ -0,0 +1,399 @@
+use super::{vwap, BidOffer, MarketSide, UpdateAction, VwapAmount};
+use std::{
+    collections::BTreeMap,
+    ops::{Add, Div, Sub},
+};
+
+/// A structure to hold L2 pricing for the same instrument from multiple venues, consolidated into a single sweepable book.
+/// The size at each price is kept per venue so that the venues which make up a price can be reported.
+///
+/// # Generic Parameters
+///
+/// * `V` - The venue type that should be used.
+/// * `P` - The price type that should be used.
+/// * `A` - The amount type that should be used.
+pub struct ConsolidatedMarketData<V, P, A>
+where
+    V: Ord + Clone,
+    P: Copy + Ord + Add<Output = P> + Div<Output = P> + From<u8>,
+    A: Copy + PartialOrd + Add<Output = A> + Sub<Output = A> + Default + VwapAmount<P>,
+{
+    bids: BTreeMap<P, BTreeMap<V, A>>,
+    offers: BTreeMap<P, BTreeMap<V, A>>,
+}
+
+impl<V, P, A> ConsolidatedMarketData<V, P, A>
+where
+    V: Ord + Clone,
+    P: Copy + Ord + Add<Output = P> + Div<Output = P> + From<u8>,
+    A: Copy + PartialOrd + Add<Output = A> + Sub<Output = A> + Default + VwapAmount<P>,
+{
+    /// Use the new function to create a new ConsolidatedMarketData with no pricing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, ConsolidatedMarketData};
+    ///
+    /// let market_data = ConsolidatedMarketData::<&str, i32, i32>::new();
+    ///
+    /// assert_eq!(market_data.get_price(1), BidOffer::new());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            bids: BTreeMap::new(),
+            offers: BTreeMap::new(),
+        }
+    }
+
+    /// Update the size a venue has at a price.  As with the L2 books an Update for a venue which has no size at the price
+    /// is ignored, and a ClearSide or Snapshot only clears the levels of the venue.
+    ///
+    /// # Parameters
+    ///
+    /// * `venue` - The venue the update is from
+    /// * `action` - The update action
+    /// * `side` - The side of the market
+    /// * `price` - The price of the level
+    /// * `size` - The size the venue has at the level
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, ConsolidatedMarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = ConsolidatedMarketData::<&str, i32, i32>::new();
+    ///
+    /// market_data.update("XLON", UpdateAction::Add, MarketSide::Bid, 10, 50);
+    /// market_data.update("BATE", UpdateAction::Add, MarketSide::Bid, 10, 30);
+    ///
+    /// assert_eq!(market_data.get_price(80), BidOffer::new_with_price(Some(10), None));
+    /// ```
+    pub fn update(&mut self, venue: V, action: UpdateAction, side: MarketSide, price: P, size: A) {
+        // A snapshot from a venue replaces everything it had on both sides
+        if action == UpdateAction::Snapshot {
+            self.remove_venue(&venue);
+        }
+
+        let side_store = match side {
+            MarketSide::Bid => &mut self.bids,
+            MarketSide::Offer => &mut self.offers,
+        };
+
+        match action {
+            UpdateAction::Add | UpdateAction::Replace | UpdateAction::Snapshot => {
+                side_store.entry(price).or_default().insert(venue, size);
+            }
+            UpdateAction::Update => {
+                if let Some(value) = side_store
+                    .get_mut(&price)
+                    .and_then(|level| level.get_mut(&venue))
+                {
+                    *value = size;
+                }
+            }
+            UpdateAction::Remove => {
+                if let Some(level) = side_store.get_mut(&price) {
+                    level.remove(&venue);
+
+                    if level.is_empty() {
+                        side_store.remove(&price);
+                    }
+                }
+            }
+            UpdateAction::ClearSide => Self::remove_venue_from_side(side_store, &venue),
+        }
+    }
+
+    /// Replace all the levels a venue has on one side of the book, typically from the `get_levels` of an
+    /// `L2SweepableMarketData` or `L3MarketData` holding the book for the venue.
+    ///
+    /// # Parameters
+    ///
+    /// * `venue` - The venue the levels are from
+    /// * `side` - The side of the market
+    /// * `levels` - The (price, size) levels the venue now has
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{
+    ///     BidOffer, ConsolidatedMarketData, L2SweepableMarketData, L3MarketData, MarketSide, UpdateAction,
+    /// };
+    ///
+    /// let mut xlon = L2SweepableMarketData::<i32, i32>::new();
+    /// xlon.update(UpdateAction::Add, MarketSide::Offer, 15, 50).unwrap();
+    ///
+    /// let mut bate = L3MarketData::<u64, i32, i32>::new();
+    /// bate.update(UpdateAction::Add, MarketSide::Offer, 1, 14, 30).unwrap();
+    ///
+    /// let mut market_data = ConsolidatedMarketData::new();
+    /// market_data.replace_venue_levels("XLON", MarketSide::Offer, xlon.get_levels(MarketSide::Offer));
+    /// market_data.replace_venue_levels("BATE", MarketSide::Offer, bate.get_levels(MarketSide::Offer));
+    ///
+    /// assert_eq!(market_data.get_price(30), BidOffer::new_with_price(None, Some(14)));
+    /// assert_eq!(market_data.get_sweep_venues(MarketSide::Offer, 60), Some(vec![("BATE", 30), ("XLON", 30)]));
+    /// ```
+    pub fn replace_venue_levels<L>(&mut self, venue: V, side: MarketSide, levels: L)
+    where
+        L: IntoIterator<Item = (P, A)>,
+    {
+        let side_store = match side {
+            MarketSide::Bid => &mut self.bids,
+            MarketSide::Offer => &mut self.offers,
+        };
+
+        Self::remove_venue_from_side(side_store, &venue);
+
+        for (price, size) in levels {
+            side_store
+                .entry(price)
+                .or_default()
+                .insert(venue.clone(), size);
+        }
+    }
+
+    /// Remove all the pricing from a venue, for example if the connection to it is lost
+    pub fn remove_venue(&mut self, venue: &V) {
+        Self::remove_venue_from_side(&mut self.bids, venue);
+        Self::remove_venue_from_side(&mut self.offers, venue);
+    }
+
+    fn remove_venue_from_side(side_store: &mut BTreeMap<P, BTreeMap<V, A>>, venue: &V) {
+        side_store.retain(|_, level| {
+            level.remove(venue);
+            !level.is_empty()
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.bids.clear();
+        self.offers.clear();
+    }
+
+    /// Get the consolidated price levels on a side of the book, best price first, as (price, total size across venues)
+    pub fn get_levels(&self, side: MarketSide) -> Box<dyn Iterator<Item = (P, A)> + '_> {
+        let total = |(&price, level): (&P, &BTreeMap<V, A>)| {
+            (
+                price,
+                level
+                    .values()
+                    .fold(A::default(), |total, &size| total + size),
+            )
+        };
+
+        match side {
+            MarketSide::Bid => Box::new(self.bids.iter().rev().map(total)),
+            MarketSide::Offer => Box::new(self.offers.iter().map(total)),
+        }
+    }
+
+    /// Get the size each venue has at a price
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the market
+    /// * `price` - The price of the level
+    pub fn get_level_venues(&self, side: MarketSide, price: P) -> Vec<(V, A)> {
+        let side_store = match side {
+            MarketSide::Bid => &self.bids,
+            MarketSide::Offer => &self.offers,
+        };
+
+        side_store
+            .get(&price)
+            .map(|level| {
+                level
+                    .iter()
+                    .map(|(venue, &size)| (venue.clone(), size))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Get the price for a size by sweeping the consolidated levels of all the venues on each side, so the size can be
+    /// filled from more than one venue.  A side which cannot fill the size across all the venues has no price.
+    ///
+    /// # Parameters
+    ///
+    /// * `size` - The size to price
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, ConsolidatedMarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = ConsolidatedMarketData::<&str, i32, i32>::new();
+    ///
+    /// market_data.update("XLON", UpdateAction::Add, MarketSide::Offer, 10, 50);
+    /// market_data.update("BATE", UpdateAction::Add, MarketSide::Offer, 13, 100);
+    ///
+    /// assert_eq!(market_data.get_price(150), BidOffer::new_with_price(None, Some(12)));
+    /// assert_eq!(market_data.get_price(200), BidOffer::new());
+    /// ```
+    pub fn get_price(&self, size: A) -> BidOffer<P> {
+        BidOffer::new_with_price(
+            vwap::sweep(size, self.get_levels(MarketSide::Bid)),
+            vwap::sweep(size, self.get_levels(MarketSide::Offer)),
+        )
+    }
+
+    /// Get the size each venue contributes to a sweep of the book for a size, or None if there is not enough liquidity.
+    /// Where a level is only partly needed the venues at the level are filled in venue order.
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the market
+    /// * `size` - The size of the sweep
+    pub fn get_sweep_venues(&self, side: MarketSide, size: A) -> Option<Vec<(V, A)>> {
+        let side_store = match side {
+            MarketSide::Bid => &self.bids,
+            MarketSide::Offer => &self.offers,
+        };
+        let levels: Box<dyn Iterator<Item = &BTreeMap<V, A>>> = match side {
+            MarketSide::Bid => Box::new(side_store.values().rev()),
+            MarketSide::Offer => Box::new(side_store.values()),
+        };
+
+        let mut current_size = A::default();
+        let mut venues: BTreeMap<V, A> = BTreeMap::new();
+
+        for level in levels {
+            for (venue, &next_size) in level {
+                let mut incremental_size = next_size;
+
+                if next_size > size - current_size {
+                    incremental_size = size - current_size;
+                }
+
+                let venue_size = venues.entry(venue.clone()).or_default();
+                *venue_size = *venue_size + incremental_size;
+                current_size = current_size + incremental_size;
+
+                if current_size >= size {
+                    return Some(venues.into_iter().collect());
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<V, P, A> Default for ConsolidatedMarketData<V, P, A>
+where
+    V: Ord + Clone,
+    P: Copy + Ord + Add<Output = P> + Div<Output = P> + From<u8>,
+    A: Copy + PartialOrd + Add<Output = A> + Sub<Output = A> + Default + VwapAmount<P>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default() {
+        let test = ConsolidatedMarketData::<u8, i32, i32>::default();
+
+        assert_eq!(test.get_price(1), BidOffer::default());
+        assert_eq!(test.get_sweep_venues(MarketSide::Bid, 1), None);
+    }
+
+    #[test]
+    fn consolidated_vwap() {
+        let mut test = ConsolidatedMarketData::new();
+
+        test.update(1, UpdateAction::Add, MarketSide::Bid, 12, 10);
+        test.update(2, UpdateAction::Add, MarketSide::Bid, 12, 10);
+        test.update(2, UpdateAction::Add, MarketSide::Bid, 8, 20);
+        test.update(1, UpdateAction::Add, MarketSide::Offer, 16, 10);
+        test.update(2, UpdateAction::Add, MarketSide::Offer, 20, 10);
+
+        assert_eq!(
+            test.get_price(20),
+            BidOffer::new_with_price(Some(12), Some(18))
+        );
+        assert_eq!(test.get_price(40), BidOffer::new_with_price(Some(10), None));
+        assert_eq!(
+            test.get_level_venues(MarketSide::Bid, 12),
+            vec![(1, 10), (2, 10)]
+        );
+        assert_eq!(
+            test.get_levels(MarketSide::Bid).collect::<Vec<_>>(),
+            vec![(12, 20), (8, 20)]
+        );
+    }
+
+    #[test]
+    fn sweep_venues() {
+        let mut test = ConsolidatedMarketData::new();
+
+        test.update(1, UpdateAction::Add, MarketSide::Bid, 12, 10);
+        test.update(2, UpdateAction::Add, MarketSide::Bid, 12, 10);
+        test.update(3, UpdateAction::Add, MarketSide::Bid, 11, 20);
+
+        assert_eq!(
+            test.get_sweep_venues(MarketSide::Bid, 5),
+            Some(vec![(1, 5)])
+        );
+        assert_eq!(
+            test.get_sweep_venues(MarketSide::Bid, 25),
+            Some(vec![(1, 10), (2, 10), (3, 5)])
+        );
+        assert_eq!(test.get_sweep_venues(MarketSide::Bid, 41), None);
+    }
+
+    #[test]
+    fn modify_remove_venue() {
+        let mut test = ConsolidatedMarketData::new();
+
+        test.update(1, UpdateAction::Add, MarketSide::Offer, 15, 10);
+        test.update(2, UpdateAction::Add, MarketSide::Offer, 15, 10);
+        test.update(2, UpdateAction::Add, MarketSide::Offer, 16, 10);
+
+        test.update(1, UpdateAction::Update, MarketSide::Offer, 15, 20);
+        test.update(1, UpdateAction::Update, MarketSide::Offer, 16, 20);
+        assert_eq!(test.get_level_venues(MarketSide::Offer, 16), vec![(2, 10)]);
+
+        test.update(2, UpdateAction::Remove, MarketSide::Offer, 15, 0);
+        assert_eq!(test.get_level_venues(MarketSide::Offer, 15), vec![(1, 20)]);
+
+        test.remove_venue(&1);
+        assert_eq!(
+            test.get_levels(MarketSide::Offer).collect::<Vec<_>>(),
+            vec![(16, 10)]
+        );
+
+        test.replace_venue_levels(2, MarketSide::Offer, vec![(17, 5)]);
+        assert_eq!(
+            test.get_levels(MarketSide::Offer).collect::<Vec<_>>(),
+            vec![(17, 5)]
+        );
+
+        test.clear();
+        assert_eq!(test.get_price(1), BidOffer::default());
+    }
+
+    #[test]
+    fn venue_clear_and_snapshot() {
+        let mut test = ConsolidatedMarketData::new();
+
+        test.update(1, UpdateAction::Add, MarketSide::Bid, 10, 10);
+        test.update(1, UpdateAction::Add, MarketSide::Offer, 15, 10);
+        test.update(2, UpdateAction::Add, MarketSide::Offer, 15, 10);
+
+        test.update(2, UpdateAction::ClearSide, MarketSide::Offer, 0, 0);
+        assert_eq!(test.get_level_venues(MarketSide::Offer, 15), vec![(1, 10)]);
+
+        test.update(1, UpdateAction::Snapshot, MarketSide::Offer, 14, 20);
+        assert_eq!(test.get_levels(MarketSide::Bid).count(), 0);
+        assert_eq!(
+            test.get_levels(MarketSide::Offer).collect::<Vec<_>>(),
+            vec![(14, 20)]
+        );
+    }
+}
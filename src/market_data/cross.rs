@@ -0,0 +1,24 @@
+/// Whether the best bid and offer of a book overlap
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub enum CrossStatus {
+    /// The best bid is below the best offer, or one of the sides has no price
+    #[default]
+    Normal,
+    /// The best bid is equal to the best offer
+    Locked,
+    /// The best bid is above the best offer
+    Crossed,
+}
+
+/// What a book does with an update which would leave it crossed.  Locked books are flagged but always allowed.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum CrossPolicy {
+    /// Reject the update with an error, leaving the book as it was
+    Reject,
+    /// Treat the opposite side to the update as stale and remove the prices it crosses.  When both sides are updated
+    /// together there is no stale side, so the book is kept and flagged.
+    RemoveStale,
+    /// Apply the update and flag the book as crossed
+    #[default]
+    KeepAndFlag,
+}
@@ -0,0 +1,79 @@
+use std::{error::Error, fmt};
+
+/// The reasons an update to the market data can be rejected
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MarketDataError {
+    /// The price is not a multiple of the tick size
+    OffTickPrice,
+    /// The size is not a multiple of the lot size
+    OffLotSize,
+    /// The size is below the minimum size for the instrument
+    BelowMinimumSize,
+    /// The order id is not in the book
+    UnknownOrder,
+    /// The instrument is not in the registry
+    UnknownInstrument,
+    /// The update cannot be applied to this kind of book
+    UnsupportedUpdate,
+    /// The update would leave the best bid above the best offer
+    CrossedBook,
+    /// The level being added is already in the book
+    LevelExists,
+    /// The level being updated or removed is not in the book
+    UnknownLevel,
+    /// The size is zero or negative
+    InvalidSize,
+    /// The market cannot move from its current status to the one given
+    InvalidTransition,
+}
+
+impl fmt::Display for MarketDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarketDataError::OffTickPrice => write!(f, "price is not a multiple of the tick size"),
+            MarketDataError::OffLotSize => write!(f, "size is not a multiple of the lot size"),
+            MarketDataError::BelowMinimumSize => write!(f, "size is below the minimum size"),
+            MarketDataError::UnknownOrder => write!(f, "order is not in the book"),
+            MarketDataError::UnknownInstrument => write!(f, "instrument is not in the registry"),
+            MarketDataError::UnsupportedUpdate => {
+                write!(f, "update is not supported by this kind of book")
+            }
+            MarketDataError::CrossedBook => write!(f, "update would cross the book"),
+            MarketDataError::LevelExists => write!(f, "level is already in the book"),
+            MarketDataError::UnknownLevel => write!(f, "level is not in the book"),
+            MarketDataError::InvalidSize => write!(f, "size is zero or negative"),
+            MarketDataError::InvalidTransition => {
+                write!(
+                    f,
+                    "market cannot move to the status from its current status"
+                )
+            }
+        }
+    }
+}
+
+impl Error for MarketDataError {}
+
+/// A value which could not be parsed from text
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseError {
+    kind: &'static str,
+    value: String,
+}
+
+impl ParseError {
+    pub(crate) fn new(kind: &'static str, value: &str) -> Self {
+        Self {
+            kind,
+            value: value.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' is not a valid {}", self.value, self.kind)
+    }
+}
+
+impl Error for ParseError {}
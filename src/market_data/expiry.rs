@@ -0,0 +1,226 @@
+use super::MarketSide;
+use std::{
+    cell::Cell,
+    rc::Rc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The source of the current time used to timestamp updates, so that it can be replaced when testing.  Times are a
+/// duration since an epoch chosen by the clock, and only need to be consistent with the times passed to the books.
+pub trait Clock {
+    fn now(&self) -> Duration;
+}
+
+/// A clock reading the system time as the duration since the Unix epoch
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+    }
+}
+
+/// A clock which only moves when it is told to, for tests and replaying historic data
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use pricing::market_data::{Clock, ManualClock};
+///
+/// let clock = ManualClock::new(Duration::from_secs(10));
+///
+/// clock.advance(Duration::from_secs(5));
+///
+/// assert_eq!(clock.now(), Duration::from_secs(15));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ManualClock {
+    now: Cell<Duration>,
+}
+
+impl ManualClock {
+    pub fn new(now: Duration) -> Self {
+        Self {
+            now: Cell::new(now),
+        }
+    }
+
+    /// Set the current time
+    pub fn set(&self, now: Duration) {
+        self.now.set(now);
+    }
+
+    /// Move the current time forward
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+/// The timestamps, time to live and staleness of the two sides of a book
+pub(crate) struct QuoteExpiry {
+    clock: Rc<dyn Clock>,
+    bid_ttl: Option<Duration>,
+    offer_ttl: Option<Duration>,
+    bid_updated: Option<Duration>,
+    offer_updated: Option<Duration>,
+    bid_stale: bool,
+    offer_stale: bool,
+}
+
+impl QuoteExpiry {
+    pub(crate) fn new() -> Self {
+        Self {
+            clock: Rc::new(SystemClock),
+            bid_ttl: None,
+            offer_ttl: None,
+            bid_updated: None,
+            offer_updated: None,
+            bid_stale: false,
+            offer_stale: false,
+        }
+    }
+
+    pub(crate) fn set_clock(&mut self, clock: Rc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    pub(crate) fn set_time_to_live(&mut self, side: MarketSide, ttl: Option<Duration>) {
+        match side {
+            MarketSide::Bid => self.bid_ttl = ttl,
+            MarketSide::Offer => self.offer_ttl = ttl,
+        }
+    }
+
+    pub(crate) fn get_time_to_live(&self, side: MarketSide) -> Option<Duration> {
+        match side {
+            MarketSide::Bid => self.bid_ttl,
+            MarketSide::Offer => self.offer_ttl,
+        }
+    }
+
+    pub(crate) fn get_updated(&self, side: MarketSide) -> Option<Duration> {
+        match side {
+            MarketSide::Bid => self.bid_updated,
+            MarketSide::Offer => self.offer_updated,
+        }
+    }
+
+    /// Get the time now from the clock
+    pub(crate) fn now(&self) -> Duration {
+        self.clock.now()
+    }
+
+    /// Clears the stale flag of a side if none of its quotes have expired
+    pub(crate) fn refresh_stale(&mut self, side: MarketSide, expired: bool) {
+        if !expired {
+            match side {
+                MarketSide::Bid => self.bid_stale = false,
+                MarketSide::Offer => self.offer_stale = false,
+            }
+        }
+    }
+
+    /// Record that a side has been updated now, returning the time recorded
+    pub(crate) fn touch(&mut self, side: MarketSide) -> Duration {
+        let now = self.clock.now();
+
+        match side {
+            MarketSide::Bid => {
+                self.bid_updated = Some(now);
+                self.bid_stale = false;
+            }
+            MarketSide::Offer => {
+                self.offer_updated = Some(now);
+                self.offer_stale = false;
+            }
+        }
+
+        now
+    }
+
+    /// Returns true if a quote on the side updated at the time has expired by now
+    pub(crate) fn has_expired(&self, side: MarketSide, updated: Duration, now: Duration) -> bool {
+        self.get_time_to_live(side)
+            .is_some_and(|ttl| updated.checked_add(ttl).is_some_and(|expiry| now > expiry))
+    }
+
+    /// Returns true if the side as a whole has expired by now
+    pub(crate) fn is_expired(&self, side: MarketSide, now: Duration) -> bool {
+        self.get_updated(side)
+            .is_some_and(|updated| self.has_expired(side, updated, now))
+    }
+
+    /// Returns true if the side has been flagged as stale since it was last updated
+    pub(crate) fn is_stale(&self, side: MarketSide) -> bool {
+        match side {
+            MarketSide::Bid => self.bid_stale,
+            MarketSide::Offer => self.offer_stale,
+        }
+    }
+
+    /// Flag the side as stale, returning true if it was not already
+    pub(crate) fn mark_stale(&mut self, side: MarketSide) -> bool {
+        let stale = match side {
+            MarketSide::Bid => &mut self.bid_stale,
+            MarketSide::Offer => &mut self.offer_stale,
+        };
+
+        !std::mem::replace(stale, true)
+    }
+
+    /// Flags the sides which have expired as stale, returning those which were not already
+    pub(crate) fn check_stale(&mut self, now: Duration) -> Vec<MarketSide> {
+        [MarketSide::Bid, MarketSide::Offer]
+            .into_iter()
+            .filter(|&side| self.is_expired(side, now) && self.mark_stale(side))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expiry() {
+        let clock = Rc::new(ManualClock::new(Duration::from_secs(100)));
+        let mut test = QuoteExpiry::new();
+
+        test.set_clock(clock.clone());
+        test.set_time_to_live(MarketSide::Bid, Some(Duration::from_secs(5)));
+        test.touch(MarketSide::Bid);
+        test.touch(MarketSide::Offer);
+
+        assert!(!test.is_expired(MarketSide::Bid, Duration::from_secs(105)));
+        assert!(test.is_expired(MarketSide::Bid, Duration::from_secs(106)));
+        assert!(!test.is_expired(MarketSide::Offer, Duration::from_secs(1000)));
+
+        assert_eq!(
+            test.check_stale(Duration::from_secs(106)),
+            vec![MarketSide::Bid]
+        );
+        assert_eq!(test.check_stale(Duration::from_secs(107)), vec![]);
+
+        clock.advance(Duration::from_secs(10));
+        test.touch(MarketSide::Bid);
+        assert_eq!(
+            test.get_updated(MarketSide::Bid),
+            Some(Duration::from_secs(110))
+        );
+        assert_eq!(test.check_stale(Duration::from_secs(110)), vec![]);
+
+        // A time to live too long to add to the update time never expires
+        test.set_time_to_live(MarketSide::Offer, Some(Duration::MAX));
+        assert!(!test.is_expired(MarketSide::Offer, Duration::MAX));
+    }
+}
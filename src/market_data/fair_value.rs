@@ -0,0 +1,100 @@
+use super::FloatConvert;
+
+/// What a fair value measure of a book gives when only one side has a price
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum OneSidedPolicy {
+    /// There is no price
+    None,
+    /// The price of the side which has one, as with `BidOffer::get_mid`
+    #[default]
+    AvailableSide,
+    /// The last mid of the book when it had both sides, if it has had them
+    LastMid,
+}
+
+impl OneSidedPolicy {
+    /// Gives the measure of the (price, size) of both sides if there are both, otherwise applies the policy
+    pub(crate) fn resolve<P, A, F>(
+        self,
+        bid: Option<(P, A)>,
+        offer: Option<(P, A)>,
+        last_mid: Option<P>,
+        two_sided: F,
+    ) -> Option<P>
+    where
+        F: FnOnce((P, A), (P, A)) -> P,
+    {
+        match (bid, offer) {
+            (Some(bid), Some(offer)) => Some(two_sided(bid, offer)),
+            (bid, offer) => match self {
+                OneSidedPolicy::None => None,
+                OneSidedPolicy::AvailableSide => bid.or(offer).map(|(price, _)| price),
+                OneSidedPolicy::LastMid => last_mid,
+            },
+        }
+    }
+}
+
+/// The average of the bid and offer weighted by the size on each, which leans towards the side with more size
+pub(crate) fn weighted_mid<P, A>((bid, bid_size): (P, A), (offer, offer_size): (P, A)) -> P
+where
+    P: FloatConvert,
+    A: FloatConvert,
+{
+    weighted(bid, bid_size.to_f64(), offer, offer_size.to_f64())
+}
+
+/// The average of the bid and offer weighted by the size on the opposite side, which leans towards the side with less
+/// size as that is the side the price is more likely to move to
+pub(crate) fn microprice<P, A>((bid, bid_size): (P, A), (offer, offer_size): (P, A)) -> P
+where
+    P: FloatConvert,
+    A: FloatConvert,
+{
+    weighted(bid, offer_size.to_f64(), offer, bid_size.to_f64())
+}
+
+// Falls back to the plain mid if there is no weight on either side
+fn weighted<P: FloatConvert>(bid: P, bid_weight: f64, offer: P, offer_weight: f64) -> P {
+    let total = bid_weight + offer_weight;
+
+    if total > 0.0 {
+        P::from_f64((bid.to_f64() * bid_weight + offer.to_f64() * offer_weight) / total)
+    } else {
+        P::from_f64((bid.to_f64() + offer.to_f64()) / 2.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weightings() {
+        assert_eq!(weighted_mid((10.0, 30.0), (11.0, 10.0)), 10.25);
+        assert_eq!(microprice((10.0, 30.0), (11.0, 10.0)), 10.75);
+        assert_eq!(microprice((10, 0), (12, 0)), 11);
+    }
+
+    #[test]
+    fn one_sided() {
+        let mid = |(bid, _): (i32, i32), (offer, _): (i32, i32)| (bid + offer) / 2;
+
+        assert_eq!(
+            OneSidedPolicy::None.resolve(Some((10, 5)), Some((12, 5)), None, mid),
+            Some(11)
+        );
+        assert_eq!(
+            OneSidedPolicy::None.resolve(Some((10, 5)), None, Some(11), mid),
+            None
+        );
+        assert_eq!(
+            OneSidedPolicy::AvailableSide.resolve(None, Some((12, 5)), Some(11), mid),
+            Some(12)
+        );
+        assert_eq!(
+            OneSidedPolicy::LastMid.resolve(None, Some((12, 5)), Some(11), mid),
+            Some(11)
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use super::{metrics, FloatConvert, MarketSide};
+
+/// The estimated cost of sweeping a size from one side of a book, compared with the best price on the side and with the
+/// mid.  Slippage is positive when the average price is worse than the price it is compared with, and can be negative if
+/// the book is crossed, so it is given as an `f64` which can hold a negative slippage for unsigned prices.
+///
+/// # Generic Parameters
+///
+/// * `P` - The price type that should be used.
+///
+/// # Example
+///
+/// ```
+/// use pricing::market_data::{L2SweepableMarketData, MarketSide, UpdateAction};
+///
+/// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+///
+/// market_data.update(UpdateAction::Add, MarketSide::Bid, 98, 50).unwrap();
+/// market_data.update(UpdateAction::Add, MarketSide::Offer, 102, 50).unwrap();
+/// market_data.update(UpdateAction::Add, MarketSide::Offer, 106, 50).unwrap();
+///
+/// let impact = market_data.estimate_impact(MarketSide::Offer, 100).unwrap();
+///
+/// assert_eq!(impact.get_price(), 104);
+/// assert_eq!(impact.get_touch_slippage(), 2.0);
+/// assert_eq!(impact.get_mid_slippage(), Some(4.0));
+/// assert_eq!(impact.get_mid_slippage_bps(), Some(400.0));
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct MarketImpact<P> {
+    side: MarketSide,
+    price: P,
+    touch: P,
+    mid: Option<P>,
+}
+
+impl<P> MarketImpact<P>
+where
+    P: Copy,
+{
+    pub(crate) fn new(side: MarketSide, price: P, touch: P, mid: Option<P>) -> Self {
+        Self {
+            side,
+            price,
+            touch,
+            mid,
+        }
+    }
+
+    /// Get the side of the book swept
+    pub fn get_side(&self) -> MarketSide {
+        self.side
+    }
+
+    /// Get the average price of sweeping the size
+    pub fn get_price(&self) -> P {
+        self.price
+    }
+
+    /// Get the best price on the side swept
+    pub fn get_touch(&self) -> P {
+        self.touch
+    }
+
+    /// Get the mid of the book, which is None unless both sides have a price
+    pub fn get_mid(&self) -> Option<P> {
+        self.mid
+    }
+
+    /// Get the slippage of the average price from the best price in price units
+    pub fn get_touch_slippage(&self) -> f64
+    where
+        P: FloatConvert,
+    {
+        self.slippage(self.touch)
+    }
+
+    /// Get the slippage of the average price from the mid in price units
+    pub fn get_mid_slippage(&self) -> Option<f64>
+    where
+        P: FloatConvert,
+    {
+        self.mid.map(|mid| self.slippage(mid))
+    }
+
+    /// Get the slippage of the average price from the best price in basis points of the best price
+    pub fn get_touch_slippage_bps(&self) -> Option<f64>
+    where
+        P: FloatConvert,
+    {
+        self.slippage_bps(self.touch)
+    }
+
+    /// Get the slippage of the average price from the mid in basis points of the mid
+    pub fn get_mid_slippage_bps(&self) -> Option<f64>
+    where
+        P: FloatConvert,
+    {
+        self.mid.and_then(|mid| self.slippage_bps(mid))
+    }
+
+    fn slippage(&self, from: P) -> f64
+    where
+        P: FloatConvert,
+    {
+        match self.side {
+            MarketSide::Bid => from.to_f64() - self.price.to_f64(),
+            MarketSide::Offer => self.price.to_f64() - from.to_f64(),
+        }
+    }
+
+    fn slippage_bps(&self, from: P) -> Option<f64>
+    where
+        P: FloatConvert,
+    {
+        match self.side {
+            MarketSide::Bid => metrics::bps(self.price, from, from),
+            MarketSide::Offer => metrics::bps(from, self.price, from),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slippage_is_positive_when_worse() {
+        let bid = MarketImpact::new(MarketSide::Bid, 95.0, 100.0, Some(101.0));
+
+        assert_eq!(bid.get_touch_slippage(), 5.0);
+        assert_eq!(bid.get_mid_slippage(), Some(6.0));
+        assert_eq!(bid.get_touch_slippage_bps(), Some(500.0));
+
+        let offer = MarketImpact::new(MarketSide::Offer, 102, 102, None);
+
+        assert_eq!(offer.get_touch_slippage(), 0.0);
+        assert_eq!(offer.get_mid_slippage(), None);
+        assert_eq!(offer.get_mid_slippage_bps(), None);
+    }
+}
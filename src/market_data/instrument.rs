@@ -0,0 +1,232 @@
+use super::{Increment, InstrumentSpec, SpecPolicy};
+use std::rc::Rc;
+
+/// The asset class of an instrument
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum AssetClass {
+    /// Shares and exchange traded funds
+    Equity,
+    /// Bonds, bills and notes
+    FixedIncome,
+    /// Currency pairs
+    ForeignExchange,
+    /// Physical commodities and metals
+    Commodity,
+    /// Futures, options and swaps
+    Derivative,
+}
+
+/// How the price of an instrument is quoted
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub enum QuotingConvention {
+    /// The price is quoted directly in the quote currency
+    #[default]
+    Price,
+    /// The price is quoted as a percentage of par, which is common for bonds
+    PercentOfPar,
+    /// The price is quoted as a yield
+    Yield,
+    /// The price is quoted as a spread to a benchmark
+    Spread,
+}
+
+/// When trades in an instrument settle
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum SettlementConvention {
+    /// Settlement a number of business days after the trade date
+    TPlus(u32),
+    /// Cash settled with no delivery of the underlying
+    Cash,
+}
+
+/// The reference data for an instrument, i.e. what a book is pricing and how.
+///
+/// # Generic Parameters
+///
+/// * `P` - The price type that should be used.
+/// * `A` - The amount type that should be used, which defaults to the price type for markets without sizes.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Instrument<P, A = P> {
+    symbol: String,
+    asset_class: AssetClass,
+    base_currency: Option<String>,
+    quote_currency: String,
+    spec: InstrumentSpec<P, A>,
+    quoting_convention: QuotingConvention,
+    settlement_convention: SettlementConvention,
+}
+
+impl<P, A> Instrument<P, A>
+where
+    P: Increment,
+    A: Increment,
+{
+    /// Use the new function to create a new Instrument which is quoted as a price, settles T+2 and has no restrictions on
+    /// the prices or sizes.
+    ///
+    /// # Parameters
+    ///
+    /// * `symbol` - The symbol of the instrument
+    /// * `asset_class` - The asset class of the instrument
+    /// * `quote_currency` - The currency the instrument is priced in
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{AssetClass, Instrument, InstrumentSpec, SettlementConvention, SpecPolicy, TickSize};
+    ///
+    /// let instrument = Instrument::<i32, i32>::new("EURUSD", AssetClass::ForeignExchange, "USD")
+    ///     .with_base_currency("EUR")
+    ///     .with_spec(InstrumentSpec::new(SpecPolicy::Reject).with_tick_size(TickSize::Fixed(1)))
+    ///     .with_settlement_convention(SettlementConvention::TPlus(2));
+    ///
+    /// assert_eq!(instrument.get_symbol(), "EURUSD");
+    /// assert_eq!(instrument.get_base_currency(), Some("EUR"));
+    /// assert_eq!(instrument.get_quote_currency(), "USD");
+    /// ```
+    pub fn new(symbol: &str, asset_class: AssetClass, quote_currency: &str) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            asset_class,
+            base_currency: None,
+            quote_currency: quote_currency.to_string(),
+            spec: InstrumentSpec::new(SpecPolicy::Reject),
+            quoting_convention: QuotingConvention::Price,
+            settlement_convention: SettlementConvention::TPlus(2),
+        }
+    }
+
+    /// Set the base currency, for instruments such as currency pairs which have one
+    pub fn with_base_currency(mut self, base_currency: &str) -> Self {
+        self.base_currency = Some(base_currency.to_string());
+        self
+    }
+
+    /// Set the trading specification of the instrument
+    pub fn with_spec(mut self, spec: InstrumentSpec<P, A>) -> Self {
+        self.spec = spec;
+        self
+    }
+
+    /// Set how the price of the instrument is quoted
+    pub fn with_quoting_convention(mut self, quoting_convention: QuotingConvention) -> Self {
+        self.quoting_convention = quoting_convention;
+        self
+    }
+
+    /// Set when trades in the instrument settle
+    pub fn with_settlement_convention(
+        mut self,
+        settlement_convention: SettlementConvention,
+    ) -> Self {
+        self.settlement_convention = settlement_convention;
+        self
+    }
+
+    /// Get the symbol
+    pub fn get_symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Get the asset class
+    pub fn get_asset_class(&self) -> AssetClass {
+        self.asset_class
+    }
+
+    /// Get the base currency
+    pub fn get_base_currency(&self) -> Option<&str> {
+        self.base_currency.as_deref()
+    }
+
+    /// Get the currency the instrument is priced in
+    pub fn get_quote_currency(&self) -> &str {
+        &self.quote_currency
+    }
+
+    /// Get the trading specification
+    pub fn get_spec(&self) -> &InstrumentSpec<P, A> {
+        &self.spec
+    }
+
+    /// Get how the price is quoted
+    pub fn get_quoting_convention(&self) -> QuotingConvention {
+        self.quoting_convention
+    }
+
+    /// Get when trades settle
+    pub fn get_settlement_convention(&self) -> SettlementConvention {
+        self.settlement_convention
+    }
+}
+
+/// The instrument attached to a book along with any specification set on the book itself, which takes precedence over
+/// the specification of the instrument
+pub(crate) struct InstrumentReference<P, A = P> {
+    spec: Option<InstrumentSpec<P, A>>,
+    instrument: Option<Rc<Instrument<P, A>>>,
+}
+
+impl<P, A> InstrumentReference<P, A>
+where
+    P: Increment,
+    A: Increment,
+{
+    pub(crate) fn new() -> Self {
+        Self {
+            spec: None,
+            instrument: None,
+        }
+    }
+
+    pub(crate) fn set_book_spec(&mut self, spec: Option<InstrumentSpec<P, A>>) {
+        self.spec = spec;
+    }
+
+    pub(crate) fn get_book_spec(&self) -> &Option<InstrumentSpec<P, A>> {
+        &self.spec
+    }
+
+    pub(crate) fn set_instrument(&mut self, instrument: Option<Rc<Instrument<P, A>>>) {
+        self.instrument = instrument;
+    }
+
+    pub(crate) fn get_instrument(&self) -> Option<&Rc<Instrument<P, A>>> {
+        self.instrument.as_ref()
+    }
+
+    /// Get the specification updates are validated against
+    pub(crate) fn get_spec(&self) -> Option<&InstrumentSpec<P, A>> {
+        self.spec.as_ref().or_else(|| {
+            self.instrument
+                .as_ref()
+                .map(|instrument| instrument.get_spec())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        let instrument = Instrument::<f64, f64>::new("VOD.L", AssetClass::Equity, "GBP");
+
+        assert_eq!(instrument.get_symbol(), "VOD.L");
+        assert_eq!(instrument.get_asset_class(), AssetClass::Equity);
+        assert_eq!(instrument.get_base_currency(), None);
+        assert_eq!(instrument.get_quote_currency(), "GBP");
+        assert_eq!(
+            *instrument.get_spec(),
+            InstrumentSpec::new(SpecPolicy::Reject)
+        );
+        assert_eq!(
+            instrument.get_quoting_convention(),
+            QuotingConvention::Price
+        );
+        assert_eq!(
+            instrument.get_settlement_convention(),
+            SettlementConvention::TPlus(2)
+        );
+    }
+}
@@ -0,0 +1,466 @@
+use super::{FloatConvert, MarketDataError, MarketSide};
+
+/// Rounding to a fixed increment, used to enforce the tick size of prices and the lot size of amounts.
+///
+/// Implementations are provided for the primitive integer and floating point types.  Floating point values are treated as
+/// a multiple of the increment if they are within a small relative tolerance of one.  An increment of zero or less places
+/// no restriction on the value.
+pub trait Increment: Copy + PartialOrd {
+    /// Returns true if the value is a whole multiple of the increment
+    fn is_on_increment(self, increment: Self) -> bool;
+
+    /// Rounds the value down to a whole multiple of the increment, returning None if the result cannot be represented
+    fn round_down_to(self, increment: Self) -> Option<Self>;
+
+    /// Rounds the value up to a whole multiple of the increment, returning None if the result cannot be represented
+    fn round_up_to(self, increment: Self) -> Option<Self>;
+}
+
+macro_rules! integer_increment {
+    ($($t:ty),*) => {
+        $(
+            impl Increment for $t {
+                fn is_on_increment(self, increment: Self) -> bool {
+                    increment <= 0 || self.rem_euclid(increment) == 0
+                }
+
+                fn round_down_to(self, increment: Self) -> Option<Self> {
+                    if increment > 0 {
+                        self.checked_sub(self.rem_euclid(increment))
+                    } else {
+                        Some(self)
+                    }
+                }
+
+                fn round_up_to(self, increment: Self) -> Option<Self> {
+                    if self.is_on_increment(increment) {
+                        Some(self)
+                    } else {
+                        self.round_down_to(increment)?.checked_add(increment)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! float_increment {
+    ($($t:ty => $tolerance:expr),*) => {
+        $(
+            impl Increment for $t {
+                fn is_on_increment(self, increment: Self) -> bool {
+                    if increment <= 0.0 {
+                        return true;
+                    }
+
+                    let steps = self / increment;
+                    (steps - steps.round()).abs() <= $tolerance * steps.abs().max(1.0)
+                }
+
+                fn round_down_to(self, increment: Self) -> Option<Self> {
+                    if increment <= 0.0 {
+                        return Some(self);
+                    }
+
+                    let rounded = if self.is_on_increment(increment) {
+                        (self / increment).round() * increment
+                    } else {
+                        (self / increment).floor() * increment
+                    };
+
+                    rounded.is_finite().then_some(rounded)
+                }
+
+                fn round_up_to(self, increment: Self) -> Option<Self> {
+                    if increment <= 0.0 {
+                        return Some(self);
+                    }
+
+                    let rounded = if self.is_on_increment(increment) {
+                        (self / increment).round() * increment
+                    } else {
+                        (self / increment).ceil() * increment
+                    };
+
+                    rounded.is_finite().then_some(rounded)
+                }
+            }
+        )*
+    };
+}
+
+integer_increment!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+float_increment!(f32 => 1e-5, f64 => 1e-9);
+
+/// What to do with a price or size which does not conform to the instrument specification
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum SpecPolicy {
+    /// Reject the update with an error
+    #[default]
+    Reject,
+    /// Snap the value onto the tick or lot.  Bids are rounded down and offers up so that the book never shows a better
+    /// price than was quoted, and sizes are rounded down so that it never shows more liquidity than was quoted.
+    Snap,
+}
+
+/// The tick size of an instrument, which can either be fixed or vary by price band.
+///
+/// # Generic Parameters
+///
+/// * `P` - The price type that should be used.
+#[derive(Clone, PartialEq, Debug)]
+pub enum TickSize<P> {
+    /// A single tick size for all prices
+    Fixed(P),
+    /// A tick table as a list of (price the band starts from, tick size) in ascending price order.  Prices below the
+    /// first band use the tick size of the first band.
+    Banded(Vec<(P, P)>),
+}
+
+impl<P> TickSize<P>
+where
+    P: Copy + PartialOrd,
+{
+    /// Get the tick size which applies at a price
+    ///
+    /// # Parameters
+    ///
+    /// * `price` - The price to get the tick size for
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::TickSize;
+    ///
+    /// let tick_size = TickSize::Banded(vec![(0, 1), (100, 5), (1000, 10)]);
+    ///
+    /// assert_eq!(tick_size.get_tick(50), Some(1));
+    /// assert_eq!(tick_size.get_tick(100), Some(5));
+    /// assert_eq!(tick_size.get_tick(5000), Some(10));
+    /// ```
+    pub fn get_tick(&self, price: P) -> Option<P> {
+        match self {
+            TickSize::Fixed(tick) => Some(*tick),
+            TickSize::Banded(bands) => bands
+                .iter()
+                .take_while(|(from, _)| *from <= price)
+                .last()
+                .or(bands.first())
+                .map(|(_, tick)| *tick),
+        }
+    }
+
+    /// Get the number of ticks between two prices, counting each part of the range at the tick size of its band.  The
+    /// count is not rounded, so a range which is not a whole number of ticks gives a fraction.  A tick size of zero gives
+    /// None.
+    ///
+    /// # Parameters
+    ///
+    /// * `from` - The lower price
+    /// * `to` - The higher price
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::TickSize;
+    ///
+    /// let tick_size = TickSize::Banded(vec![(0, 1), (100, 5)]);
+    ///
+    /// assert_eq!(tick_size.ticks_between(95, 110), Some(7.0));
+    /// assert_eq!(TickSize::Fixed(2).ticks_between(10, 15), Some(2.5));
+    /// ```
+    pub fn ticks_between(&self, from: P, to: P) -> Option<f64>
+    where
+        P: FloatConvert,
+    {
+        let (from, to) = (from.to_f64(), to.to_f64());
+
+        match self {
+            TickSize::Fixed(tick) => {
+                let tick = tick.to_f64();
+                (tick > 0.0).then(|| (to - from) / tick)
+            }
+            TickSize::Banded(bands) => {
+                let mut ticks = 0.0;
+
+                for (index, (start, tick)) in bands.iter().enumerate() {
+                    // The first band also covers prices below it
+                    let start = if index == 0 {
+                        f64::NEG_INFINITY
+                    } else {
+                        start.to_f64()
+                    };
+                    let end = bands
+                        .get(index + 1)
+                        .map_or(f64::INFINITY, |(next, _)| next.to_f64());
+                    let overlap = to.min(end) - from.max(start);
+
+                    if overlap > 0.0 {
+                        let tick = tick.to_f64();
+
+                        if tick <= 0.0 {
+                            return None;
+                        }
+
+                        ticks += overlap / tick;
+                    }
+                }
+
+                (!bands.is_empty()).then_some(ticks)
+            }
+        }
+    }
+}
+
+/// The trading specification of an instrument, i.e. the tick size, lot size and minimum size, along with the policy for
+/// handling updates which do not conform to it.
+///
+/// # Generic Parameters
+///
+/// * `P` - The price type that should be used.
+/// * `A` - The amount type that should be used, which defaults to the price type for markets without sizes.
+#[derive(Clone, PartialEq, Debug)]
+pub struct InstrumentSpec<P, A = P> {
+    tick_size: Option<TickSize<P>>,
+    lot_size: Option<A>,
+    min_size: Option<A>,
+    policy: SpecPolicy,
+}
+
+impl<P, A> InstrumentSpec<P, A>
+where
+    P: Increment,
+    A: Increment,
+{
+    /// Use the new function to create a specification which places no restriction on prices or sizes
+    ///
+    /// # Parameters
+    ///
+    /// * `policy` - What to do with prices and sizes which do not conform
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{InstrumentSpec, SpecPolicy, TickSize};
+    ///
+    /// let spec = InstrumentSpec::<i32, i32>::new(SpecPolicy::Reject)
+    ///     .with_tick_size(TickSize::Fixed(5))
+    ///     .with_lot_size(100)
+    ///     .with_min_size(1000);
+    /// ```
+    pub fn new(policy: SpecPolicy) -> Self {
+        Self {
+            tick_size: None,
+            lot_size: None,
+            min_size: None,
+            policy,
+        }
+    }
+
+    /// Set the tick size prices must be a multiple of
+    pub fn with_tick_size(mut self, tick_size: TickSize<P>) -> Self {
+        self.tick_size = Some(tick_size);
+        self
+    }
+
+    /// Set the lot size amounts must be a multiple of
+    pub fn with_lot_size(mut self, lot_size: A) -> Self {
+        self.lot_size = Some(lot_size);
+        self
+    }
+
+    /// Set the minimum amount
+    pub fn with_min_size(mut self, min_size: A) -> Self {
+        self.min_size = Some(min_size);
+        self
+    }
+
+    /// Get the tick size
+    pub fn get_tick_size(&self) -> &Option<TickSize<P>> {
+        &self.tick_size
+    }
+
+    /// Get the lot size
+    pub fn get_lot_size(&self) -> &Option<A> {
+        &self.lot_size
+    }
+
+    /// Get the minimum size
+    pub fn get_min_size(&self) -> &Option<A> {
+        &self.min_size
+    }
+
+    /// Get the policy for prices and sizes which do not conform
+    pub fn get_policy(&self) -> SpecPolicy {
+        self.policy
+    }
+
+    /// Checks a price against the tick size, returning the price which should be used.
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the market the price is for, which decides the direction it is snapped in
+    /// * `price` - The price to check
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{InstrumentSpec, MarketDataError, MarketSide, SpecPolicy, TickSize};
+    ///
+    /// let spec = InstrumentSpec::<i32, i32>::new(SpecPolicy::Reject).with_tick_size(TickSize::Fixed(5));
+    ///
+    /// assert_eq!(spec.validate_price(&MarketSide::Bid, 15), Ok(15));
+    /// assert_eq!(spec.validate_price(&MarketSide::Bid, 17), Err(MarketDataError::OffTickPrice));
+    ///
+    /// let spec = InstrumentSpec::<i32, i32>::new(SpecPolicy::Snap).with_tick_size(TickSize::Fixed(5));
+    ///
+    /// assert_eq!(spec.validate_price(&MarketSide::Bid, 17), Ok(15));
+    /// assert_eq!(spec.validate_price(&MarketSide::Offer, 17), Ok(20));
+    /// ```
+    pub fn validate_price(&self, side: &MarketSide, price: P) -> Result<P, MarketDataError> {
+        match self
+            .tick_size
+            .as_ref()
+            .and_then(|tick_size| tick_size.get_tick(price))
+        {
+            Some(tick) if !price.is_on_increment(tick) => match self.policy {
+                SpecPolicy::Reject => Err(MarketDataError::OffTickPrice),
+                SpecPolicy::Snap => match side {
+                    MarketSide::Bid => price.round_down_to(tick),
+                    MarketSide::Offer => price.round_up_to(tick),
+                }
+                .ok_or(MarketDataError::OffTickPrice),
+            },
+            _ => Ok(price),
+        }
+    }
+
+    /// Checks a size against the lot size and minimum size, returning the size which should be used.  A size below the
+    /// minimum is always rejected as it cannot be snapped without increasing the liquidity, as is a size below one lot as
+    /// snapping it would leave nothing.
+    ///
+    /// # Parameters
+    ///
+    /// * `size` - The size to check
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{InstrumentSpec, MarketDataError, SpecPolicy};
+    ///
+    /// let spec = InstrumentSpec::<i32, i32>::new(SpecPolicy::Snap).with_lot_size(100).with_min_size(200);
+    ///
+    /// assert_eq!(spec.validate_size(350), Ok(300));
+    /// assert_eq!(spec.validate_size(150), Err(MarketDataError::BelowMinimumSize));
+    ///
+    /// let spec = InstrumentSpec::<i32, i32>::new(SpecPolicy::Snap).with_lot_size(100);
+    ///
+    /// assert_eq!(spec.validate_size(50), Err(MarketDataError::OffLotSize));
+    /// ```
+    pub fn validate_size(&self, size: A) -> Result<A, MarketDataError> {
+        let size = match self.lot_size {
+            Some(lot_size) if !size.is_on_increment(lot_size) => match self.policy {
+                SpecPolicy::Reject => return Err(MarketDataError::OffLotSize),
+                SpecPolicy::Snap if size < lot_size => return Err(MarketDataError::OffLotSize),
+                SpecPolicy::Snap => size
+                    .round_down_to(lot_size)
+                    .ok_or(MarketDataError::OffLotSize)?,
+            },
+            _ => size,
+        };
+
+        match self.min_size {
+            Some(min_size) if size < min_size => Err(MarketDataError::BelowMinimumSize),
+            _ => Ok(size),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integer_increment() {
+        assert!(15.is_on_increment(5));
+        assert!(!17.is_on_increment(5));
+        assert!(17.is_on_increment(0));
+        assert_eq!(17.round_down_to(5), Some(15));
+        assert_eq!(17.round_up_to(5), Some(20));
+        assert_eq!((-17).round_down_to(5), Some(-20));
+        assert_eq!((-17).round_up_to(5), Some(-15));
+        assert_eq!(20u32.round_up_to(5), Some(20));
+        assert_eq!(i32::MAX.round_up_to(5), None);
+        assert_eq!(i32::MIN.round_down_to(5), None);
+        assert_eq!(u8::MAX.round_up_to(10), None);
+        assert!(i32::MIN.is_on_increment(-1));
+        assert_eq!(7.round_up_to(-5), Some(7));
+        assert_eq!(7.round_down_to(-5), Some(7));
+        assert_eq!(7u32.round_up_to(0), Some(7));
+    }
+
+    #[test]
+    fn float_increment() {
+        assert!(0.3.is_on_increment(0.1));
+        assert!(1.2345.is_on_increment(0.0001));
+        assert!(!1.23455.is_on_increment(0.0001));
+        assert!((1.23455_f64.round_down_to(0.0001).unwrap() - 1.2345).abs() < 1e-12);
+        assert!((1.23455_f64.round_up_to(0.0001).unwrap() - 1.2346).abs() < 1e-12);
+        assert!((0.3_f64.round_up_to(0.1).unwrap() - 0.3).abs() < 1e-12);
+        assert_eq!(f64::MAX.round_up_to(3.0), None);
+        assert_eq!(1.23455.round_down_to(0.0), Some(1.23455));
+        assert_eq!(1.23455.round_up_to(-0.1), Some(1.23455));
+    }
+
+    #[test]
+    fn banded_tick_size() {
+        let tick_size = TickSize::Banded(vec![(10, 1), (100, 5)]);
+
+        assert_eq!(tick_size.get_tick(5), Some(1));
+        assert_eq!(tick_size.get_tick(99), Some(1));
+        assert_eq!(tick_size.get_tick(100), Some(5));
+        assert_eq!(TickSize::<i32>::Banded(vec![]).get_tick(5), None);
+
+        let spec = InstrumentSpec::<i32, i32>::new(SpecPolicy::Reject).with_tick_size(tick_size);
+
+        assert_eq!(spec.validate_price(&MarketSide::Bid, 99), Ok(99));
+        assert_eq!(
+            spec.validate_price(&MarketSide::Bid, 101),
+            Err(MarketDataError::OffTickPrice)
+        );
+    }
+
+    #[test]
+    fn reject_size() {
+        let spec = InstrumentSpec::<i32, i32>::new(SpecPolicy::Reject)
+            .with_lot_size(100)
+            .with_min_size(200);
+
+        assert_eq!(spec.validate_size(300), Ok(300));
+        assert_eq!(spec.validate_size(350), Err(MarketDataError::OffLotSize));
+        assert_eq!(
+            spec.validate_size(100),
+            Err(MarketDataError::BelowMinimumSize)
+        );
+    }
+
+    #[test]
+    fn snap_size() {
+        let spec = InstrumentSpec::<i32, i32>::new(SpecPolicy::Snap).with_lot_size(100);
+
+        assert_eq!(spec.validate_size(350), Ok(300));
+        assert_eq!(spec.validate_size(99), Err(MarketDataError::OffLotSize));
+
+        let spec =
+            InstrumentSpec::<i32, i32>::new(SpecPolicy::Snap).with_tick_size(TickSize::Fixed(5));
+
+        assert_eq!(
+            spec.validate_price(&MarketSide::Offer, i32::MAX),
+            Err(MarketDataError::OffTickPrice)
+        );
+        assert_eq!(
+            spec.validate_price(&MarketSide::Bid, i32::MIN),
+            Err(MarketDataError::OffTickPrice)
+        );
+    }
+}
@@ -2,12 +2,27 @@ use std::{
     cell::RefCell,
     ops::{Add, Div},
     rc::Rc,
+    time::Duration,
 };
 
-use super::BidOffer;
+use super::{
+    expiry::QuoteExpiry, instrument::InstrumentReference, quality::SideQuality,
+    session::MarketSession, BidOffer, Clock, CrossPolicy, CrossStatus, Increment, Instrument,
+    InstrumentSpec, MarketDataError, MarketSide, MarketStatus, PriceQuality, QuotedPrice,
+    TradingSchedule,
+};
 
 pub trait L1MarketCallback {
     fn market_updated(&self);
+
+    /// Called when the pricing on a side has not been updated within its time to live
+    fn market_stale(&self, _side: MarketSide) {}
+
+    /// Called when the bid and offer start or stop overlapping
+    fn cross_status_changed(&self, _status: CrossStatus) {}
+
+    /// Called when the market status moves from one status to another
+    fn status_changed(&self, _from: MarketStatus, _to: MarketStatus) {}
 }
 
 /// A structure to hold L1 pricing, i.e. a single level of pricing.  This can either be to be the top of book of a deeper
@@ -18,16 +33,22 @@ pub trait L1MarketCallback {
 /// * `P` - The price type that should be used.
 pub struct L1MarketData<P>
 where
-    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<i32>,
+    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<u8> + Increment,
 {
     price: BidOffer<P>,
+    reference: InstrumentReference<P>,
+    expiry: QuoteExpiry,
+    cross_policy: CrossPolicy,
+    cross_status: CrossStatus,
+    quality: SideQuality,
+    session: MarketSession,
 
     callbacks: RefCell<Vec<Rc<dyn L1MarketCallback>>>,
 }
 
 impl<P> L1MarketData<P>
 where
-    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<i32>,
+    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<u8> + Increment,
 {
     /// Use the new function to create a new L1MarketData with no pricing.
     ///
@@ -44,6 +65,12 @@ where
     pub fn new() -> Self {
         Self {
             price: BidOffer::new(),
+            reference: InstrumentReference::new(),
+            expiry: QuoteExpiry::new(),
+            cross_policy: CrossPolicy::KeepAndFlag,
+            cross_status: CrossStatus::Normal,
+            quality: SideQuality::default(),
+            session: MarketSession::default(),
             callbacks: RefCell::new(Vec::new()),
         }
     }
@@ -66,8 +93,20 @@ where
     /// assert_eq!(*market_data.get_offer(), Some(20));
     /// ```
     pub fn new_with_price(bid: Option<P>, offer: Option<P>) -> Self {
+        let mut expiry = QuoteExpiry::new();
+        expiry.touch(MarketSide::Bid);
+        expiry.touch(MarketSide::Offer);
+
+        let price = BidOffer::new_with_price(bid, offer);
+
         Self {
-            price: BidOffer::new_with_price(bid, offer),
+            price,
+            reference: InstrumentReference::new(),
+            expiry,
+            cross_policy: CrossPolicy::KeepAndFlag,
+            cross_status: price.get_cross_status(),
+            quality: SideQuality::default(),
+            session: MarketSession::default(),
             callbacks: RefCell::new(Vec::new()),
         }
     }
@@ -132,15 +171,25 @@ where
     ///
     /// assert_eq!(*market_data.get_bid(), Some(10));
     ///
-    /// market_data.update_bid(Some(12));
+    /// market_data.update_bid(Some(12)).unwrap();
     ///
     /// assert_eq!(*market_data.get_bid(), Some(12));
     /// ```
-    pub fn update_bid(&mut self, bid: Option<P>) {
-        if *self.price.get_bid() != bid {
-            self.price = BidOffer::new_with_price(bid, *self.price.get_offer());
+    pub fn update_bid(&mut self, bid: Option<P>) -> Result<(), MarketDataError> {
+        let bid = self.validate_price(MarketSide::Bid, bid)?;
+        let (price, _) = self.resolve_cross(
+            Some(MarketSide::Bid),
+            BidOffer::new_with_price(bid, *self.price.get_offer()),
+        )?;
+        self.expiry.touch(MarketSide::Bid);
+
+        if self.price != price {
+            self.price = price;
             self.publish_to_subscribers();
+            self.refresh_cross_status();
         }
+
+        Ok(())
     }
 
     /// Update the offer price
@@ -158,15 +207,25 @@ where
     ///
     /// assert_eq!(*market_data.get_offer(), Some(20));
     ///
-    /// market_data.update_offer(Some(22));
+    /// market_data.update_offer(Some(22)).unwrap();
     ///
     /// assert_eq!(*market_data.get_offer(), Some(22));
     /// ```
-    pub fn update_offer(&mut self, offer: Option<P>) {
-        if *self.price.get_offer() != offer {
-            self.price = BidOffer::new_with_price(*self.price.get_bid(), offer);
+    pub fn update_offer(&mut self, offer: Option<P>) -> Result<(), MarketDataError> {
+        let offer = self.validate_price(MarketSide::Offer, offer)?;
+        let (price, _) = self.resolve_cross(
+            Some(MarketSide::Offer),
+            BidOffer::new_with_price(*self.price.get_bid(), offer),
+        )?;
+        self.expiry.touch(MarketSide::Offer);
+
+        if self.price != price {
+            self.price = price;
             self.publish_to_subscribers();
+            self.refresh_cross_status();
         }
+
+        Ok(())
     }
 
     /// Update both the bid and offer prices
@@ -186,16 +245,25 @@ where
     /// assert_eq!(*market_data.get_bid(), Some(10));
     /// assert_eq!(*market_data.get_offer(), Some(20));
     ///
-    /// market_data.update(Some(12), Some(22));
+    /// market_data.update(Some(12), Some(22)).unwrap();
     ///
     /// assert_eq!(*market_data.get_bid(), Some(12));
     /// assert_eq!(*market_data.get_offer(), Some(22));
     /// ```
-    pub fn update(&mut self, bid: Option<P>, offer: Option<P>) {
-        if *self.price.get_bid() != bid || *self.price.get_offer() != offer {
-            self.price = BidOffer::new_with_price(bid, offer);
+    pub fn update(&mut self, bid: Option<P>, offer: Option<P>) -> Result<(), MarketDataError> {
+        let bid = self.validate_price(MarketSide::Bid, bid)?;
+        let offer = self.validate_price(MarketSide::Offer, offer)?;
+        let (price, _) = self.resolve_cross(None, BidOffer::new_with_price(bid, offer))?;
+        self.expiry.touch(MarketSide::Bid);
+        self.expiry.touch(MarketSide::Offer);
+
+        if self.price != price {
+            self.price = price;
             self.publish_to_subscribers();
+            self.refresh_cross_status();
         }
+
+        Ok(())
     }
 
     /// Update both the bid and offer prices using a bid/offer structure
@@ -214,16 +282,13 @@ where
     /// assert_eq!(*market_data.get_bid(), Some(10));
     /// assert_eq!(*market_data.get_offer(), Some(20));
     ///
-    /// market_data.update_price(BidOffer::new_with_price(Some(12), Some(22)));
+    /// market_data.update_price(BidOffer::new_with_price(Some(12), Some(22))).unwrap();
     ///
     /// assert_eq!(*market_data.get_bid(), Some(12));
     /// assert_eq!(*market_data.get_offer(), Some(22));
     /// ```
-    pub fn update_price(&mut self, price: BidOffer<P>) {
-        if self.price != price {
-            self.price = price;
-            self.publish_to_subscribers();
-        }
+    pub fn update_price(&mut self, price: BidOffer<P>) -> Result<(), MarketDataError> {
+        self.update(*price.get_bid(), *price.get_offer())
     }
 
     /// Clears the data structure setting bid/off and the max sizes all to None
@@ -247,10 +312,11 @@ where
         if self.price.get_bid().is_some() || self.price.get_offer().is_some() {
             self.price = BidOffer::new();
             self.publish_to_subscribers();
+            self.refresh_cross_status();
         }
     }
 
-    /// Returns the price for the size passed in, which is always the current pricing.
+    /// Returns the current pricing while the market status has firm prices, and no pricing otherwise.
     ///
     /// # Returns
     ///
@@ -266,7 +332,307 @@ where
     /// assert_eq!(market_data.get_price(), BidOffer::new_with_price(Some(10), Some(20)));
     /// ```
     pub fn get_price(&self) -> BidOffer<P> {
-        self.price
+        if self.session.get_status().has_firm_prices() {
+            self.price
+        } else {
+            BidOffer::new()
+        }
+    }
+
+    /// Set the instrument specification that prices are validated against on update, in place of the specification of
+    /// any attached instrument.  A value of None means the instrument's specification is used.  As the L1 market has no
+    /// sizes only the tick size of the specification is used.
+    ///
+    /// # Parameters
+    ///
+    /// * `spec` - The instrument specification
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{InstrumentSpec, L1MarketData, MarketDataError, SpecPolicy, TickSize};
+    ///
+    /// let mut market_data = L1MarketData::new();
+    ///
+    /// market_data.set_instrument_spec(Some(InstrumentSpec::new(SpecPolicy::Reject).with_tick_size(TickSize::Fixed(5))));
+    ///
+    /// assert_eq!(market_data.update(Some(10), Some(15)), Ok(()));
+    /// assert_eq!(market_data.update(Some(11), Some(15)), Err(MarketDataError::OffTickPrice));
+    /// assert_eq!(*market_data.get_bid(), Some(10));
+    /// ```
+    pub fn set_instrument_spec(&mut self, spec: Option<InstrumentSpec<P>>) {
+        self.reference.set_book_spec(spec);
+    }
+
+    /// Get the instrument specification set on the book, which prices are validated against in place
+    /// of that of any attached instrument
+    pub fn get_instrument_spec(&self) -> &Option<InstrumentSpec<P>> {
+        self.reference.get_book_spec()
+    }
+
+    /// Attach the reference data of the instrument the book is pricing.  Updates are validated against the specification
+    /// of the instrument unless one has been set on the book, which takes precedence.
+    ///
+    /// # Parameters
+    ///
+    /// * `instrument` - The instrument, a value of None detaches the instrument
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use pricing::market_data::{
+    ///     AssetClass, Instrument, InstrumentSpec, L1MarketData, MarketDataError, SpecPolicy, TickSize,
+    /// };
+    ///
+    /// let mut market_data = L1MarketData::new();
+    ///
+    /// market_data.set_instrument(Some(Rc::new(
+    ///     Instrument::new("UST10Y", AssetClass::FixedIncome, "USD")
+    ///         .with_spec(InstrumentSpec::new(SpecPolicy::Reject).with_tick_size(TickSize::Fixed(5))),
+    /// )));
+    ///
+    /// assert_eq!(market_data.get_instrument().unwrap().get_symbol(), "UST10Y");
+    /// assert_eq!(market_data.update(Some(11), Some(15)), Err(MarketDataError::OffTickPrice));
+    /// ```
+    pub fn set_instrument(&mut self, instrument: Option<Rc<Instrument<P>>>) {
+        self.reference.set_instrument(instrument);
+    }
+
+    /// Get the reference data of the instrument the book is pricing
+    pub fn get_instrument(&self) -> Option<&Rc<Instrument<P>>> {
+        self.reference.get_instrument()
+    }
+
+    /// Set what happens to an update which would leave the bid above the offer, which defaults to keeping it and flagging
+    /// the book as crossed
+    ///
+    /// # Parameters
+    ///
+    /// * `policy` - The policy for crossing updates
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{CrossPolicy, CrossStatus, L1MarketData, MarketDataError};
+    ///
+    /// let mut market_data = L1MarketData::<i32>::new_with_price(Some(10), Some(12));
+    ///
+    /// market_data.set_cross_policy(CrossPolicy::Reject);
+    /// assert_eq!(market_data.update_bid(Some(13)), Err(MarketDataError::CrossedBook));
+    ///
+    /// market_data.set_cross_policy(CrossPolicy::RemoveStale);
+    /// market_data.update_bid(Some(13)).unwrap();
+    /// assert_eq!(*market_data.get_offer(), None);
+    ///
+    /// market_data.set_cross_policy(CrossPolicy::KeepAndFlag);
+    /// market_data.update_offer(Some(12)).unwrap();
+    /// assert_eq!(market_data.get_cross_status(), CrossStatus::Crossed);
+    /// ```
+    pub fn set_cross_policy(&mut self, policy: CrossPolicy) {
+        self.cross_policy = policy;
+    }
+
+    /// Get what happens to an update which would leave the bid above the offer
+    pub fn get_cross_policy(&self) -> CrossPolicy {
+        self.cross_policy
+    }
+
+    /// Get whether the bid and offer currently overlap
+    pub fn get_cross_status(&self) -> CrossStatus {
+        self.cross_status
+    }
+
+    /// Set the quality of the price on a side, for example to flag it as indicative.  Subscribers are notified if the
+    /// quality changes.
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the market
+    /// * `quality` - The quality of the price on the side
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, L1MarketData, MarketSide, PriceQuality};
+    ///
+    /// let mut market_data = L1MarketData::new_with_price(Some(10), Some(12));
+    ///
+    /// market_data.set_quality(MarketSide::Offer, PriceQuality::Indicative);
+    ///
+    /// let quoted = market_data.get_quoted_price();
+    /// assert!(quoted.is_firm(MarketSide::Bid));
+    /// assert!(!quoted.is_firm(MarketSide::Offer));
+    /// assert_eq!(quoted.get_firm_price(), BidOffer::new_with_price(Some(10), None));
+    /// ```
+    pub fn set_quality(&mut self, side: MarketSide, quality: PriceQuality) {
+        if self.quality.set(side, quality) {
+            self.publish_to_subscribers();
+        }
+    }
+
+    /// Get the quality of the price on a side, which is stale once `check_stale` has found the side expired and is limited
+    /// by the market status
+    pub fn get_quality(&self, side: MarketSide) -> PriceQuality {
+        self.session
+            .get_status()
+            .limit_quality(self.quality.get(side, self.expiry.is_stale(side)))
+    }
+
+    /// Returns the current pricing along with the quality of each side, whatever the market status
+    pub fn get_quoted_price(&self) -> QuotedPrice<P> {
+        QuotedPrice::new(
+            self.price,
+            self.get_quality(MarketSide::Bid),
+            self.get_quality(MarketSide::Offer),
+        )
+    }
+
+    /// Move the market to a status, for example from a status message from the venue.  Subscribers are notified if the
+    /// status changes.
+    ///
+    /// # Parameters
+    ///
+    /// * `status` - The status of the market
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, L1MarketData, MarketDataError, MarketSide, MarketStatus, PriceQuality};
+    ///
+    /// let mut market_data = L1MarketData::new_with_price(Some(10), Some(12));
+    ///
+    /// market_data.set_status(MarketStatus::Halted).unwrap();
+    /// assert_eq!(market_data.get_price(), BidOffer::new());
+    /// assert_eq!(market_data.get_quality(MarketSide::Bid), PriceQuality::Indicative);
+    ///
+    /// assert_eq!(market_data.set_status(MarketStatus::PreOpen), Err(MarketDataError::InvalidTransition));
+    /// ```
+    pub fn set_status(&mut self, status: MarketStatus) -> Result<(), MarketDataError> {
+        if let Some(from) = self.session.transition(status)? {
+            self.publish_status(from, status);
+        }
+
+        Ok(())
+    }
+
+    /// Get the status of the market, which defaults to continuous trading
+    pub fn get_status(&self) -> MarketStatus {
+        self.session.get_status()
+    }
+
+    /// Set the schedule which `check_schedule` moves the market status through.  A value of None means the status only
+    /// changes with `set_status`.
+    pub fn set_schedule(&mut self, schedule: Option<TradingSchedule>) {
+        self.session.set_schedule(schedule);
+    }
+
+    /// Get the schedule the market status moves through
+    pub fn get_schedule(&self) -> Option<&TradingSchedule> {
+        self.session.get_schedule()
+    }
+
+    /// Moves the market to the status the schedule gives at the time passed in, notifying subscribers if it changes.  The
+    /// first check after the schedule is set moves straight to the status it gives.  After that a status the market
+    /// cannot move to is ignored.  A halted market only follows the schedule when it closes.
+    ///
+    /// # Parameters
+    ///
+    /// * `now` - The time to check the schedule against
+    ///
+    /// # Returns
+    ///
+    /// The new status if it has changed
+    pub fn check_schedule(&mut self, now: Duration) -> Option<MarketStatus> {
+        let from = self.session.check_schedule(now)?;
+        let status = self.session.get_status();
+
+        self.publish_status(from, status);
+
+        Some(status)
+    }
+
+    /// Set the clock used to timestamp updates, which defaults to the system clock
+    pub fn set_clock(&mut self, clock: Rc<dyn Clock>) {
+        self.expiry.set_clock(clock);
+    }
+
+    /// Set how long the pricing on a side is valid for after it is updated.  A value of None means it never expires.
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the market
+    /// * `ttl` - The time to live
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use std::time::Duration;
+    /// use pricing::market_data::{BidOffer, L1MarketData, ManualClock, MarketSide};
+    ///
+    /// let clock = Rc::new(ManualClock::new(Duration::from_secs(100)));
+    /// let mut market_data = L1MarketData::<i32>::new();
+    ///
+    /// market_data.set_clock(clock.clone());
+    /// market_data.set_time_to_live(MarketSide::Bid, Some(Duration::from_secs(5)));
+    /// market_data.update(Some(10), Some(20)).unwrap();
+    ///
+    /// assert_eq!(market_data.get_price_at(Duration::from_secs(105)), BidOffer::new_with_price(Some(10), Some(20)));
+    /// assert_eq!(market_data.get_price_at(Duration::from_secs(106)), BidOffer::new_with_price(None, Some(20)));
+    /// ```
+    pub fn set_time_to_live(&mut self, side: MarketSide, ttl: Option<Duration>) {
+        self.expiry.set_time_to_live(side, ttl);
+    }
+
+    /// Get how long the pricing on a side is valid for after it is updated
+    pub fn get_time_to_live(&self, side: MarketSide) -> Option<Duration> {
+        self.expiry.get_time_to_live(side)
+    }
+
+    /// Get when a side was last updated, even if the update did not change the pricing
+    pub fn get_updated_time(&self, side: MarketSide) -> Option<Duration> {
+        self.expiry.get_updated(side)
+    }
+
+    /// Returns the current pricing with any side which has expired by the time passed in removed
+    ///
+    /// # Parameters
+    ///
+    /// * `now` - The time to check expiry against
+    pub fn get_price_at(&self, now: Duration) -> BidOffer<P> {
+        let side_price = |side, price: &Option<P>| {
+            if self.expiry.is_expired(side, now) {
+                None
+            } else {
+                *price
+            }
+        };
+
+        BidOffer::new_with_price(
+            side_price(MarketSide::Bid, self.price.get_bid()),
+            side_price(MarketSide::Offer, self.price.get_offer()),
+        )
+    }
+
+    /// Checks for sides which have expired by the time passed in, notifying subscribers of each side the first time it is
+    /// found to be stale.  A side stops being stale when it is next updated.
+    ///
+    /// # Parameters
+    ///
+    /// * `now` - The time to check expiry against
+    ///
+    /// # Returns
+    ///
+    /// The sides which have newly become stale
+    pub fn check_stale(&mut self, now: Duration) -> Vec<MarketSide> {
+        let stale = self.expiry.check_stale(now);
+
+        for &side in &stale {
+            self.publish_stale(side);
+        }
+
+        stale
     }
 
     /// Subscribe to changes to the pricing, and is only called if the pricing actually changes (i.e. updating with the current
@@ -314,25 +680,84 @@ where
     /// let callback = Rc::new(TestCallback::new());
     /// test.subscribe(callback.clone());
     ///
-    /// test.update_bid(Some(10));
+    /// test.update_bid(Some(10)).unwrap();
     /// assert!(!callback.is_called());
-    /// test.update_bid(Some(9));
+    /// test.update_bid(Some(9)).unwrap();
     /// assert!(callback.is_called());
     /// ```
     pub fn subscribe(&self, callback: Rc<dyn L1MarketCallback>) {
         self.callbacks.borrow_mut().push(callback.clone());
     }
 
+    // Applies the cross policy to the price an update would leave, returning the price to use and the side removed as stale
+    fn resolve_cross(
+        &self,
+        side: Option<MarketSide>,
+        price: BidOffer<P>,
+    ) -> Result<(BidOffer<P>, Option<MarketSide>), MarketDataError> {
+        if !price.is_crossed() {
+            return Ok((price, None));
+        }
+
+        match (self.cross_policy, side) {
+            (CrossPolicy::Reject, _) => Err(MarketDataError::CrossedBook),
+            (CrossPolicy::RemoveStale, Some(MarketSide::Bid)) => Ok((
+                BidOffer::new_with_price(*price.get_bid(), None),
+                Some(MarketSide::Offer),
+            )),
+            (CrossPolicy::RemoveStale, Some(MarketSide::Offer)) => Ok((
+                BidOffer::new_with_price(None, *price.get_offer()),
+                Some(MarketSide::Bid),
+            )),
+            _ => Ok((price, None)),
+        }
+    }
+
+    fn refresh_cross_status(&mut self) {
+        let status = self.price.get_cross_status();
+
+        if status != self.cross_status {
+            self.cross_status = status;
+
+            for callback in self.callbacks.borrow().iter() {
+                callback.cross_status_changed(status);
+            }
+        }
+    }
+
+    fn validate_price(
+        &self,
+        side: MarketSide,
+        price: Option<P>,
+    ) -> Result<Option<P>, MarketDataError> {
+        match (self.reference.get_spec(), price) {
+            (Some(spec), Some(price)) => spec.validate_price(&side, price).map(Some),
+            _ => Ok(price),
+        }
+    }
+
     fn publish_to_subscribers(&self) {
         for callback in self.callbacks.borrow().iter() {
             callback.market_updated();
         }
     }
+
+    fn publish_stale(&self, side: MarketSide) {
+        for callback in self.callbacks.borrow().iter() {
+            callback.market_stale(side);
+        }
+    }
+
+    fn publish_status(&self, from: MarketStatus, to: MarketStatus) {
+        for callback in self.callbacks.borrow().iter() {
+            callback.status_changed(from, to);
+        }
+    }
 }
 
 impl<P> Default for L1MarketData<P>
 where
-    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<i32>,
+    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<u8> + Increment,
 {
     fn default() -> Self {
         Self::new()
@@ -349,19 +774,25 @@ where
 /// * `P` - The price type that should be used.
 pub struct L1MarketDataWithMax<P, A>
 where
-    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<i32>,
-    A: Copy + PartialOrd + Add<Output = A> + Div<Output = A> + From<i32>,
+    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<u8> + Increment,
+    A: Copy + PartialOrd + Add<Output = A> + Div<Output = A> + From<u8> + Increment,
 {
     price: BidOffer<P>,
     max: BidOffer<A>,
+    reference: InstrumentReference<P, A>,
+    expiry: QuoteExpiry,
+    cross_policy: CrossPolicy,
+    cross_status: CrossStatus,
+    quality: SideQuality,
+    session: MarketSession,
 
     callbacks: RefCell<Vec<Rc<dyn L1MarketCallback>>>,
 }
 
 impl<P, A> L1MarketDataWithMax<P, A>
 where
-    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<i32>,
-    A: Copy + PartialOrd + Add<Output = A> + Div<Output = A> + From<i32>,
+    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<u8> + Increment,
+    A: Copy + PartialOrd + Add<Output = A> + Div<Output = A> + From<u8> + Increment,
 {
     /// Use the new function to create a new L1MarketDataWithMax with no pricing.
     ///
@@ -381,6 +812,12 @@ where
         Self {
             price: BidOffer::new(),
             max: BidOffer::new(),
+            reference: InstrumentReference::new(),
+            expiry: QuoteExpiry::new(),
+            cross_policy: CrossPolicy::KeepAndFlag,
+            cross_status: CrossStatus::Normal,
+            quality: SideQuality::default(),
+            session: MarketSession::default(),
             callbacks: RefCell::new(Vec::new()),
         }
     }
@@ -435,9 +872,21 @@ where
         max_bid: Option<A>,
         max_offer: Option<A>,
     ) -> Self {
+        let mut expiry = QuoteExpiry::new();
+        expiry.touch(MarketSide::Bid);
+        expiry.touch(MarketSide::Offer);
+
+        let price = BidOffer::new_with_price(bid, offer);
+
         Self {
-            price: BidOffer::new_with_price(bid, offer),
+            price,
             max: BidOffer::new_with_price(max_bid, max_offer),
+            reference: InstrumentReference::new(),
+            expiry,
+            cross_policy: CrossPolicy::KeepAndFlag,
+            cross_status: price.get_cross_status(),
+            quality: SideQuality::default(),
+            session: MarketSession::default(),
             callbacks: RefCell::new(Vec::new()),
         }
     }
@@ -532,15 +981,26 @@ where
     ///
     /// assert_eq!(*market_data.get_bid(), Some(10));
     ///
-    /// market_data.update_bid(Some(12));
+    /// market_data.update_bid(Some(12)).unwrap();
     ///
     /// assert_eq!(*market_data.get_bid(), Some(12));
     /// ```
-    pub fn update_bid(&mut self, bid: Option<P>) {
-        if *self.price.get_bid() != bid {
-            self.price = BidOffer::new_with_price(bid, *self.price.get_offer());
+    pub fn update_bid(&mut self, bid: Option<P>) -> Result<(), MarketDataError> {
+        let bid = self.validate_price(MarketSide::Bid, bid)?;
+        let (price, removed) = self.resolve_cross(
+            Some(MarketSide::Bid),
+            BidOffer::new_with_price(bid, *self.price.get_offer()),
+        )?;
+        self.expiry.touch(MarketSide::Bid);
+
+        if self.price != price {
+            self.price = price;
+            self.clear_removed_max(removed);
             self.publish_to_subscribers();
+            self.refresh_cross_status();
         }
+
+        Ok(())
     }
 
     /// Update the offer price
@@ -558,15 +1018,26 @@ where
     ///
     /// assert_eq!(*market_data.get_offer(), Some(20));
     ///
-    /// market_data.update_offer(Some(22));
+    /// market_data.update_offer(Some(22)).unwrap();
     ///
     /// assert_eq!(*market_data.get_offer(), Some(22));
     /// ```
-    pub fn update_offer(&mut self, offer: Option<P>) {
-        if *self.price.get_offer() != offer {
-            self.price = BidOffer::new_with_price(*self.price.get_bid(), offer);
+    pub fn update_offer(&mut self, offer: Option<P>) -> Result<(), MarketDataError> {
+        let offer = self.validate_price(MarketSide::Offer, offer)?;
+        let (price, removed) = self.resolve_cross(
+            Some(MarketSide::Offer),
+            BidOffer::new_with_price(*self.price.get_bid(), offer),
+        )?;
+        self.expiry.touch(MarketSide::Offer);
+
+        if self.price != price {
+            self.price = price;
+            self.clear_removed_max(removed);
             self.publish_to_subscribers();
+            self.refresh_cross_status();
         }
+
+        Ok(())
     }
 
     /// Update the maximum size the bid is valid for
@@ -584,15 +1055,20 @@ where
     ///
     /// assert_eq!(*market_data.get_max_bid(), Some(40));
     ///
-    /// market_data.update_max_bid(Some(42));
+    /// market_data.update_max_bid(Some(42)).unwrap();
     ///
     /// assert_eq!(*market_data.get_max_bid(), Some(42));
     /// ```
-    pub fn update_max_bid(&mut self, max_bid: Option<A>) {
+    pub fn update_max_bid(&mut self, max_bid: Option<A>) -> Result<(), MarketDataError> {
+        let max_bid = self.validate_size(max_bid)?;
+        self.expiry.touch(MarketSide::Bid);
+
         if *self.max.get_bid() != max_bid {
             self.max = BidOffer::new_with_price(max_bid, *self.max.get_offer());
             self.publish_to_subscribers();
         }
+
+        Ok(())
     }
 
     /// Update the maximum size the offer is valid for
@@ -610,15 +1086,20 @@ where
     ///
     /// assert_eq!(*market_data.get_max_offer(), Some(50));
     ///
-    /// market_data.update_max_offer(Some(52));
+    /// market_data.update_max_offer(Some(52)).unwrap();
     ///
     /// assert_eq!(*market_data.get_max_offer(), Some(52));
     /// ```
-    pub fn update_max_offer(&mut self, max_offer: Option<A>) {
+    pub fn update_max_offer(&mut self, max_offer: Option<A>) -> Result<(), MarketDataError> {
+        let max_offer = self.validate_size(max_offer)?;
+        self.expiry.touch(MarketSide::Offer);
+
         if *self.max.get_offer() != max_offer {
             self.max = BidOffer::new_with_price(*self.max.get_bid(), max_offer);
             self.publish_to_subscribers();
         }
+
+        Ok(())
     }
 
     /// Update both the bid and offer prices
@@ -638,16 +1119,25 @@ where
     /// assert_eq!(*market_data.get_bid(), Some(10));
     /// assert_eq!(*market_data.get_offer(), Some(20));
     ///
-    /// market_data.update(Some(12), Some(22));
+    /// market_data.update(Some(12), Some(22)).unwrap();
     ///
     /// assert_eq!(*market_data.get_bid(), Some(12));
     /// assert_eq!(*market_data.get_offer(), Some(22));
     /// ```
-    pub fn update(&mut self, bid: Option<P>, offer: Option<P>) {
-        if *self.price.get_bid() != bid || *self.price.get_offer() != offer {
-            self.price = BidOffer::new_with_price(bid, offer);
+    pub fn update(&mut self, bid: Option<P>, offer: Option<P>) -> Result<(), MarketDataError> {
+        let bid = self.validate_price(MarketSide::Bid, bid)?;
+        let offer = self.validate_price(MarketSide::Offer, offer)?;
+        let (price, _) = self.resolve_cross(None, BidOffer::new_with_price(bid, offer))?;
+        self.expiry.touch(MarketSide::Bid);
+        self.expiry.touch(MarketSide::Offer);
+
+        if self.price != price {
+            self.price = price;
             self.publish_to_subscribers();
+            self.refresh_cross_status();
         }
+
+        Ok(())
     }
 
     /// Update both the bid and offer prices using a bid/offer structure
@@ -666,16 +1156,13 @@ where
     /// assert_eq!(*market_data.get_bid(), Some(10));
     /// assert_eq!(*market_data.get_offer(), Some(20));
     ///
-    /// market_data.update_price(BidOffer::new_with_price(Some(12), Some(22)));
+    /// market_data.update_price(BidOffer::new_with_price(Some(12), Some(22))).unwrap();
     ///
     /// assert_eq!(*market_data.get_bid(), Some(12));
     /// assert_eq!(*market_data.get_offer(), Some(22));
     /// ```
-    pub fn update_price(&mut self, price: BidOffer<P>) {
-        if self.price != price {
-            self.price = price;
-            self.publish_to_subscribers();
-        }
+    pub fn update_price(&mut self, price: BidOffer<P>) -> Result<(), MarketDataError> {
+        self.update(*price.get_bid(), *price.get_offer())
     }
 
     /// Update the price and maximum sizes in a single call
@@ -699,7 +1186,7 @@ where
     /// assert_eq!(*market_data.get_max_bid(), Some(40));
     /// assert_eq!(*market_data.get_max_offer(), Some(50));
     ///
-    /// market_data.update_with_max(Some(12), Some(22), Some(42), Some(52));
+    /// market_data.update_with_max(Some(12), Some(22), Some(42), Some(52)).unwrap();
     ///
     /// assert_eq!(*market_data.get_bid(), Some(12));
     /// assert_eq!(*market_data.get_offer(), Some(22));
@@ -712,16 +1199,26 @@ where
         offer: Option<P>,
         max_bid: Option<A>,
         max_offer: Option<A>,
-    ) {
-        if *self.price.get_bid() != bid
-            || *self.price.get_offer() != offer
+    ) -> Result<(), MarketDataError> {
+        let bid = self.validate_price(MarketSide::Bid, bid)?;
+        let offer = self.validate_price(MarketSide::Offer, offer)?;
+        let max_bid = self.validate_size(max_bid)?;
+        let max_offer = self.validate_size(max_offer)?;
+        let (price, _) = self.resolve_cross(None, BidOffer::new_with_price(bid, offer))?;
+        self.expiry.touch(MarketSide::Bid);
+        self.expiry.touch(MarketSide::Offer);
+
+        if self.price != price
             || *self.max.get_bid() != max_bid
             || *self.max.get_offer() != max_offer
         {
-            self.price = BidOffer::new_with_price(bid, offer);
+            self.price = price;
             self.max = BidOffer::new_with_price(max_bid, max_offer);
             self.publish_to_subscribers();
+            self.refresh_cross_status();
         }
+
+        Ok(())
     }
 
     /// Update both the bid and offer max size using a bid/offer structure
@@ -740,16 +1237,23 @@ where
     /// assert_eq!(*market_data.get_max_bid(), Some(40));
     /// assert_eq!(*market_data.get_max_offer(), Some(50));
     ///
-    /// market_data.update_max(BidOffer::new_with_price(Some(42), Some(52)));
+    /// market_data.update_max(BidOffer::new_with_price(Some(42), Some(52))).unwrap();
     ///
     /// assert_eq!(*market_data.get_max_bid(), Some(42));
     /// assert_eq!(*market_data.get_max_offer(), Some(52));
     /// ```
-    pub fn update_max(&mut self, max: BidOffer<A>) {
-        if self.max != max {
-            self.max = max;
+    pub fn update_max(&mut self, max: BidOffer<A>) -> Result<(), MarketDataError> {
+        let max_bid = self.validate_size(*max.get_bid())?;
+        let max_offer = self.validate_size(*max.get_offer())?;
+        self.expiry.touch(MarketSide::Bid);
+        self.expiry.touch(MarketSide::Offer);
+
+        if *self.max.get_bid() != max_bid || *self.max.get_offer() != max_offer {
+            self.max = BidOffer::new_with_price(max_bid, max_offer);
             self.publish_to_subscribers();
         }
+
+        Ok(())
     }
 
     /// Update the both the price and the max sizes using bid/offer structures
@@ -771,19 +1275,24 @@ where
     /// assert_eq!(*market_data.get_max_bid(), Some(40));
     /// assert_eq!(*market_data.get_max_offer(), Some(50));
     ///
-    /// market_data.update_price_with_max(BidOffer::new_with_price(Some(12),Some(22)),BidOffer::new_with_price(Some(42),Some(52)));
+    /// market_data.update_price_with_max(BidOffer::new_with_price(Some(12),Some(22)),BidOffer::new_with_price(Some(42),Some(52))).unwrap();
     ///
     /// assert_eq!(*market_data.get_bid(), Some(12));
     /// assert_eq!(*market_data.get_offer(), Some(22));
     /// assert_eq!(*market_data.get_max_bid(), Some(42));
     /// assert_eq!(*market_data.get_max_offer(), Some(52));
     /// ```
-    pub fn update_price_with_max(&mut self, price: BidOffer<P>, max: BidOffer<A>) {
-        if self.price != price || self.max != max {
-            self.price = price;
-            self.max = max;
-            self.publish_to_subscribers();
-        }
+    pub fn update_price_with_max(
+        &mut self,
+        price: BidOffer<P>,
+        max: BidOffer<A>,
+    ) -> Result<(), MarketDataError> {
+        self.update_with_max(
+            *price.get_bid(),
+            *price.get_offer(),
+            *max.get_bid(),
+            *max.get_offer(),
+        )
     }
 
     /// Clears the data structure setting bid/off and the max sizes all to None
@@ -816,11 +1325,13 @@ where
             self.price = BidOffer::new();
             self.max = BidOffer::new();
             self.publish_to_subscribers();
+            self.refresh_cross_status();
         }
     }
 
     /// Returns the price for the size passed in.  For an L1 market this is pretty straight forward, as the bid/offer
-    /// returned is the current bid/offer unless the size exceeds the max size.
+    /// returned is the current bid/offer unless the size exceeds the max size, or the market status does not have firm
+    /// prices.
     ///
     /// # Parameters
     ///
@@ -842,17 +1353,17 @@ where
     /// assert_eq!(market_data.get_price(55), BidOffer::new_with_price(None, None));
     /// ```
     pub fn get_price(&self, size: A) -> BidOffer<P> {
+        if !self.session.get_status().has_firm_prices() {
+            return BidOffer::new();
+        }
+
         BidOffer::new_with_price(
-            if self.max.get_bid().map_or(true, |max_size| max_size >= size) {
+            if self.max.get_bid().is_none_or(|max_size| max_size >= size) {
                 *self.price.get_bid()
             } else {
                 None
             },
-            if self
-                .max
-                .get_offer()
-                .map_or(true, |max_size| max_size >= size)
-            {
+            if self.max.get_offer().is_none_or(|max_size| max_size >= size) {
                 *self.price.get_offer()
             } else {
                 None
@@ -860,44 +1371,345 @@ where
         )
     }
 
-    /// Subscribe to changes to the pricing, and is only called if the pricing actually changes (i.e. updating with the current
-    /// value will not trigger the subscription)  NOTE: this will occur in the same thread as the caller, so make sure that this
-    /// does not cause a recursion issue.
+    /// Set the instrument specification that prices and max sizes are validated against on update, in place of the
+    /// specification of any attached instrument.  A value of None means the instrument's specification is used.
     ///
     /// # Parameters
     ///
-    /// * `callback` - The object which implements the L1MarketCallback trait to callback on
+    /// * `spec` - The instrument specification
     ///
     /// # Example
     ///
     /// ```
-    /// use std::cell::RefCell;
-    /// use std::rc::Rc;
-    /// use pricing::market_data::{L1MarketCallback, L1MarketDataWithMax};
+    /// use pricing::market_data::{InstrumentSpec, L1MarketDataWithMax, MarketDataError, SpecPolicy, TickSize};
     ///
-    /// struct TestCallback {
-    ///     called: RefCell<bool>,
-    /// }
+    /// let mut market_data = L1MarketDataWithMax::new();
     ///
-    /// impl TestCallback {
-    ///     fn new() -> Self {
-    ///         Self {
-    ///             called: RefCell::new(false),
-    ///         }
-    ///     }
+    /// market_data.set_instrument_spec(Some(
+    ///     InstrumentSpec::new(SpecPolicy::Snap)
+    ///         .with_tick_size(TickSize::Fixed(5))
+    ///         .with_lot_size(100),
+    /// ));
     ///
-    ///     fn reset(&self) {
-    ///         *self.called.borrow_mut() = false;
-    ///     }
+    /// assert_eq!(market_data.update_with_max(Some(11), Some(14), Some(250), Some(300)), Ok(()));
+    /// assert_eq!(*market_data.get_bid(), Some(10));
+    /// assert_eq!(*market_data.get_offer(), Some(15));
+    /// assert_eq!(*market_data.get_max_bid(), Some(200));
+    /// assert_eq!(*market_data.get_max_offer(), Some(300));
+    /// ```
+    pub fn set_instrument_spec(&mut self, spec: Option<InstrumentSpec<P, A>>) {
+        self.reference.set_book_spec(spec);
+    }
+
+    /// Get the instrument specification set on the book, which prices and max sizes are validated against in place
+    /// of that of any attached instrument
+    pub fn get_instrument_spec(&self) -> &Option<InstrumentSpec<P, A>> {
+        self.reference.get_book_spec()
+    }
+
+    /// Attach the reference data of the instrument the book is pricing.  Updates are validated against the specification
+    /// of the instrument unless one has been set on the book, which takes precedence.
     ///
-    ///     fn is_called(&self) -> bool {
-    ///         *self.called.borrow()
-    ///     }
-    /// }
+    /// # Parameters
     ///
-    /// impl L1MarketCallback for TestCallback {
-    ///     fn market_updated(&self) {
-    ///         *self.called.borrow_mut() = true;
+    /// * `instrument` - The instrument, a value of None detaches the instrument
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use pricing::market_data::{
+    ///     AssetClass, Instrument, InstrumentSpec, L1MarketDataWithMax, MarketDataError, SpecPolicy, TickSize,
+    /// };
+    ///
+    /// let mut market_data = L1MarketDataWithMax::<_, i32>::new();
+    ///
+    /// market_data.set_instrument(Some(Rc::new(
+    ///     Instrument::new("UST10Y", AssetClass::FixedIncome, "USD")
+    ///         .with_spec(InstrumentSpec::new(SpecPolicy::Reject).with_tick_size(TickSize::Fixed(5))),
+    /// )));
+    ///
+    /// assert_eq!(market_data.get_instrument().unwrap().get_symbol(), "UST10Y");
+    /// assert_eq!(market_data.update(Some(11), Some(15)), Err(MarketDataError::OffTickPrice));
+    /// ```
+    pub fn set_instrument(&mut self, instrument: Option<Rc<Instrument<P, A>>>) {
+        self.reference.set_instrument(instrument);
+    }
+
+    /// Get the reference data of the instrument the book is pricing
+    pub fn get_instrument(&self) -> Option<&Rc<Instrument<P, A>>> {
+        self.reference.get_instrument()
+    }
+
+    /// Set what happens to an update which would leave the bid above the offer, which defaults to keeping it and flagging
+    /// the book as crossed
+    ///
+    /// # Parameters
+    ///
+    /// * `policy` - The policy for crossing updates
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{CrossPolicy, CrossStatus, L1MarketDataWithMax, MarketDataError};
+    ///
+    /// let mut market_data = L1MarketDataWithMax::<i32, i32>::new_with_price(Some(10), Some(12));
+    ///
+    /// market_data.set_cross_policy(CrossPolicy::Reject);
+    /// assert_eq!(market_data.update_bid(Some(13)), Err(MarketDataError::CrossedBook));
+    ///
+    /// market_data.set_cross_policy(CrossPolicy::RemoveStale);
+    /// market_data.update_bid(Some(13)).unwrap();
+    /// assert_eq!(*market_data.get_offer(), None);
+    ///
+    /// market_data.set_cross_policy(CrossPolicy::KeepAndFlag);
+    /// market_data.update_offer(Some(12)).unwrap();
+    /// assert_eq!(market_data.get_cross_status(), CrossStatus::Crossed);
+    /// ```
+    pub fn set_cross_policy(&mut self, policy: CrossPolicy) {
+        self.cross_policy = policy;
+    }
+
+    /// Get what happens to an update which would leave the bid above the offer
+    pub fn get_cross_policy(&self) -> CrossPolicy {
+        self.cross_policy
+    }
+
+    /// Get whether the bid and offer currently overlap
+    pub fn get_cross_status(&self) -> CrossStatus {
+        self.cross_status
+    }
+
+    /// Set the quality of the price on a side, for example to flag it as indicative.  Subscribers are notified if the
+    /// quality changes.
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the market
+    /// * `quality` - The quality of the price on the side
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, L1MarketDataWithMax, MarketSide, PriceQuality};
+    ///
+    /// let mut market_data = L1MarketDataWithMax::<i32, i32>::new_with_price(Some(10), Some(12));
+    ///
+    /// market_data.set_quality(MarketSide::Offer, PriceQuality::Indicative);
+    ///
+    /// let quoted = market_data.get_quoted_price();
+    /// assert!(quoted.is_firm(MarketSide::Bid));
+    /// assert!(!quoted.is_firm(MarketSide::Offer));
+    /// assert_eq!(quoted.get_firm_price(), BidOffer::new_with_price(Some(10), None));
+    /// ```
+    pub fn set_quality(&mut self, side: MarketSide, quality: PriceQuality) {
+        if self.quality.set(side, quality) {
+            self.publish_to_subscribers();
+        }
+    }
+
+    /// Get the quality of the price on a side, which is stale once `check_stale` has found the side expired and is limited
+    /// by the market status
+    pub fn get_quality(&self, side: MarketSide) -> PriceQuality {
+        self.session
+            .get_status()
+            .limit_quality(self.quality.get(side, self.expiry.is_stale(side)))
+    }
+
+    /// Returns the current pricing along with the quality of each side, whatever the market status
+    pub fn get_quoted_price(&self) -> QuotedPrice<P> {
+        QuotedPrice::new(
+            self.price,
+            self.get_quality(MarketSide::Bid),
+            self.get_quality(MarketSide::Offer),
+        )
+    }
+
+    /// Move the market to a status, for example from a status message from the venue.  Subscribers are notified if the
+    /// status changes.
+    ///
+    /// # Parameters
+    ///
+    /// * `status` - The status of the market
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, L1MarketDataWithMax, MarketDataError, MarketSide, MarketStatus, PriceQuality};
+    ///
+    /// let mut market_data = L1MarketDataWithMax::<i32, i32>::new_with_price(Some(10), Some(12));
+    ///
+    /// market_data.set_status(MarketStatus::Halted).unwrap();
+    /// assert_eq!(market_data.get_price(50), BidOffer::new());
+    /// assert_eq!(market_data.get_quality(MarketSide::Bid), PriceQuality::Indicative);
+    ///
+    /// assert_eq!(market_data.set_status(MarketStatus::PreOpen), Err(MarketDataError::InvalidTransition));
+    /// ```
+    pub fn set_status(&mut self, status: MarketStatus) -> Result<(), MarketDataError> {
+        if let Some(from) = self.session.transition(status)? {
+            self.publish_status(from, status);
+        }
+
+        Ok(())
+    }
+
+    /// Get the status of the market, which defaults to continuous trading
+    pub fn get_status(&self) -> MarketStatus {
+        self.session.get_status()
+    }
+
+    /// Set the schedule which `check_schedule` moves the market status through.  A value of None means the status only
+    /// changes with `set_status`.
+    pub fn set_schedule(&mut self, schedule: Option<TradingSchedule>) {
+        self.session.set_schedule(schedule);
+    }
+
+    /// Get the schedule the market status moves through
+    pub fn get_schedule(&self) -> Option<&TradingSchedule> {
+        self.session.get_schedule()
+    }
+
+    /// Moves the market to the status the schedule gives at the time passed in, notifying subscribers if it changes.  The
+    /// first check after the schedule is set moves straight to the status it gives.  After that a status the market
+    /// cannot move to is ignored.  A halted market only follows the schedule when it closes.
+    ///
+    /// # Parameters
+    ///
+    /// * `now` - The time to check the schedule against
+    ///
+    /// # Returns
+    ///
+    /// The new status if it has changed
+    pub fn check_schedule(&mut self, now: Duration) -> Option<MarketStatus> {
+        let from = self.session.check_schedule(now)?;
+        let status = self.session.get_status();
+
+        self.publish_status(from, status);
+
+        Some(status)
+    }
+
+    /// Set the clock used to timestamp updates, which defaults to the system clock
+    pub fn set_clock(&mut self, clock: Rc<dyn Clock>) {
+        self.expiry.set_clock(clock);
+    }
+
+    /// Set how long the pricing on a side is valid for after it is updated.  A value of None means it never expires.
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the market
+    /// * `ttl` - The time to live
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use std::time::Duration;
+    /// use pricing::market_data::{BidOffer, L1MarketDataWithMax, ManualClock, MarketSide};
+    ///
+    /// let clock = Rc::new(ManualClock::new(Duration::from_secs(100)));
+    /// let mut market_data = L1MarketDataWithMax::<i32, i32>::new();
+    ///
+    /// market_data.set_clock(clock.clone());
+    /// market_data.set_time_to_live(MarketSide::Bid, Some(Duration::from_secs(5)));
+    /// market_data.update(Some(10), Some(20)).unwrap();
+    ///
+    /// assert_eq!(market_data.get_price_at(Duration::from_secs(105)), BidOffer::new_with_price(Some(10), Some(20)));
+    /// assert_eq!(market_data.get_price_at(Duration::from_secs(106)), BidOffer::new_with_price(None, Some(20)));
+    /// ```
+    pub fn set_time_to_live(&mut self, side: MarketSide, ttl: Option<Duration>) {
+        self.expiry.set_time_to_live(side, ttl);
+    }
+
+    /// Get how long the pricing on a side is valid for after it is updated
+    pub fn get_time_to_live(&self, side: MarketSide) -> Option<Duration> {
+        self.expiry.get_time_to_live(side)
+    }
+
+    /// Get when a side was last updated, even if the update did not change the pricing
+    pub fn get_updated_time(&self, side: MarketSide) -> Option<Duration> {
+        self.expiry.get_updated(side)
+    }
+
+    /// Returns the current pricing with any side which has expired by the time passed in removed
+    ///
+    /// # Parameters
+    ///
+    /// * `now` - The time to check expiry against
+    pub fn get_price_at(&self, now: Duration) -> BidOffer<P> {
+        let side_price = |side, price: &Option<P>| {
+            if self.expiry.is_expired(side, now) {
+                None
+            } else {
+                *price
+            }
+        };
+
+        BidOffer::new_with_price(
+            side_price(MarketSide::Bid, self.price.get_bid()),
+            side_price(MarketSide::Offer, self.price.get_offer()),
+        )
+    }
+
+    /// Checks for sides which have expired by the time passed in, notifying subscribers of each side the first time it is
+    /// found to be stale.  A side stops being stale when it is next updated.
+    ///
+    /// # Parameters
+    ///
+    /// * `now` - The time to check expiry against
+    ///
+    /// # Returns
+    ///
+    /// The sides which have newly become stale
+    pub fn check_stale(&mut self, now: Duration) -> Vec<MarketSide> {
+        let stale = self.expiry.check_stale(now);
+
+        for &side in &stale {
+            self.publish_stale(side);
+        }
+
+        stale
+    }
+
+    /// Subscribe to changes to the pricing, and is only called if the pricing actually changes (i.e. updating with the current
+    /// value will not trigger the subscription)  NOTE: this will occur in the same thread as the caller, so make sure that this
+    /// does not cause a recursion issue.
+    ///
+    /// # Parameters
+    ///
+    /// * `callback` - The object which implements the L1MarketCallback trait to callback on
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    /// use pricing::market_data::{L1MarketCallback, L1MarketDataWithMax};
+    ///
+    /// struct TestCallback {
+    ///     called: RefCell<bool>,
+    /// }
+    ///
+    /// impl TestCallback {
+    ///     fn new() -> Self {
+    ///         Self {
+    ///             called: RefCell::new(false),
+    ///         }
+    ///     }
+    ///
+    ///     fn reset(&self) {
+    ///         *self.called.borrow_mut() = false;
+    ///     }
+    ///
+    ///     fn is_called(&self) -> bool {
+    ///         *self.called.borrow()
+    ///     }
+    /// }
+    ///
+    /// impl L1MarketCallback for TestCallback {
+    ///     fn market_updated(&self) {
+    ///         *self.called.borrow_mut() = true;
     ///     }
     /// }
     ///
@@ -905,26 +1717,104 @@ where
     /// let callback = Rc::new(TestCallback::new());
     /// test.subscribe(callback.clone());
     ///
-    /// test.update_bid(Some(10));
+    /// test.update_bid(Some(10)).unwrap();
     /// assert!(!callback.is_called());
-    /// test.update_bid(Some(9));
+    /// test.update_bid(Some(9)).unwrap();
     /// assert!(callback.is_called());
     /// ```
     pub fn subscribe(&self, callback: Rc<dyn L1MarketCallback>) {
         self.callbacks.borrow_mut().push(callback.clone());
     }
 
+    // Applies the cross policy to the price an update would leave, returning the price to use and the side removed as stale
+    fn resolve_cross(
+        &self,
+        side: Option<MarketSide>,
+        price: BidOffer<P>,
+    ) -> Result<(BidOffer<P>, Option<MarketSide>), MarketDataError> {
+        if !price.is_crossed() {
+            return Ok((price, None));
+        }
+
+        match (self.cross_policy, side) {
+            (CrossPolicy::Reject, _) => Err(MarketDataError::CrossedBook),
+            (CrossPolicy::RemoveStale, Some(MarketSide::Bid)) => Ok((
+                BidOffer::new_with_price(*price.get_bid(), None),
+                Some(MarketSide::Offer),
+            )),
+            (CrossPolicy::RemoveStale, Some(MarketSide::Offer)) => Ok((
+                BidOffer::new_with_price(None, *price.get_offer()),
+                Some(MarketSide::Bid),
+            )),
+            _ => Ok((price, None)),
+        }
+    }
+
+    fn refresh_cross_status(&mut self) {
+        let status = self.price.get_cross_status();
+
+        if status != self.cross_status {
+            self.cross_status = status;
+
+            for callback in self.callbacks.borrow().iter() {
+                callback.cross_status_changed(status);
+            }
+        }
+    }
+
+    fn clear_removed_max(&mut self, removed: Option<MarketSide>) {
+        match removed {
+            Some(MarketSide::Bid) => {
+                self.max = BidOffer::new_with_price(None, *self.max.get_offer())
+            }
+            Some(MarketSide::Offer) => {
+                self.max = BidOffer::new_with_price(*self.max.get_bid(), None)
+            }
+            None => {}
+        }
+    }
+
+    fn validate_price(
+        &self,
+        side: MarketSide,
+        price: Option<P>,
+    ) -> Result<Option<P>, MarketDataError> {
+        match (self.reference.get_spec(), price) {
+            (Some(spec), Some(price)) => spec.validate_price(&side, price).map(Some),
+            _ => Ok(price),
+        }
+    }
+
+    fn validate_size(&self, size: Option<A>) -> Result<Option<A>, MarketDataError> {
+        match (self.reference.get_spec(), size) {
+            (Some(spec), Some(size)) => spec.validate_size(size).map(Some),
+            _ => Ok(size),
+        }
+    }
+
     fn publish_to_subscribers(&self) {
         for callback in self.callbacks.borrow().iter() {
             callback.market_updated();
         }
     }
+
+    pub(crate) fn publish_stale(&self, side: MarketSide) {
+        for callback in self.callbacks.borrow().iter() {
+            callback.market_stale(side);
+        }
+    }
+
+    fn publish_status(&self, from: MarketStatus, to: MarketStatus) {
+        for callback in self.callbacks.borrow().iter() {
+            callback.status_changed(from, to);
+        }
+    }
 }
 
 impl<P, A> Default for L1MarketDataWithMax<P, A>
 where
-    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<i32>,
-    A: Copy + PartialOrd + Add<Output = A> + Div<Output = A> + From<i32>,
+    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<u8> + Increment,
+    A: Copy + PartialOrd + Add<Output = A> + Div<Output = A> + From<u8> + Increment,
 {
     fn default() -> Self {
         Self::new()
@@ -936,34 +1826,9 @@ mod tests {
     use std::{cell::RefCell, rc::Rc};
 
     use super::*;
+    use crate::market_data::test_callback::TestCallback;
     use crate::market_data::BidOffer;
 
-    struct TestCallback {
-        called: RefCell<bool>,
-    }
-
-    impl TestCallback {
-        fn new() -> Self {
-            Self {
-                called: RefCell::new(false),
-            }
-        }
-
-        fn reset(&self) {
-            *self.called.borrow_mut() = false;
-        }
-
-        fn is_called(&self) -> bool {
-            *self.called.borrow()
-        }
-    }
-
-    impl L1MarketCallback for TestCallback {
-        fn market_updated(&self) {
-            *self.called.borrow_mut() = true;
-        }
-    }
-
     #[test]
     fn default() {
         let test = L1MarketData::<i32>::default();
@@ -1050,15 +1915,15 @@ mod tests {
         let callback = Rc::new(TestCallback::new());
         test.subscribe(callback.clone());
 
-        test.update_bid(Some(10));
+        test.update_bid(Some(10)).unwrap();
         assert!(!callback.is_called());
-        test.update_bid(Some(9));
+        test.update_bid(Some(9)).unwrap();
         assert!(callback.is_called());
 
         callback.reset();
-        test.update_offer(Some(10));
+        test.update_offer(Some(10)).unwrap();
         assert!(!callback.is_called());
-        test.update_offer(Some(19));
+        test.update_offer(Some(19)).unwrap();
         assert!(callback.is_called());
     }
 
@@ -1069,27 +1934,27 @@ mod tests {
         let callback = Rc::new(TestCallback::new());
         test.subscribe(callback.clone());
 
-        test.update_bid(Some(10));
+        test.update_bid(Some(10)).unwrap();
         assert!(!callback.is_called());
-        test.update_bid(Some(9));
+        test.update_bid(Some(9)).unwrap();
         assert!(callback.is_called());
 
         callback.reset();
-        test.update_offer(Some(10));
+        test.update_offer(Some(10)).unwrap();
         assert!(!callback.is_called());
-        test.update_offer(Some(19));
+        test.update_offer(Some(19)).unwrap();
         assert!(callback.is_called());
 
         callback.reset();
-        test.update_max_bid(Some(60));
+        test.update_max_bid(Some(60)).unwrap();
         assert!(!callback.is_called());
-        test.update_max_bid(Some(59));
+        test.update_max_bid(Some(59)).unwrap();
         assert!(callback.is_called());
 
         callback.reset();
-        test.update_max_offer(Some(70));
+        test.update_max_offer(Some(70)).unwrap();
         assert!(!callback.is_called());
-        test.update_max_offer(Some(71));
+        test.update_max_offer(Some(71)).unwrap();
         assert!(callback.is_called());
     }
 
@@ -1100,21 +1965,24 @@ mod tests {
         let callback = Rc::new(TestCallback::new());
         test.subscribe(callback.clone());
 
-        test.update(Some(10), Some(10));
+        test.update(Some(10), Some(10)).unwrap();
         assert!(!callback.is_called());
-        test.update(Some(12), Some(10));
+        test.update(Some(12), Some(10)).unwrap();
         assert!(callback.is_called());
         callback.reset();
-        test.update(Some(12), Some(12));
+        test.update(Some(12), Some(12)).unwrap();
         assert!(callback.is_called());
 
         callback.reset();
-        test.update_price(BidOffer::new_with_price(Some(12), Some(12)));
+        test.update_price(BidOffer::new_with_price(Some(12), Some(12)))
+            .unwrap();
         assert!(!callback.is_called());
-        test.update_price(BidOffer::new_with_price(Some(11), Some(12)));
+        test.update_price(BidOffer::new_with_price(Some(11), Some(12)))
+            .unwrap();
         assert!(callback.is_called());
         callback.reset();
-        test.update_price(BidOffer::new_with_price(Some(11), Some(11)));
+        test.update_price(BidOffer::new_with_price(Some(11), Some(11)))
+            .unwrap();
         assert!(callback.is_called());
     }
 
@@ -1125,60 +1993,102 @@ mod tests {
         let callback = Rc::new(TestCallback::new());
         test.subscribe(callback.clone());
 
-        test.update(Some(10), Some(10));
+        test.update(Some(10), Some(10)).unwrap();
         assert!(!callback.is_called());
-        test.update(Some(12), Some(10));
+        test.update(Some(12), Some(10)).unwrap();
         assert!(callback.is_called());
         callback.reset();
-        test.update(Some(12), Some(12));
+        test.update(Some(12), Some(12)).unwrap();
         assert!(callback.is_called());
 
         callback.reset();
-        test.update_price(BidOffer::new_with_price(Some(12), Some(12)));
+        test.update_price(BidOffer::new_with_price(Some(12), Some(12)))
+            .unwrap();
         assert!(!callback.is_called());
-        test.update_price(BidOffer::new_with_price(Some(11), Some(12)));
+        test.update_price(BidOffer::new_with_price(Some(11), Some(12)))
+            .unwrap();
         assert!(callback.is_called());
         callback.reset();
-        test.update_price(BidOffer::new_with_price(Some(11), Some(11)));
+        test.update_price(BidOffer::new_with_price(Some(11), Some(11)))
+            .unwrap();
         assert!(callback.is_called());
 
         callback.reset();
-        test.update_max(BidOffer::new_with_price(Some(60), Some(70)));
+        test.update_max(BidOffer::new_with_price(Some(60), Some(70)))
+            .unwrap();
         assert!(!callback.is_called());
-        test.update_max(BidOffer::new_with_price(Some(61), Some(70)));
+        test.update_max(BidOffer::new_with_price(Some(61), Some(70)))
+            .unwrap();
         assert!(callback.is_called());
         callback.reset();
-        test.update_max(BidOffer::new_with_price(Some(61), Some(71)));
+        test.update_max(BidOffer::new_with_price(Some(61), Some(71)))
+            .unwrap();
         assert!(callback.is_called());
 
         callback.reset();
         test.update_price_with_max(
             BidOffer::new_with_price(Some(11), Some(11)),
             BidOffer::new_with_price(Some(61), Some(71)),
-        );
+        )
+        .unwrap();
         assert!(!callback.is_called());
         test.update_price_with_max(
             BidOffer::new_with_price(Some(12), Some(11)),
             BidOffer::new_with_price(Some(61), Some(71)),
-        );
+        )
+        .unwrap();
         assert!(callback.is_called());
         callback.reset();
         test.update_price_with_max(
             BidOffer::new_with_price(Some(12), Some(12)),
             BidOffer::new_with_price(Some(61), Some(71)),
-        );
+        )
+        .unwrap();
         assert!(callback.is_called());
         callback.reset();
         test.update_price_with_max(
             BidOffer::new_with_price(Some(12), Some(12)),
             BidOffer::new_with_price(Some(62), Some(71)),
-        );
+        )
+        .unwrap();
         assert!(callback.is_called());
         callback.reset();
         test.update_price_with_max(
             BidOffer::new_with_price(Some(12), Some(12)),
             BidOffer::new_with_price(Some(62), Some(72)),
+        )
+        .unwrap();
+        assert!(callback.is_called());
+    }
+
+    #[test]
+    fn instrument_spec_rejected_update_not_published() {
+        let mut test = L1MarketDataWithMax::new_with_max(Some(10), Some(20), Some(100), Some(100));
+
+        test.set_instrument_spec(Some(
+            InstrumentSpec::new(crate::market_data::SpecPolicy::Reject)
+                .with_tick_size(crate::market_data::TickSize::Fixed(5))
+                .with_min_size(50),
+        ));
+
+        let callback = Rc::new(TestCallback::new());
+        test.subscribe(callback.clone());
+
+        assert_eq!(
+            test.update(Some(12), Some(20)),
+            Err(MarketDataError::OffTickPrice)
+        );
+        assert_eq!(
+            test.update_max(BidOffer::new_with_price(Some(40), Some(100))),
+            Err(MarketDataError::BelowMinimumSize)
+        );
+        assert!(!callback.is_called());
+        assert_eq!(
+            test.get_price(100),
+            BidOffer::new_with_price(Some(10), Some(20))
         );
+
+        assert_eq!(test.update(Some(15), None), Ok(()));
         assert!(callback.is_called());
     }
 
@@ -1192,18 +2102,18 @@ mod tests {
         test.clear();
         assert!(!callback.is_called());
 
-        test.update(Some(10), Some(10));
+        test.update(Some(10), Some(10)).unwrap();
         callback.reset();
 
         test.clear();
         assert!(callback.is_called());
 
-        test.update(Some(10), None);
+        test.update(Some(10), None).unwrap();
         callback.reset();
         test.clear();
         assert!(callback.is_called());
 
-        test.update(None, Some(10));
+        test.update(None, Some(10)).unwrap();
         callback.reset();
         test.clear();
         assert!(callback.is_called());
@@ -1219,30 +2129,246 @@ mod tests {
         test.clear();
         assert!(!callback.is_called());
 
-        test.update_with_max(Some(10), Some(10), Some(60), Some(70));
+        test.update_with_max(Some(10), Some(10), Some(60), Some(70))
+            .unwrap();
         callback.reset();
 
         test.clear();
         assert!(callback.is_called());
 
-        test.update_with_max(Some(10), None, None, None);
+        test.update_with_max(Some(10), None, None, None).unwrap();
         callback.reset();
         test.clear();
         assert!(callback.is_called());
 
-        test.update_with_max(None, Some(10), None, None);
+        test.update_with_max(None, Some(10), None, None).unwrap();
         callback.reset();
         test.clear();
         assert!(callback.is_called());
 
-        test.update_with_max(None, None, Some(10), None);
+        test.update_with_max(None, None, Some(10), None).unwrap();
         callback.reset();
         test.clear();
         assert!(callback.is_called());
 
-        test.update_with_max(None, None, None, Some(10));
+        test.update_with_max(None, None, None, Some(10)).unwrap();
         callback.reset();
         test.clear();
         assert!(callback.is_called());
     }
+
+    struct StaleCallback {
+        stale: RefCell<Vec<MarketSide>>,
+    }
+
+    impl L1MarketCallback for StaleCallback {
+        fn market_updated(&self) {}
+
+        fn market_stale(&self, side: MarketSide) {
+            self.stale.borrow_mut().push(side);
+        }
+    }
+
+    #[test]
+    fn stale_triggers_subscriptions() {
+        let clock = Rc::new(crate::market_data::ManualClock::new(Duration::from_secs(
+            100,
+        )));
+        let mut test = L1MarketDataWithMax::<i32, i32>::new();
+        let callback = Rc::new(StaleCallback {
+            stale: RefCell::new(Vec::new()),
+        });
+
+        test.set_clock(clock.clone());
+        test.set_time_to_live(MarketSide::Bid, Some(Duration::from_secs(5)));
+        test.set_time_to_live(MarketSide::Offer, Some(Duration::from_secs(10)));
+        test.subscribe(callback.clone());
+        test.update(Some(10), Some(12)).unwrap();
+
+        clock.advance(Duration::from_secs(4));
+        test.update_max_offer(Some(100)).unwrap();
+        assert_eq!(
+            test.get_updated_time(MarketSide::Offer),
+            Some(Duration::from_secs(104))
+        );
+
+        assert_eq!(test.check_stale(Duration::from_secs(105)), vec![]);
+        assert_eq!(
+            test.check_stale(Duration::from_secs(106)),
+            vec![MarketSide::Bid]
+        );
+        assert_eq!(
+            test.check_stale(Duration::from_secs(120)),
+            vec![MarketSide::Offer]
+        );
+        assert_eq!(test.check_stale(Duration::from_secs(130)), vec![]);
+        assert_eq!(
+            *callback.stale.borrow(),
+            vec![MarketSide::Bid, MarketSide::Offer]
+        );
+        assert_eq!(test.get_price_at(Duration::from_secs(130)), BidOffer::new());
+
+        clock.set(Duration::from_secs(130));
+        test.update_bid(Some(10)).unwrap();
+        assert_eq!(
+            test.get_price_at(Duration::from_secs(130)),
+            BidOffer::new_with_price(Some(10), None)
+        );
+        assert_eq!(
+            test.check_stale(Duration::from_secs(136)),
+            vec![MarketSide::Bid]
+        );
+    }
+
+    struct CrossCallback {
+        statuses: RefCell<Vec<CrossStatus>>,
+    }
+
+    impl L1MarketCallback for CrossCallback {
+        fn market_updated(&self) {}
+
+        fn cross_status_changed(&self, status: CrossStatus) {
+            self.statuses.borrow_mut().push(status);
+        }
+    }
+
+    #[test]
+    fn cross_status_notifies_subscribers() {
+        let mut test = L1MarketData::<i32>::new_with_price(Some(10), Some(12));
+        let callback = Rc::new(CrossCallback {
+            statuses: RefCell::new(Vec::new()),
+        });
+        test.subscribe(callback.clone());
+
+        test.update_bid(Some(12)).unwrap();
+        test.update_bid(Some(13)).unwrap();
+        test.update_bid(Some(14)).unwrap();
+        test.update_offer(Some(15)).unwrap();
+
+        assert_eq!(
+            *callback.statuses.borrow(),
+            vec![
+                CrossStatus::Locked,
+                CrossStatus::Crossed,
+                CrossStatus::Normal
+            ]
+        );
+    }
+
+    #[test]
+    fn cross_policies() {
+        let mut test =
+            L1MarketDataWithMax::<i32, i32>::new_with_max(Some(10), Some(12), Some(50), Some(60));
+
+        test.set_cross_policy(CrossPolicy::Reject);
+        assert_eq!(
+            test.update(Some(13), Some(12)),
+            Err(MarketDataError::CrossedBook)
+        );
+        assert_eq!(test.update_bid(Some(12)), Ok(()));
+        assert_eq!(test.get_cross_status(), CrossStatus::Locked);
+
+        test.set_cross_policy(CrossPolicy::RemoveStale);
+        test.update_offer(Some(11)).unwrap();
+        assert_eq!(test.get_price(10), BidOffer::new_with_price(None, Some(11)));
+        assert_eq!(*test.get_max_bid(), None);
+        assert_eq!(*test.get_max_offer(), Some(60));
+        assert_eq!(test.get_cross_status(), CrossStatus::Normal);
+
+        // Both sides updating together leaves no stale side to remove
+        test.update(Some(13), Some(12)).unwrap();
+        assert_eq!(test.get_cross_status(), CrossStatus::Crossed);
+    }
+
+    #[test]
+    fn quality() {
+        let clock = Rc::new(crate::market_data::ManualClock::new(Duration::from_secs(
+            100,
+        )));
+        let mut test = L1MarketData::<i32>::new();
+        let callback = Rc::new(TestCallback::new());
+
+        test.set_clock(clock.clone());
+        test.set_time_to_live(MarketSide::Bid, Some(Duration::from_secs(5)));
+        test.update(Some(10), Some(12)).unwrap();
+        test.subscribe(callback.clone());
+
+        test.set_quality(MarketSide::Offer, PriceQuality::Indicative);
+        assert!(callback.is_called());
+        callback.reset();
+        test.set_quality(MarketSide::Offer, PriceQuality::Indicative);
+        assert!(!callback.is_called());
+
+        test.check_stale(Duration::from_secs(106));
+        assert_eq!(test.get_quality(MarketSide::Bid), PriceQuality::Stale);
+        assert_eq!(test.get_quoted_price().get_firm_price(), BidOffer::new());
+
+        // An update clears the staleness but not the quality set on the side
+        test.update(Some(11), Some(12)).unwrap();
+        assert_eq!(test.get_quality(MarketSide::Bid), PriceQuality::Firm);
+        assert_eq!(
+            test.get_quoted_price().get_firm_price(),
+            BidOffer::new_with_price(Some(11), None)
+        );
+    }
+
+    struct StatusCallback {
+        transitions: RefCell<Vec<(MarketStatus, MarketStatus)>>,
+    }
+
+    impl L1MarketCallback for StatusCallback {
+        fn market_updated(&self) {}
+
+        fn status_changed(&self, from: MarketStatus, to: MarketStatus) {
+            self.transitions.borrow_mut().push((from, to));
+        }
+    }
+
+    #[test]
+    fn status_notifies_subscribers() {
+        let mut test =
+            L1MarketDataWithMax::<i32, i32>::new_with_max(Some(10), Some(12), Some(50), Some(50));
+        let callback = Rc::new(StatusCallback {
+            transitions: RefCell::new(Vec::new()),
+        });
+        test.subscribe(callback.clone());
+
+        test.set_status(MarketStatus::Halted).unwrap();
+        test.set_status(MarketStatus::Halted).unwrap();
+        assert_eq!(test.get_price(10), BidOffer::new());
+        assert_eq!(
+            test.get_quoted_price().get_price(),
+            BidOffer::new_with_price(Some(10), Some(12))
+        );
+
+        test.set_status(MarketStatus::Closed).unwrap();
+        assert_eq!(
+            test.set_status(MarketStatus::Halted),
+            Err(MarketDataError::InvalidTransition)
+        );
+        assert_eq!(test.get_quality(MarketSide::Offer), PriceQuality::Closed);
+
+        test.set_schedule(Some(TradingSchedule::new(vec![(
+            Duration::from_secs(8 * 60 * 60),
+            MarketStatus::Continuous,
+        )])));
+        assert_eq!(
+            test.check_schedule(Duration::from_secs(9 * 60 * 60)),
+            Some(MarketStatus::Continuous)
+        );
+        assert_eq!(test.check_schedule(Duration::from_secs(10 * 60 * 60)), None);
+        assert_eq!(
+            test.get_price(10),
+            BidOffer::new_with_price(Some(10), Some(12))
+        );
+
+        assert_eq!(
+            *callback.transitions.borrow(),
+            vec![
+                (MarketStatus::Continuous, MarketStatus::Halted),
+                (MarketStatus::Halted, MarketStatus::Closed),
+                (MarketStatus::Closed, MarketStatus::Continuous),
+            ]
+        );
+    }
 }
@@ -1,191 +1,222 @@
-use super::{BidOffer, MarketSide, UpdateAction};
+use super::{
+    expiry::QuoteExpiry, fair_value, instrument::InstrumentReference, metrics,
+    quality::SideQuality, session::MarketSession, vwap, BidOffer, Clock, CrossPolicy, CrossStatus,
+    FloatConvert, Increment, IncrementalBook, Instrument, InstrumentSpec, L1MarketCallback,
+    L1MarketDataWithMax, MarketDataError, MarketImpact, MarketSide, MarketStatus, OneSidedPolicy,
+    PriceQuality, QuotedPrice, TradingSchedule, UpdateAction, UpdateMode, VwapAmount,
+};
 use std::{
+    cell::RefCell,
     collections::BTreeMap,
-    ops::{Add, Div, Mul, Sub},
+    ops::{Add, Div, Sub},
+    rc::Rc,
+    time::Duration,
 };
 
-/// A structure to hold L2 pricing which is Sweepable.  This means that it is that the price for any given size
-/// is the VWAP of the levels required to obtain that size.
+/// Called with the changes to the price levels of a book
+pub trait L2MarketCallback<P, A> {
+    fn level_updated(&self, update: &L2Update<P, A>);
+}
+
+/// A change to a single price level of an L2 book.
 ///
 /// # Generic Parameters
 ///
-/// * `A` - The amount type that should be used.
 /// * `P` - The price type that should be used.
-pub struct L2SweepableMarketData<P, A>
-where
-    P: Copy
-        + PartialOrd
-        + Ord
-        + Add<Output = P>
-        + Mul<A, Output = A>
-        + Div<Output = P>
-        + Default
-        + From<i32>,
-    A: Copy
-        + PartialOrd
-        + Add<Output = A>
-        + Sub<Output = A>
-        + Div<P, Output = A>
-        + Div<A, Output = P>
-        + Default
-        + From<i32>,
-{
-    bids: BTreeMap<P, A>,
-    offers: BTreeMap<P, A>,
+/// * `A` - The amount type that should be used.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct L2Update<P, A> {
+    action: UpdateAction,
+    side: MarketSide,
+    price: P,
+    size: A,
 }
 
-impl<P, A> L2SweepableMarketData<P, A>
+impl<P, A> L2Update<P, A>
 where
-    P: Copy
-        + PartialOrd
-        + Ord
-        + Add<Output = P>
-        + Mul<A, Output = A>
-        + Div<Output = P>
-        + Default
-        + From<i32>,
-    A: Copy
-        + PartialOrd
-        + Add<Output = A>
-        + Sub<Output = A>
-        + Div<P, Output = A>
-        + Div<A, Output = P>
-        + Default
-        + From<i32>,
+    P: Copy,
+    A: Copy,
 {
-    /// Use the new function to create a new L2SweepableMarketData with no pricing.
+    /// Use the new function to create a new L2Update
+    ///
+    /// # Parameters
+    ///
+    /// * `action` - The update action
+    /// * `side` - The side of the market
+    /// * `price` - The price of the level
+    /// * `size` - The size of the level
     ///
     /// # Example
     ///
     /// ```
-    /// use pricing::market_data::L2SweepableMarketData;
+    /// use pricing::market_data::{L2Update, MarketSide, UpdateAction};
     ///
-    /// let market_data = L2SweepableMarketData::<i32, i32>::new();
+    /// let update = L2Update::new(UpdateAction::Add, MarketSide::Bid, 10, 100);
     ///
-    /// // TBC
+    /// assert_eq!(update.get_action(), UpdateAction::Add);
+    /// assert_eq!(update.get_side(), MarketSide::Bid);
+    /// assert_eq!(update.get_price(), 10);
+    /// assert_eq!(update.get_size(), 100);
     /// ```
-    pub fn new() -> Self {
+    pub fn new(action: UpdateAction, side: MarketSide, price: P, size: A) -> Self {
         Self {
-            bids: BTreeMap::new(),
-            offers: BTreeMap::new(),
+            action,
+            side,
+            price,
+            size,
         }
     }
 
-    pub fn update(&mut self, action: UpdateAction, side: MarketSide, price: P, size: A) {
-        let side_store = match side {
-            MarketSide::Bid => &mut self.bids,
-            MarketSide::Offer => &mut self.offers,
-        };
-
-        match action {
-            UpdateAction::Add => {
-                side_store.insert(price, size);
-            }
-            UpdateAction::Update => {
-                if let Some(value) = side_store.get_mut(&price) {
-                    *value = size
-                };
-            }
-            UpdateAction::Remove => {
-                side_store.remove(&price);
-            }
-        };
+    /// Get the update action
+    pub fn get_action(&self) -> UpdateAction {
+        self.action
     }
 
-    pub fn clear(&mut self) {
-        self.bids.clear();
-        self.offers.clear();
+    /// Get the side of the market
+    pub fn get_side(&self) -> MarketSide {
+        self.side
     }
 
-    pub fn get_price(&self, size: A) -> BidOffer<P> {
-        BidOffer::new_with_price(
-            self.calc_vwap(size, self.bids.iter().rev()),
-            self.calc_vwap(size, self.offers.iter()),
-        )
+    /// Get the price of the level
+    pub fn get_price(&self) -> P {
+        self.price
     }
 
-    fn calc_vwap<'a, I>(&self, size: A, iter: I) -> Option<P>
-    where
-        I: Iterator<Item = (&'a P, &'a A)>,
-        A: 'a,
-        P: 'a,
-    {
-        let mut current_size = A::default();
-        let mut current_total = A::default();
-
-        for (&next_price, &next_size) in iter {
-            let mut incremental_size = next_size;
-
-            if next_size + current_size > size {
-                incremental_size = size - current_size;
-            }
+    /// Get the size of the level
+    pub fn get_size(&self) -> A {
+        self.size
+    }
+}
 
-            current_total = current_total + (next_price * incremental_size);
-            current_size = current_size + incremental_size;
+// Applies an action to the levels of one side of a book held as a map for each side, which are keyed by price for
+// sweepable books and by size for full amount books
+fn apply_level<K, V>(
+    mode: UpdateMode,
+    bids: &mut BTreeMap<K, V>,
+    offers: &mut BTreeMap<K, V>,
+    action: UpdateAction,
+    side: MarketSide,
+    key: K,
+    value: V,
+) where
+    K: Ord,
+{
+    if action == UpdateAction::Snapshot {
+        bids.clear();
+        offers.clear();
+    }
 
-            // Should always be equal at this point but just in case of some weird rounding issues
-            if current_size >= size {
-                return Some(current_total / current_size);
-            }
-        }
+    let levels = match side {
+        MarketSide::Bid => bids,
+        MarketSide::Offer => offers,
+    };
 
-        None
+    if action == UpdateAction::ClearSide {
+        levels.clear();
+    } else if mode.leaves_level(action, levels.contains_key(&key)) {
+        levels.insert(key, value);
+    } else {
+        levels.remove(&key);
     }
 }
 
-impl<P, A> Default for L2SweepableMarketData<P, A>
+/// Calls back with the changes which turn one side of a book into another, in key order
+pub(crate) fn diff_side<K, V, F>(from: &BTreeMap<K, V>, to: &BTreeMap<K, V>, mut changed: F)
 where
-    P: Copy
-        + PartialOrd
-        + Ord
-        + Add<Output = P>
-        + Mul<A, Output = A>
-        + Div<Output = P>
-        + Default
-        + From<i32>,
-    A: Copy
-        + PartialOrd
-        + Add<Output = A>
-        + Sub<Output = A>
-        + Div<P, Output = A>
-        + Div<A, Output = P>
-        + Default
-        + From<i32>,
+    K: Ord + Copy,
+    V: PartialEq + Copy,
+    F: FnMut(UpdateAction, K, V),
 {
-    fn default() -> Self {
-        Self::new()
+    let mut from_iter = from.iter().peekable();
+    let mut to_iter = to.iter().peekable();
+
+    loop {
+        match (from_iter.peek(), to_iter.peek()) {
+            (Some(&(&from_key, &from_value)), Some(&(&to_key, &to_value))) => {
+                if from_key < to_key {
+                    changed(UpdateAction::Remove, from_key, from_value);
+                    from_iter.next();
+                } else if to_key < from_key {
+                    changed(UpdateAction::Add, to_key, to_value);
+                    to_iter.next();
+                } else {
+                    if from_value != to_value {
+                        changed(UpdateAction::Update, to_key, to_value);
+                    }
+                    from_iter.next();
+                    to_iter.next();
+                }
+            }
+            (Some(&(&from_key, &from_value)), None) => {
+                changed(UpdateAction::Remove, from_key, from_value);
+                from_iter.next();
+            }
+            (None, Some(&(&to_key, &to_value))) => {
+                changed(UpdateAction::Add, to_key, to_value);
+                to_iter.next();
+            }
+            (None, None) => break,
+        }
     }
 }
 
-/// A structure to hold L2 pricing which is Full Amount.  This means that it is that the price for any given size
-/// is the price publish for the size.
+/// A structure to hold L2 pricing which is Sweepable.  This means that it is that the price for any given size
+/// is the VWAP of the levels required to obtain that size.
 ///
 /// # Generic Parameters
 ///
 /// * `A` - The amount type that should be used.
 /// * `P` - The price type that should be used.
-pub struct L2FullAmountMarketData<P, A>
+pub struct L2SweepableMarketData<P, A>
 where
-    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<i32>,
-    A: Copy + Ord + PartialOrd + Add<Output = A> + Div<Output = A> + From<i32>,
+    P: Copy + PartialOrd + Ord + Add<Output = P> + Div<Output = P> + Default + From<u8> + Increment,
+    A: Copy
+        + PartialOrd
+        + Add<Output = A>
+        + Sub<Output = A>
+        + Div<P, Output = A>
+        + Div<Output = A>
+        + Default
+        + From<u8>
+        + Increment
+        + VwapAmount<P>,
 {
-    bids: BTreeMap<A, P>,
-    offers: BTreeMap<A, P>,
+    bids: BTreeMap<P, A>,
+    offers: BTreeMap<P, A>,
+    reference: InstrumentReference<P, A>,
+    top_of_book: L1MarketDataWithMax<P, A>,
+    expiry: QuoteExpiry,
+    bid_times: BTreeMap<P, Duration>,
+    offer_times: BTreeMap<P, Duration>,
+    cross_policy: CrossPolicy,
+    update_mode: UpdateMode,
+    quality: SideQuality,
+    one_sided_policy: OneSidedPolicy,
+    last_mid: Option<P>,
 }
 
-impl<P, A> L2FullAmountMarketData<P, A>
+impl<P, A> L2SweepableMarketData<P, A>
 where
-    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<i32>,
-    A: Copy + Ord + PartialOrd + Add<Output = A> + Div<Output = A> + From<i32>,
+    P: Copy + PartialOrd + Ord + Add<Output = P> + Div<Output = P> + Default + From<u8> + Increment,
+    A: Copy
+        + PartialOrd
+        + Add<Output = A>
+        + Sub<Output = A>
+        + Div<P, Output = A>
+        + Div<Output = A>
+        + Default
+        + From<u8>
+        + Increment
+        + VwapAmount<P>,
 {
-    /// Use the new function to create a new L2FullAmountMarketData with no pricing.
+    /// Use the new function to create a new L2SweepableMarketData with no pricing.
     ///
     /// # Example
     ///
     /// ```
-    /// use pricing::market_data::L2FullAmountMarketData;
+    /// use pricing::market_data::L2SweepableMarketData;
     ///
-    /// let market_data = L2FullAmountMarketData::<i32, i32>::new();
+    /// let market_data = L2SweepableMarketData::<i32, i32>::new();
     ///
     /// // TBC
     /// ```
@@ -193,194 +224,2708 @@ where
         Self {
             bids: BTreeMap::new(),
             offers: BTreeMap::new(),
+            reference: InstrumentReference::new(),
+            top_of_book: L1MarketDataWithMax::new(),
+            expiry: QuoteExpiry::new(),
+            bid_times: BTreeMap::new(),
+            offer_times: BTreeMap::new(),
+            cross_policy: CrossPolicy::KeepAndFlag,
+            update_mode: UpdateMode::Lenient,
+            quality: SideQuality::default(),
+            one_sided_policy: OneSidedPolicy::AvailableSide,
+            last_mid: None,
         }
     }
 
-    pub fn update(&mut self, action: UpdateAction, side: MarketSide, price: P, size: A) {
-        let side_store = match side {
-            MarketSide::Bid => &mut self.bids,
-            MarketSide::Offer => &mut self.offers,
-        };
+    pub fn update(
+        &mut self,
+        action: UpdateAction,
+        side: MarketSide,
+        price: P,
+        size: A,
+    ) -> Result<(), MarketDataError> {
+        let (price, size) = self.validate_update(action, side, price, size)?;
+        self.update_mode
+            .check(action, self.side_store(side).contains_key(&price), size)?;
+        self.check_cross(&self.bids, &self.offers, action, side, price)?;
+        self.remove_stale_crossed(action, side, price);
+        self.apply_update(action, side, price, size);
+        self.refresh_top_of_book();
 
-        match action {
-            UpdateAction::Add => {
-                side_store.insert(size, price);
-            }
-            UpdateAction::Update => {
-                if let Some(value) = side_store.get_mut(&size) {
-                    *value = price
-                };
-            }
-            UpdateAction::Remove => {
-                side_store.remove(&size);
-            }
-        };
+        Ok(())
     }
 
-    pub fn clear(&mut self) {
-        self.bids.clear();
-        self.offers.clear();
-    }
+    /// Apply a batch of updates as a single change.  Every update is validated before any are applied, so if one is
+    /// rejected the book is left as it was, and subscribers to the top of book are only notified once.  The cross policy is
+    /// applied to each update in turn.
+    ///
+    /// # Parameters
+    ///
+    /// * `updates` - The updates to apply in order
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, L2SweepableMarketData, L2Update, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data
+    ///     .apply_batch(&[
+    ///         L2Update::new(UpdateAction::Add, MarketSide::Bid, 10, 50),
+    ///         L2Update::new(UpdateAction::Add, MarketSide::Offer, 12, 50),
+    ///     ])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(market_data.get_price(50), BidOffer::new_with_price(Some(10), Some(12)));
+    /// ```
+    pub fn apply_batch(&mut self, updates: &[L2Update<P, A>]) -> Result<(), MarketDataError> {
+        // The levels the book is left with by the updates so far
+        let mut bids = self.bids.clone();
+        let mut offers = self.offers.clone();
+        let mut validated = Vec::with_capacity(updates.len());
 
-    pub fn get_price(&self, size: A) -> BidOffer<P> {
-        BidOffer::new_with_price(
-            self.bids
-                .iter()
-                .find(|(&current_size, _)| current_size >= size)
-                .map(|(_, &current_price)| current_price),
-            self.offers
-                .iter()
-                .find(|(&current_size, _)| current_size >= size)
-                .map(|(_, &current_price)| current_price),
-        )
-    }
-}
+        for update in updates {
+            let (action, side) = (update.get_action(), update.get_side());
+            let (price, size) =
+                self.validate_update(action, side, update.get_price(), update.get_size())?;
+            let exists = match side {
+                MarketSide::Bid => bids.contains_key(&price),
+                MarketSide::Offer => offers.contains_key(&price),
+            };
 
-impl<P, A> Default for L2FullAmountMarketData<P, A>
-where
-    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<i32>,
-    A: Copy + Ord + PartialOrd + Add<Output = A> + Div<Output = A> + From<i32>,
-{
-    fn default() -> Self {
-        Self::new()
-    }
-}
+            self.update_mode.check(action, exists, size)?;
+            self.check_cross(&bids, &offers, action, side, price)?;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+            if self.cross_policy == CrossPolicy::RemoveStale
+                && self.crosses(&bids, &offers, action, side, price)
+            {
+                Self::remove_crossed(&mut bids, &mut offers, side, price);
+            }
 
-    #[test]
-    fn default_sweepable() {
-        let test = L2SweepableMarketData::default();
+            apply_level(
+                self.update_mode,
+                &mut bids,
+                &mut offers,
+                action,
+                side,
+                price,
+                size,
+            );
+            validated.push((action, side, price, size));
+        }
 
-        assert_eq!(test.get_price(1), BidOffer::default());
-    }
+        for (action, side, price, size) in validated {
+            self.remove_stale_crossed(action, side, price);
+            self.apply_update(action, side, price, size);
+        }
 
-    #[test]
-    fn default_full_amount() {
-        let test = L2FullAmountMarketData::<i32, i32>::default();
+        self.refresh_top_of_book();
 
-        assert_eq!(test.get_price(1), BidOffer::default());
+        Ok(())
     }
 
-    #[test]
-    fn sweepable_get_basic_price() {
-        let mut test = L2SweepableMarketData::new();
+    fn validate_update(
+        &self,
+        action: UpdateAction,
+        side: MarketSide,
+        price: P,
+        size: A,
+    ) -> Result<(P, A), MarketDataError> {
+        match self.reference.get_spec() {
+            Some(spec) => match action {
+                UpdateAction::Remove => Ok((spec.validate_price(&side, price)?, size)),
+                UpdateAction::ClearSide => Ok((price, size)),
+                _ => Ok((
+                    spec.validate_price(&side, price)?,
+                    spec.validate_size(size)?,
+                )),
+            },
+            None => Ok((price, size)),
+        }
+    }
 
-        test.update(UpdateAction::Add, MarketSide::Bid, 12, 10);
-        test.update(UpdateAction::Add, MarketSide::Offer, 15, 20);
+    // Returns true if setting a level at the price would leave it through the best opposite price
+    fn crosses(
+        &self,
+        bids: &BTreeMap<P, A>,
+        offers: &BTreeMap<P, A>,
+        action: UpdateAction,
+        side: MarketSide,
+        price: P,
+    ) -> bool {
+        // A snapshot clears the opposite side first
+        if action == UpdateAction::Snapshot {
+            return false;
+        }
 
-        assert_eq!(
-            test.get_price(1),
-            BidOffer::new_with_price(Some(12), Some(15))
-        );
-        assert_eq!(
-            test.get_price(10),
-            BidOffer::new_with_price(Some(12), Some(15))
-        );
-        assert_eq!(test.get_price(11), BidOffer::new_with_price(None, Some(15)));
-        assert_eq!(test.get_price(20), BidOffer::new_with_price(None, Some(15)));
-        assert_eq!(test.get_price(21), BidOffer::new_with_price(None, None));
+        let (side_store, opposite_best) = match side {
+            MarketSide::Bid => (bids, offers.keys().next()),
+            MarketSide::Offer => (offers, bids.keys().next_back()),
+        };
+
+        opposite_best.is_some_and(|&best| side.is_better(price, best))
+            && self
+                .update_mode
+                .leaves_level(action, side_store.contains_key(&price))
     }
 
-    #[test]
-    fn sweepable_get_vwap_price() {
-        let mut test = L2SweepableMarketData::new();
+    fn check_cross(
+        &self,
+        bids: &BTreeMap<P, A>,
+        offers: &BTreeMap<P, A>,
+        action: UpdateAction,
+        side: MarketSide,
+        price: P,
+    ) -> Result<(), MarketDataError> {
+        if self.cross_policy == CrossPolicy::Reject
+            && self.crosses(bids, offers, action, side, price)
+        {
+            Err(MarketDataError::CrossedBook)
+        } else {
+            Ok(())
+        }
+    }
 
-        test.update(UpdateAction::Add, MarketSide::Bid, 12, 10);
-        test.update(UpdateAction::Add, MarketSide::Bid, 10, 10);
-        test.update(UpdateAction::Add, MarketSide::Bid, 8, 10);
-        test.update(UpdateAction::Add, MarketSide::Bid, 6, 10);
-        test.update(UpdateAction::Add, MarketSide::Offer, 16, 10);
-        test.update(UpdateAction::Add, MarketSide::Offer, 20, 20);
-        test.update(UpdateAction::Add, MarketSide::Offer, 24, 10);
+    // Removes the opposite levels an update crosses if the policy is to treat them as stale
+    fn remove_stale_crossed(&mut self, action: UpdateAction, side: MarketSide, price: P) {
+        if self.cross_policy == CrossPolicy::RemoveStale
+            && self.crosses(&self.bids, &self.offers, action, side, price)
+        {
+            Self::remove_crossed(&mut self.bids, &mut self.offers, side, price);
+            Self::remove_crossed(&mut self.bid_times, &mut self.offer_times, side, price);
+        }
+    }
 
-        assert_eq!(
-            test.get_price(20),
-            BidOffer::new_with_price(Some(11), Some(18))
-        );
+    // Removes the levels opposite a price which it crosses, from maps holding either the sizes or times of the levels
+    fn remove_crossed<V>(
+        bids: &mut BTreeMap<P, V>,
+        offers: &mut BTreeMap<P, V>,
+        side: MarketSide,
+        price: P,
+    ) {
+        let opposite = match side {
+            MarketSide::Bid => offers,
+            MarketSide::Offer => bids,
+        };
 
-        assert_eq!(
-            test.get_price(40),
-            BidOffer::new_with_price(Some(9), Some(20))
-        );
+        opposite.retain(|&level, _| !side.is_better(price, level));
     }
 
-    #[test]
-    fn full_amount_get_basic_price() {
-        let mut test: L2FullAmountMarketData<i32, i32> = L2FullAmountMarketData::new();
-
-        test.update(UpdateAction::Add, MarketSide::Bid, 12, 10);
-        test.update(UpdateAction::Add, MarketSide::Offer, 15, 20);
+    fn apply_update(&mut self, action: UpdateAction, side: MarketSide, price: P, size: A) {
+        let now = self.expiry.now();
 
-        assert_eq!(
-            test.get_price(1),
-            BidOffer::new_with_price(Some(12), Some(15))
+        apply_level(
+            self.update_mode,
+            &mut self.bids,
+            &mut self.offers,
+            action,
+            side,
+            price,
+            size,
         );
-        assert_eq!(
-            test.get_price(10),
-            BidOffer::new_with_price(Some(12), Some(15))
+        apply_level(
+            self.update_mode,
+            &mut self.bid_times,
+            &mut self.offer_times,
+            action,
+            side,
+            price,
+            now,
         );
-        assert_eq!(test.get_price(11), BidOffer::new_with_price(None, Some(15)));
-        assert_eq!(test.get_price(20), BidOffer::new_with_price(None, Some(15)));
-        assert_eq!(test.get_price(21), BidOffer::new_with_price(None, None));
+        self.refresh_stale(now);
     }
 
-    #[test]
-    fn full_amount_get_vwap_price() {
-        let mut test = L2FullAmountMarketData::new();
+    fn side_store(&self, side: MarketSide) -> &BTreeMap<P, A> {
+        match side {
+            MarketSide::Bid => &self.bids,
+            MarketSide::Offer => &self.offers,
+        }
+    }
 
-        test.update(UpdateAction::Add, MarketSide::Bid, 12, 10);
-        test.update(UpdateAction::Add, MarketSide::Bid, 10, 20);
-        test.update(UpdateAction::Add, MarketSide::Bid, 8, 30);
-        test.update(UpdateAction::Add, MarketSide::Bid, 6, 40);
-        test.update(UpdateAction::Add, MarketSide::Offer, 16, 10);
-        test.update(UpdateAction::Add, MarketSide::Offer, 20, 30);
-        test.update(UpdateAction::Add, MarketSide::Offer, 24, 40);
+    /// Replace the whole book with the levels in a snapshot.  Every level is validated before anything is changed, so if
+    /// any is rejected the book is left as it was, and the top of book is only refreshed once.  A crossed snapshot is only
+    /// rejected if the cross policy is to reject crossing updates, as there is no stale side to remove.
+    ///
+    /// # Parameters
+    ///
+    /// * `levels` - The (side, price, size) of every level in the book
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, L2SweepableMarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 10, 50).unwrap();
+    /// market_data.apply_snapshot(&[(MarketSide::Bid, 9, 50), (MarketSide::Offer, 11, 50)]).unwrap();
+    ///
+    /// assert_eq!(market_data.get_price(50), BidOffer::new_with_price(Some(9), Some(11)));
+    /// ```
+    pub fn apply_snapshot(&mut self, levels: &[(MarketSide, P, A)]) -> Result<(), MarketDataError> {
+        let mut bids = BTreeMap::new();
+        let mut offers = BTreeMap::new();
 
-        assert_eq!(
-            test.get_price(20),
-            BidOffer::new_with_price(Some(10), Some(20))
-        );
+        for &(side, price, size) in levels {
+            let (price, size) = match self.reference.get_spec() {
+                Some(spec) => (
+                    spec.validate_price(&side, price)?,
+                    spec.validate_size(size)?,
+                ),
+                None => (price, size),
+            };
 
-        assert_eq!(
-            test.get_price(40),
-            BidOffer::new_with_price(Some(6), Some(24))
-        );
-    }
+            match side {
+                MarketSide::Bid => bids.insert(price, size),
+                MarketSide::Offer => offers.insert(price, size),
+            };
+        }
 
-    #[test]
-    fn sweepable_modify_delete_clear() {
-        let mut test = L2SweepableMarketData::new();
+        if self.cross_policy == CrossPolicy::Reject {
+            if let (Some(bid), Some(offer)) = (bids.keys().next_back(), offers.keys().next()) {
+                if bid > offer {
+                    return Err(MarketDataError::CrossedBook);
+                }
+            }
+        }
 
-        test.update(UpdateAction::Add, MarketSide::Bid, 12, 10);
-        test.update(UpdateAction::Add, MarketSide::Bid, 10, 10);
-        test.update(UpdateAction::Add, MarketSide::Offer, 16, 10);
-        test.update(UpdateAction::Add, MarketSide::Offer, 20, 10);
+        let bid_now = self.expiry.touch(MarketSide::Bid);
+        let offer_now = self.expiry.touch(MarketSide::Offer);
 
-        assert_eq!(
-            test.get_price(20),
-            BidOffer::new_with_price(Some(11), Some(18))
-        );
-        assert_eq!(test.get_price(40), BidOffer::default());
+        self.bid_times = bids.keys().map(|&price| (price, bid_now)).collect();
+        self.offer_times = offers.keys().map(|&price| (price, offer_now)).collect();
+        self.bids = bids;
+        self.offers = offers;
+        self.refresh_top_of_book();
 
-        test.update(UpdateAction::Update, MarketSide::Bid, 12, 20);
-        test.update(UpdateAction::Update, MarketSide::Offer, 16, 20);
-        test.update(UpdateAction::Update, MarketSide::Bid, 10, 20);
-        test.update(UpdateAction::Update, MarketSide::Offer, 20, 20);
+        Ok(())
+    }
 
-        assert_eq!(
-            test.get_price(20),
-            BidOffer::new_with_price(Some(12), Some(16))
+    /// Set the instrument specification that prices and sizes are validated against on update, in place of the
+    /// specification of any attached instrument.  A value of None means the instrument's specification is used.
+    ///
+    /// # Parameters
+    ///
+    /// * `spec` - The instrument specification
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{
+    ///     InstrumentSpec, L2SweepableMarketData, MarketDataError, MarketSide, SpecPolicy, TickSize, UpdateAction,
+    /// };
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.set_instrument_spec(Some(
+    ///     InstrumentSpec::new(SpecPolicy::Reject)
+    ///         .with_tick_size(TickSize::Fixed(5))
+    ///         .with_lot_size(100),
+    /// ));
+    ///
+    /// assert_eq!(market_data.update(UpdateAction::Add, MarketSide::Bid, 10, 200), Ok(()));
+    /// assert_eq!(
+    ///     market_data.update(UpdateAction::Add, MarketSide::Bid, 11, 200),
+    ///     Err(MarketDataError::OffTickPrice)
+    /// );
+    /// assert_eq!(
+    ///     market_data.update(UpdateAction::Add, MarketSide::Bid, 10, 250),
+    ///     Err(MarketDataError::OffLotSize)
+    /// );
+    /// ```
+    pub fn set_instrument_spec(&mut self, spec: Option<InstrumentSpec<P, A>>) {
+        self.reference.set_book_spec(spec);
+    }
+
+    /// Get the instrument specification set on the book, which prices and sizes are validated against in place
+    /// of that of any attached instrument
+    pub fn get_instrument_spec(&self) -> &Option<InstrumentSpec<P, A>> {
+        self.reference.get_book_spec()
+    }
+
+    /// Attach the reference data of the instrument the book is pricing.  Updates are validated against the specification
+    /// of the instrument unless one has been set on the book, which takes precedence.
+    ///
+    /// # Parameters
+    ///
+    /// * `instrument` - The instrument, a value of None detaches the instrument
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use pricing::market_data::{
+    ///     AssetClass, Instrument, InstrumentSpec, L2SweepableMarketData, MarketDataError, MarketSide, SpecPolicy, TickSize, UpdateAction,
+    /// };
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.set_instrument(Some(Rc::new(
+    ///     Instrument::new("VOD.L", AssetClass::Equity, "GBP")
+    ///         .with_spec(InstrumentSpec::new(SpecPolicy::Reject).with_tick_size(TickSize::Fixed(5))),
+    /// )));
+    ///
+    /// assert_eq!(market_data.get_instrument().unwrap().get_symbol(), "VOD.L");
+    /// assert_eq!(market_data.update(UpdateAction::Add, MarketSide::Bid, 11, 100), Err(MarketDataError::OffTickPrice));
+    /// ```
+    pub fn set_instrument(&mut self, instrument: Option<Rc<Instrument<P, A>>>) {
+        self.reference.set_instrument(instrument);
+    }
+
+    /// Get the reference data of the instrument the book is pricing
+    pub fn get_instrument(&self) -> Option<&Rc<Instrument<P, A>>> {
+        self.reference.get_instrument()
+    }
+
+    /// Get the top of book, which is maintained as the book is updated with the best bid and offer prices, and the size at
+    /// those prices as the max sizes.  Subscribers to the top of book are only called when it actually changes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L2SweepableMarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 10, 80).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 9, 100).unwrap();
+    ///
+    /// assert_eq!(*market_data.get_top_of_book().get_bid(), Some(10));
+    /// assert_eq!(*market_data.get_top_of_book().get_max_bid(), Some(80));
+    /// assert_eq!(*market_data.get_top_of_book().get_offer(), None);
+    /// ```
+    pub fn get_top_of_book(&self) -> &L1MarketDataWithMax<P, A> {
+        &self.top_of_book
+    }
+
+    /// Set what happens to an add or update which would leave the level through the best opposite price, which defaults
+    /// to keeping it and flagging the book as crossed.  Subscribers to the top of book are told when the book becomes or
+    /// stops being crossed or locked.
+    ///
+    /// # Parameters
+    ///
+    /// * `policy` - The policy for crossing updates
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{CrossPolicy, L2SweepableMarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.set_cross_policy(CrossPolicy::RemoveStale);
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 11, 50).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 12, 50).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 12, 50).unwrap();
+    ///
+    /// assert_eq!(market_data.get_levels(MarketSide::Offer).collect::<Vec<_>>(), vec![(12, 50)]);
+    /// ```
+    pub fn set_cross_policy(&mut self, policy: CrossPolicy) {
+        self.cross_policy = policy;
+    }
+
+    /// Get what happens to an add or update which would cross the book
+    pub fn get_cross_policy(&self) -> CrossPolicy {
+        self.cross_policy
+    }
+
+    /// Get whether the best bid and offer currently overlap
+    pub fn get_cross_status(&self) -> CrossStatus {
+        self.top_of_book.get_cross_status()
+    }
+
+    /// Set how an update which does not match the levels in the book is handled, which defaults to lenient
+    ///
+    /// # Parameters
+    ///
+    /// * `mode` - The update mode
+    pub fn set_update_mode(&mut self, mode: UpdateMode) {
+        self.update_mode = mode;
+    }
+
+    /// Get how an update which does not match the levels in the book is handled
+    pub fn get_update_mode(&self) -> UpdateMode {
+        self.update_mode
+    }
+
+    /// Set the quality of the prices on a side, for example to flag them as indicative.  The quality is also set on the
+    /// top of book, which notifies its subscribers if it changes.
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the market
+    /// * `quality` - The quality of the prices on the side
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, L2SweepableMarketData, MarketSide, PriceQuality, UpdateAction};
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 10, 50).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 12, 50).unwrap();
+    /// market_data.set_quality(MarketSide::Bid, PriceQuality::Indicative);
+    ///
+    /// let quoted = market_data.get_quoted_price(50);
+    /// assert_eq!(quoted.get_quality(MarketSide::Bid), PriceQuality::Indicative);
+    /// assert_eq!(quoted.get_firm_price(), BidOffer::new_with_price(None, Some(12)));
+    /// ```
+    pub fn set_quality(&mut self, side: MarketSide, quality: PriceQuality) {
+        self.quality.set(side, quality);
+        self.top_of_book.set_quality(side, quality);
+    }
+
+    /// Get the quality of the prices on a side, which is stale once `check_stale` has found a level on the side expired and
+    /// is limited by the market status
+    pub fn get_quality(&self, side: MarketSide) -> PriceQuality {
+        self.get_status()
+            .limit_quality(self.quality.get(side, self.expiry.is_stale(side)))
+    }
+
+    /// Get the price for a size along with the quality of each side, whatever the market status
+    ///
+    /// # Parameters
+    ///
+    /// * `size` - The size to get the price for
+    pub fn get_quoted_price(&self, size: A) -> QuotedPrice<P> {
+        QuotedPrice::new(
+            self.calc_price(size),
+            self.get_quality(MarketSide::Bid),
+            self.get_quality(MarketSide::Offer),
+        )
+    }
+
+    /// Move the market to a status, for example from a status message from the venue.  The status is held by the top of
+    /// book, which notifies its subscribers if it changes.
+    ///
+    /// # Parameters
+    ///
+    /// * `status` - The status of the market
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, L2SweepableMarketData, MarketSide, MarketStatus, PriceQuality, UpdateAction};
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 10, 50).unwrap();
+    /// market_data.set_status(MarketStatus::Auction).unwrap();
+    ///
+    /// assert_eq!(market_data.get_price(50), BidOffer::new());
+    /// assert_eq!(market_data.get_quoted_price(50).get_price(), BidOffer::new_with_price(Some(10), None));
+    /// assert_eq!(market_data.get_quality(MarketSide::Bid), PriceQuality::Indicative);
+    /// ```
+    pub fn set_status(&mut self, status: MarketStatus) -> Result<(), MarketDataError> {
+        self.top_of_book.set_status(status)
+    }
+
+    /// Get the status of the market, which defaults to continuous trading
+    pub fn get_status(&self) -> MarketStatus {
+        self.top_of_book.get_status()
+    }
+
+    /// Set the schedule which `check_schedule` moves the market status through.  A value of None means the status only
+    /// changes with `set_status`.
+    pub fn set_schedule(&mut self, schedule: Option<TradingSchedule>) {
+        self.top_of_book.set_schedule(schedule);
+    }
+
+    /// Get the schedule the market status moves through
+    pub fn get_schedule(&self) -> Option<&TradingSchedule> {
+        self.top_of_book.get_schedule()
+    }
+
+    /// Moves the market to the status the schedule gives at the time passed in, notifying the subscribers to the top of
+    /// book if it changes.  The first check after the schedule is set moves straight to the status it gives.  After that
+    /// a status the market cannot move to is ignored.  A halted market only follows the schedule when it closes.
+    ///
+    /// # Parameters
+    ///
+    /// * `now` - The time to check the schedule against
+    ///
+    /// # Returns
+    ///
+    /// The new status if it has changed
+    pub fn check_schedule(&mut self, now: Duration) -> Option<MarketStatus> {
+        self.top_of_book.check_schedule(now)
+    }
+
+    fn refresh_top_of_book(&mut self) {
+        let bid = self.bids.iter().next_back();
+        let offer = self.offers.iter().next();
+
+        // The top of book has no instrument specification, so the update cannot be rejected
+        let _ = self.top_of_book.update_with_max(
+            bid.map(|(&price, _)| price),
+            offer.map(|(&price, _)| price),
+            bid.map(|(_, &size)| size),
+            offer.map(|(_, &size)| size),
+        );
+
+        if let (Some((&bid, _)), Some((&offer, _))) = (bid, offer) {
+            self.last_mid = Some(Self::mid(bid, offer));
+        }
+    }
+
+    /// Get the price levels on a side of the book, best price first, as (price, size)
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the market
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L2SweepableMarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 9, 30).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 10, 50).unwrap();
+    ///
+    /// assert_eq!(market_data.get_levels(MarketSide::Bid).collect::<Vec<_>>(), vec![(10, 50), (9, 30)]);
+    /// ```
+    pub fn get_levels(&self, side: MarketSide) -> Box<dyn Iterator<Item = (P, A)> + '_> {
+        match side {
+            MarketSide::Bid => {
+                Box::new(self.bids.iter().rev().map(|(&price, &size)| (price, size)))
+            }
+            MarketSide::Offer => Box::new(self.offers.iter().map(|(&price, &size)| (price, size))),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.bids.clear();
+        self.offers.clear();
+        self.bid_times.clear();
+        self.offer_times.clear();
+        self.refresh_top_of_book();
+    }
+
+    /// Set the clock used to timestamp updates, which defaults to the system clock.  The clock is also used by the top of
+    /// book.
+    pub fn set_clock(&mut self, clock: Rc<dyn Clock>) {
+        self.top_of_book.set_clock(clock.clone());
+        self.expiry.set_clock(clock);
+    }
+
+    /// Set how long each level on a side is valid for after it is updated.  A value of None means levels never expire.
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the market
+    /// * `ttl` - The time to live of each level
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use std::time::Duration;
+    /// use pricing::market_data::{BidOffer, L2SweepableMarketData, ManualClock, MarketSide, UpdateAction};
+    ///
+    /// let clock = Rc::new(ManualClock::new(Duration::from_secs(100)));
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.set_clock(clock.clone());
+    /// market_data.set_time_to_live(MarketSide::Bid, Some(Duration::from_secs(5)));
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 10, 20).unwrap();
+    /// clock.advance(Duration::from_secs(3));
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 8, 20).unwrap();
+    ///
+    /// assert_eq!(market_data.get_price_at(20, Duration::from_secs(105)), BidOffer::new_with_price(Some(10), None));
+    /// assert_eq!(market_data.get_price_at(20, Duration::from_secs(106)), BidOffer::new_with_price(Some(8), None));
+    /// assert_eq!(market_data.check_stale(Duration::from_secs(106)), vec![MarketSide::Bid]);
+    /// ```
+    pub fn set_time_to_live(&mut self, side: MarketSide, ttl: Option<Duration>) {
+        self.expiry.set_time_to_live(side, ttl);
+    }
+
+    /// Get how long each level on a side is valid for after it is updated
+    pub fn get_time_to_live(&self, side: MarketSide) -> Option<Duration> {
+        self.expiry.get_time_to_live(side)
+    }
+
+    /// Get when the level at a price was last updated
+    pub fn get_updated_time(&self, side: MarketSide, price: P) -> Option<Duration> {
+        self.side_times(side).get(&price).copied()
+    }
+
+    /// Returns the price for the size, sweeping only the levels which have not expired by the time passed in
+    ///
+    /// # Parameters
+    ///
+    /// * `size` - The size to price
+    /// * `now` - The time to check expiry against
+    pub fn get_price_at(&self, size: A, now: Duration) -> BidOffer<P> {
+        BidOffer::new_with_price(
+            self.calc_vwap(
+                size,
+                self.bids
+                    .iter()
+                    .rev()
+                    .filter(|(price, _)| !self.has_expired(MarketSide::Bid, price, now)),
+            ),
+            self.calc_vwap(
+                size,
+                self.offers
+                    .iter()
+                    .filter(|(price, _)| !self.has_expired(MarketSide::Offer, price, now)),
+            ),
+        )
+    }
+
+    /// Checks for sides with levels which have expired by the time passed in, notifying the subscribers to the top of book
+    /// of each side the first time it is found to be stale.  A side stops being stale once none of its levels have expired,
+    /// i.e. when the expired levels are updated or removed.
+    ///
+    /// # Parameters
+    ///
+    /// * `now` - The time to check expiry against
+    ///
+    /// # Returns
+    ///
+    /// The sides which have newly become stale
+    pub fn check_stale(&mut self, now: Duration) -> Vec<MarketSide> {
+        let mut stale = Vec::new();
+
+        for side in [MarketSide::Bid, MarketSide::Offer] {
+            let expired = self
+                .side_times(side)
+                .values()
+                .any(|&updated| self.expiry.has_expired(side, updated, now));
+
+            if expired && self.expiry.mark_stale(side) {
+                self.top_of_book.publish_stale(side);
+                stale.push(side);
+            }
+        }
+
+        stale
+    }
+
+    // A side stops being stale once none of its levels have expired, so updating another level, or an update which
+    // leaves the book unchanged, does not refresh it
+    fn refresh_stale(&mut self, now: Duration) {
+        for side in [MarketSide::Bid, MarketSide::Offer] {
+            let expired = self
+                .side_times(side)
+                .values()
+                .any(|&updated| self.expiry.has_expired(side, updated, now));
+
+            self.expiry.refresh_stale(side, expired);
+        }
+    }
+
+    fn side_times(&self, side: MarketSide) -> &BTreeMap<P, Duration> {
+        match side {
+            MarketSide::Bid => &self.bid_times,
+            MarketSide::Offer => &self.offer_times,
+        }
+    }
+
+    fn has_expired(&self, side: MarketSide, price: &P, now: Duration) -> bool {
+        self.side_times(side)
+            .get(price)
+            .is_some_and(|&updated| self.expiry.has_expired(side, updated, now))
+    }
+
+    /// Get the minimal list of level changes which turns this book into another, for example to publish incremental
+    /// updates from polled snapshots.  Applying the changes to a copy of this book gives the same levels as the other.  The
+    /// removals on both sides come first, so that applying the changes in order never crosses the book on the way.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The book to turn this book into
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L2SweepableMarketData, L2Update, MarketSide, UpdateAction};
+    ///
+    /// let mut before = L2SweepableMarketData::<i32, i32>::new();
+    /// before.update(UpdateAction::Add, MarketSide::Bid, 10, 50).unwrap();
+    /// before.update(UpdateAction::Add, MarketSide::Bid, 9, 50).unwrap();
+    ///
+    /// let mut after = L2SweepableMarketData::<i32, i32>::new();
+    /// after.update(UpdateAction::Add, MarketSide::Bid, 10, 40).unwrap();
+    /// after.update(UpdateAction::Add, MarketSide::Offer, 11, 50).unwrap();
+    ///
+    /// assert_eq!(
+    ///     before.diff(&after),
+    ///     vec![
+    ///         L2Update::new(UpdateAction::Remove, MarketSide::Bid, 9, 50),
+    ///         L2Update::new(UpdateAction::Update, MarketSide::Bid, 10, 40),
+    ///         L2Update::new(UpdateAction::Add, MarketSide::Offer, 11, 50),
+    ///     ]
+    /// );
+    /// ```
+    pub fn diff(&self, other: &Self) -> Vec<L2Update<P, A>> {
+        let mut removes = Vec::new();
+        let mut changes = Vec::new();
+
+        for (side, from, to) in [
+            (MarketSide::Bid, &self.bids, &other.bids),
+            (MarketSide::Offer, &self.offers, &other.offers),
+        ] {
+            diff_side(from, to, |action, price, size| {
+                let update = L2Update::new(action, side, price, size);
+
+                match action {
+                    UpdateAction::Remove => removes.push(update),
+                    _ => changes.push(update),
+                }
+            });
+        }
+
+        removes.extend(changes);
+        removes
+    }
+
+    /// Builds a full amount ladder with a tier for each of the sizes, priced at the VWAP of sweeping the book for the size.
+    /// Sizes which the book does not have the liquidity for are left out of the ladder.
+    ///
+    /// # Parameters
+    ///
+    /// * `sizes` - The sizes of the tiers
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, L2SweepableMarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 10, 10).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 20, 10).unwrap();
+    ///
+    /// let full_amount = market_data.to_full_amount(&[10, 20, 30]);
+    ///
+    /// assert_eq!(full_amount.get_price(10), BidOffer::new_with_price(None, Some(10)));
+    /// assert_eq!(full_amount.get_price(20), BidOffer::new_with_price(None, Some(15)));
+    /// assert_eq!(full_amount.get_price(30), BidOffer::new_with_price(None, None));
+    /// ```
+    pub fn to_full_amount(&self, sizes: &[A]) -> L2FullAmountMarketData<P, A>
+    where
+        A: Ord,
+    {
+        let mut full_amount = L2FullAmountMarketData::new();
+
+        for &size in sizes {
+            let price = self.calc_price(size);
+
+            if let Some(bid) = *price.get_bid() {
+                let _ = full_amount.update(UpdateAction::Add, MarketSide::Bid, bid, size);
+            }
+
+            if let Some(offer) = *price.get_offer() {
+                let _ = full_amount.update(UpdateAction::Add, MarketSide::Offer, offer, size);
+            }
+        }
+
+        full_amount
+    }
+
+    /// Returns the price for the size by sweeping the levels on each side, or no price if the market status does not have
+    /// firm prices
+    ///
+    /// # Parameters
+    ///
+    /// * `size` - The size to price
+    pub fn get_price(&self, size: A) -> BidOffer<P> {
+        if self.get_status().has_firm_prices() {
+            self.calc_price(size)
+        } else {
+            BidOffer::new()
+        }
+    }
+
+    fn calc_price(&self, size: A) -> BidOffer<P> {
+        BidOffer::new_with_price(
+            self.calc_vwap(size, self.bids.iter().rev()),
+            self.calc_vwap(size, self.offers.iter()),
+        )
+    }
+
+    /// Set what the fair value measures give when only one side of the book has a price, which defaults to the price of
+    /// that side
+    ///
+    /// # Parameters
+    ///
+    /// * `policy` - The one sided policy
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L2SweepableMarketData, MarketSide, OneSidedPolicy, UpdateAction};
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.set_one_sided_policy(OneSidedPolicy::LastMid);
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 10, 50).unwrap();
+    /// assert_eq!(market_data.get_microprice(), None);
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 12, 50).unwrap();
+    /// market_data.update(UpdateAction::Remove, MarketSide::Offer, 12, 0).unwrap();
+    /// assert_eq!(market_data.get_microprice(), Some(11));
+    /// ```
+    pub fn set_one_sided_policy(&mut self, policy: OneSidedPolicy) {
+        self.one_sided_policy = policy;
+    }
+
+    /// Get what the fair value measures give when only one side of the book has a price
+    pub fn get_one_sided_policy(&self) -> OneSidedPolicy {
+        self.one_sided_policy
+    }
+
+    /// Get the average of the best bid and offer weighted by the size at each, which leans towards the side with more
+    /// size
+    ///
+    /// This is an estimate of value rather than a price to trade on, so it is given whatever the market status.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L2SweepableMarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 100, 30).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 110, 10).unwrap();
+    ///
+    /// assert_eq!(market_data.get_weighted_mid(), Some(103));
+    /// ```
+    pub fn get_weighted_mid(&self) -> Option<P>
+    where
+        P: FloatConvert,
+        A: FloatConvert,
+    {
+        self.one_sided_policy.resolve(
+            self.get_touch(MarketSide::Bid),
+            self.get_touch(MarketSide::Offer),
+            self.last_mid,
+            fair_value::weighted_mid,
+        )
+    }
+
+    /// Get the microprice, the average of the best bid and offer weighted by the size on the opposite side, which leans
+    /// towards the side with less size as that is the side the price is more likely to move to
+    ///
+    /// This is an estimate of value rather than a price to trade on, so it is given whatever the market status.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L2SweepableMarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 100, 30).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 110, 10).unwrap();
+    ///
+    /// assert_eq!(market_data.get_microprice(), Some(108));
+    /// ```
+    pub fn get_microprice(&self) -> Option<P>
+    where
+        P: FloatConvert,
+        A: FloatConvert,
+    {
+        self.one_sided_policy.resolve(
+            self.get_touch(MarketSide::Bid),
+            self.get_touch(MarketSide::Offer),
+            self.last_mid,
+            fair_value::microprice,
+        )
+    }
+
+    /// Get the mid of the prices to trade a size on each side
+    ///
+    /// Unlike `get_price` the mid is given whatever the market status, as it is an estimate of value rather than a price
+    /// to trade on.
+    ///
+    /// # Parameters
+    ///
+    /// * `size` - The size to price
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L2SweepableMarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 100, 10).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 90, 10).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 110, 20).unwrap();
+    ///
+    /// assert_eq!(market_data.get_mid_at_size(20), Some(102));
+    /// ```
+    pub fn get_mid_at_size(&self, size: A) -> Option<P> {
+        let price = self.calc_price(size);
+
+        self.one_sided_policy.resolve(
+            price.get_bid().map(|bid| (bid, ())),
+            price.get_offer().map(|offer| (offer, ())),
+            self.last_mid,
+            |(bid, _), (offer, _)| Self::mid(bid, offer),
+        )
+    }
+
+    // The (price, size) of the best level on a side
+    fn get_touch(&self, side: MarketSide) -> Option<(P, A)> {
+        match side {
+            MarketSide::Bid => self
+                .bids
+                .iter()
+                .next_back()
+                .map(|(&price, &size)| (price, size)),
+            MarketSide::Offer => self
+                .offers
+                .iter()
+                .next()
+                .map(|(&price, &size)| (price, size)),
+        }
+    }
+
+    fn mid(bid: P, offer: P) -> P {
+        let two: P = 2.into();
+        (bid + offer) / two
+    }
+
+    /// Get the imbalance between the size on the best levels of each side, from -1 when there is only offer size to 1
+    /// when there is only bid size.  None if there is no size on either side.
+    ///
+    /// # Parameters
+    ///
+    /// * `levels` - The number of levels from the best on each side to include
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L2SweepableMarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 100, 30).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 101, 10).unwrap();
+    ///
+    /// assert_eq!(market_data.get_imbalance(1), Some(0.5));
+    /// ```
+    pub fn get_imbalance(&self, levels: usize) -> Option<f64>
+    where
+        A: FloatConvert,
+    {
+        metrics::imbalance(
+            self.get_levels(MarketSide::Bid),
+            self.get_levels(MarketSide::Offer),
+            levels,
+        )
+    }
+
+    /// Get the total size on a side at prices within a number of basis points of the mid.  None unless both sides have a
+    /// price.
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the market
+    /// * `bps` - The distance from the mid in basis points
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L2SweepableMarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 9990, 30).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 9900, 50).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 10010, 10).unwrap();
+    ///
+    /// assert_eq!(market_data.get_depth_within_bps(MarketSide::Bid, 10.0), Some(30));
+    /// assert_eq!(market_data.get_depth_within_bps(MarketSide::Bid, 100.0), Some(80));
+    /// ```
+    pub fn get_depth_within_bps(&self, side: MarketSide, bps: f64) -> Option<A>
+    where
+        P: FloatConvert,
+    {
+        let mid = self.get_touch_mid()?;
+
+        Some(metrics::depth_within(self.get_levels(side), |price| {
+            metrics::bps(price, mid, mid).is_some_and(|distance| distance.abs() <= bps)
+        }))
+    }
+
+    /// Get the total size on a side at prices within a number of ticks of the mid, using the tick size of the instrument
+    /// specification.  None unless both sides have a price and there is a tick size.
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the market
+    /// * `ticks` - The distance from the mid in ticks
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L2SweepableMarketData, InstrumentSpec, MarketSide, SpecPolicy, TickSize, UpdateAction};
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.set_instrument_spec(Some(InstrumentSpec::new(SpecPolicy::Reject).with_tick_size(TickSize::Fixed(5))));
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 105, 30).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 120, 50).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 95, 10).unwrap();
+    ///
+    /// assert_eq!(market_data.get_depth_within_ticks(MarketSide::Offer, 1.0), Some(30));
+    /// assert_eq!(market_data.get_depth_within_ticks(MarketSide::Offer, 4.0), Some(80));
+    /// ```
+    pub fn get_depth_within_ticks(&self, side: MarketSide, ticks: f64) -> Option<A>
+    where
+        P: FloatConvert,
+    {
+        let mid = self.get_touch_mid()?;
+        let tick_size = self.reference.get_spec()?.get_tick_size().as_ref()?;
+
+        Some(metrics::depth_within(self.get_levels(side), |price| {
+            let distance = if price < mid {
+                tick_size.ticks_between(price, mid)
+            } else {
+                tick_size.ticks_between(mid, price)
+            };
+
+            distance.is_some_and(|distance| distance <= ticks)
+        }))
+    }
+
+    /// Get the spread between the best bid and offer in ticks, using the tick size of the instrument specification.  None
+    /// unless both sides have a price and there is a tick size, and negative if the book is crossed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L2SweepableMarketData, InstrumentSpec, MarketSide, SpecPolicy, TickSize, UpdateAction};
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.set_instrument_spec(Some(InstrumentSpec::new(SpecPolicy::Reject).with_tick_size(TickSize::Fixed(5))));
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 95, 10).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 110, 10).unwrap();
+    ///
+    /// assert_eq!(market_data.get_spread_ticks(), Some(3.0));
+    /// ```
+    pub fn get_spread_ticks(&self) -> Option<f64>
+    where
+        P: FloatConvert,
+    {
+        let (bid, _) = self.get_touch(MarketSide::Bid)?;
+        let (offer, _) = self.get_touch(MarketSide::Offer)?;
+        let tick_size = self.reference.get_spec()?.get_tick_size().as_ref()?;
+
+        if bid <= offer {
+            tick_size.ticks_between(bid, offer)
+        } else {
+            tick_size.ticks_between(offer, bid).map(|ticks| -ticks)
+        }
+    }
+
+    /// Get the spread between the best bid and offer in basis points of the mid.  None unless both sides have a price, and
+    /// negative if the book is crossed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L2SweepableMarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 9990, 10).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 10010, 10).unwrap();
+    ///
+    /// assert_eq!(market_data.get_spread_bps(), Some(20.0));
+    /// ```
+    pub fn get_spread_bps(&self) -> Option<f64>
+    where
+        P: FloatConvert,
+    {
+        let (bid, _) = self.get_touch(MarketSide::Bid)?;
+        let (offer, _) = self.get_touch(MarketSide::Offer)?;
+
+        metrics::bps(bid, offer, Self::mid(bid, offer))
+    }
+
+    /// Get the number of price levels on a side of the book
+    pub fn get_level_count(&self, side: MarketSide) -> usize {
+        self.side_store(side).len()
+    }
+
+    /// Estimate the impact of sweeping a size from a side of the book, which is None if the side cannot fill the size
+    ///
+    /// The book is used whatever the market status, so the impact of an order can be estimated before the market opens.
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the book to sweep, which is the offers to buy and the bids to sell
+    /// * `size` - The size to sweep
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L2SweepableMarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 100, 50).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 90, 50).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 102, 50).unwrap();
+    ///
+    /// let impact = market_data.estimate_impact(MarketSide::Bid, 100).unwrap();
+    ///
+    /// assert_eq!(impact.get_price(), 95);
+    /// assert_eq!(impact.get_touch_slippage(), 5.0);
+    /// assert_eq!(impact.get_mid_slippage(), Some(6.0));
+    /// assert_eq!(market_data.estimate_impact(MarketSide::Offer, 100), None);
+    /// ```
+    pub fn estimate_impact(&self, side: MarketSide, size: A) -> Option<MarketImpact<P>> {
+        let price = match side {
+            MarketSide::Bid => self.calc_vwap(size, self.bids.iter().rev()),
+            MarketSide::Offer => self.calc_vwap(size, self.offers.iter()),
+        }?;
+        let (touch, _) = self.get_touch(side)?;
+
+        Some(MarketImpact::new(side, price, touch, self.get_touch_mid()))
+    }
+
+    /// Estimate the impact of sweeping each of a range of sizes from a side of the book, as (size, impact)
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the book to sweep
+    /// * `sizes` - The sizes to sweep
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L2SweepableMarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 102, 50).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 106, 50).unwrap();
+    ///
+    /// let curve = market_data.get_impact_curve(MarketSide::Offer, &[50, 100, 150]);
+    /// let slippage: Vec<_> = curve
+    ///     .iter()
+    ///     .map(|(size, impact)| (*size, impact.map(|impact| impact.get_touch_slippage())))
+    ///     .collect();
+    ///
+    /// assert_eq!(slippage, vec![(50, Some(0.0)), (100, Some(2.0)), (150, None)]);
+    /// ```
+    pub fn get_impact_curve(
+        &self,
+        side: MarketSide,
+        sizes: &[A],
+    ) -> Vec<(A, Option<MarketImpact<P>>)> {
+        sizes
+            .iter()
+            .map(|&size| (size, self.estimate_impact(side, size)))
+            .collect()
+    }
+
+    fn get_touch_mid(&self) -> Option<P> {
+        let (bid, _) = self.get_touch(MarketSide::Bid)?;
+        let (offer, _) = self.get_touch(MarketSide::Offer)?;
+
+        Some(Self::mid(bid, offer))
+    }
+
+    fn calc_vwap<'a, I>(&self, size: A, iter: I) -> Option<P>
+    where
+        I: Iterator<Item = (&'a P, &'a A)>,
+        A: 'a,
+        P: 'a,
+    {
+        vwap::sweep(size, iter.map(|(&price, &amount)| (price, amount)))
+    }
+}
+
+impl<P, A> Default for L2SweepableMarketData<P, A>
+where
+    P: Copy + PartialOrd + Ord + Add<Output = P> + Div<Output = P> + Default + From<u8> + Increment,
+    A: Copy
+        + PartialOrd
+        + Add<Output = A>
+        + Sub<Output = A>
+        + Div<P, Output = A>
+        + Div<Output = A>
+        + Default
+        + From<u8>
+        + Increment
+        + VwapAmount<P>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P, A> IncrementalBook for L2SweepableMarketData<P, A>
+where
+    P: Copy + PartialOrd + Ord + Add<Output = P> + Div<Output = P> + Default + From<u8> + Increment,
+    A: Copy
+        + PartialOrd
+        + Add<Output = A>
+        + Sub<Output = A>
+        + Div<P, Output = A>
+        + Div<Output = A>
+        + Default
+        + From<u8>
+        + Increment
+        + VwapAmount<P>,
+{
+    type Update = L2Update<P, A>;
+    type SnapshotEntry = (MarketSide, P, A);
+
+    fn apply_incremental(&mut self, update: &Self::Update) -> Result<(), MarketDataError> {
+        self.update(
+            update.get_action(),
+            update.get_side(),
+            update.get_price(),
+            update.get_size(),
+        )
+    }
+
+    fn apply_snapshot(&mut self, entries: &[Self::SnapshotEntry]) -> Result<(), MarketDataError> {
+        Self::apply_snapshot(self, entries)
+    }
+}
+
+/// How the price for a size between two full amount tiers is calculated
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Interpolation {
+    /// Use the price of the next tier up
+    #[default]
+    None,
+    /// Interpolate linearly in size between the tiers either side
+    Linear,
+    /// Interpolate linearly in the log of the size between the tiers either side, which moves the price more for small
+    /// sizes than for large ones
+    LogSize,
+}
+
+/// How the price for a size above the largest full amount tier is calculated
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Extrapolation {
+    /// There is no price
+    #[default]
+    None,
+    /// Use the price of the largest tier
+    Flat,
+    /// Continue the slope between the two largest tiers, using the same interpolation as between tiers
+    Linear,
+}
+
+/// A structure to hold L2 pricing which is Full Amount.  This means that it is that the price for any given size
+/// is the price publish for the size.
+///
+/// # Generic Parameters
+///
+/// * `A` - The amount type that should be used.
+/// * `P` - The price type that should be used.
+pub struct L2FullAmountMarketData<P, A>
+where
+    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<u8> + Increment,
+    A: Copy + Ord + PartialOrd + Add<Output = A> + Div<Output = A> + From<u8> + Increment,
+{
+    bids: BTreeMap<A, P>,
+    offers: BTreeMap<A, P>,
+    reference: InstrumentReference<P, A>,
+    expiry: QuoteExpiry,
+    bid_times: BTreeMap<A, Duration>,
+    offer_times: BTreeMap<A, Duration>,
+    update_mode: UpdateMode,
+    quality: SideQuality,
+    session: MarketSession,
+    cross_policy: CrossPolicy,
+    cross_status: CrossStatus,
+
+    callbacks: RefCell<Vec<Rc<dyn L1MarketCallback>>>,
+}
+
+impl<P, A> L2FullAmountMarketData<P, A>
+where
+    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<u8> + Increment,
+    A: Copy + Ord + PartialOrd + Add<Output = A> + Div<Output = A> + From<u8> + Increment,
+{
+    /// Use the new function to create a new L2FullAmountMarketData with no pricing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::L2FullAmountMarketData;
+    ///
+    /// let market_data = L2FullAmountMarketData::<i32, i32>::new();
+    ///
+    /// // TBC
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            bids: BTreeMap::new(),
+            offers: BTreeMap::new(),
+            reference: InstrumentReference::new(),
+            expiry: QuoteExpiry::new(),
+            bid_times: BTreeMap::new(),
+            offer_times: BTreeMap::new(),
+            update_mode: UpdateMode::Lenient,
+            quality: SideQuality::default(),
+            session: MarketSession::default(),
+            cross_policy: CrossPolicy::KeepAndFlag,
+            cross_status: CrossStatus::Normal,
+            callbacks: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        action: UpdateAction,
+        side: MarketSide,
+        price: P,
+        size: A,
+    ) -> Result<(), MarketDataError> {
+        let (price, size) = self.validate_update(action, side, price, size)?;
+        self.update_mode
+            .check(action, self.side_store(side).contains_key(&size), size)?;
+        self.check_cross(&self.bids, &self.offers, action, side, price, size)?;
+        self.remove_stale_crossed(action, side, price, size);
+        self.apply_update(action, side, price, size);
+        self.refresh_cross_status();
+        self.publish_to_subscribers();
+
+        Ok(())
+    }
+
+    /// Apply a batch of updates as a single change.  Every update is validated before any are applied, so if one is
+    /// rejected the book is left as it was, and subscribers are only notified once.  The cross policy is applied to each
+    /// update in turn.
+    ///
+    /// # Parameters
+    ///
+    /// * `updates` - The updates to apply in order, where the size of each is the tier
+    pub fn apply_batch(&mut self, updates: &[L2Update<P, A>]) -> Result<(), MarketDataError> {
+        // The tiers the book is left with by the updates so far
+        let mut bids = self.bids.clone();
+        let mut offers = self.offers.clone();
+        let mut validated = Vec::with_capacity(updates.len());
+
+        for update in updates {
+            let (action, side) = (update.get_action(), update.get_side());
+            let (price, size) =
+                self.validate_update(action, side, update.get_price(), update.get_size())?;
+            let exists = match side {
+                MarketSide::Bid => bids.contains_key(&size),
+                MarketSide::Offer => offers.contains_key(&size),
+            };
+
+            self.update_mode.check(action, exists, size)?;
+            self.check_cross(&bids, &offers, action, side, price, size)?;
+
+            if self.cross_policy == CrossPolicy::RemoveStale
+                && self.crosses(&bids, &offers, action, side, price, size)
+            {
+                match side {
+                    MarketSide::Bid => Self::remove_crossed(&mut offers, side, price),
+                    MarketSide::Offer => Self::remove_crossed(&mut bids, side, price),
+                }
+            }
+
+            apply_level(
+                self.update_mode,
+                &mut bids,
+                &mut offers,
+                action,
+                side,
+                size,
+                price,
+            );
+            validated.push((action, side, price, size));
+        }
+
+        for (action, side, price, size) in validated {
+            self.remove_stale_crossed(action, side, price, size);
+            self.apply_update(action, side, price, size);
+        }
+
+        self.refresh_cross_status();
+        self.publish_to_subscribers();
+
+        Ok(())
+    }
+
+    fn validate_update(
+        &self,
+        action: UpdateAction,
+        side: MarketSide,
+        price: P,
+        size: A,
+    ) -> Result<(P, A), MarketDataError> {
+        match self.reference.get_spec() {
+            Some(spec) => match action {
+                UpdateAction::Remove => Ok((price, spec.validate_size(size)?)),
+                UpdateAction::ClearSide => Ok((price, size)),
+                _ => Ok((
+                    spec.validate_price(&side, price)?,
+                    spec.validate_size(size)?,
+                )),
+            },
+            None => Ok((price, size)),
+        }
+    }
+
+    // Returns true if setting a tier at the price would leave it through the best price of any tier on the opposite side
+    fn crosses(
+        &self,
+        bids: &BTreeMap<A, P>,
+        offers: &BTreeMap<A, P>,
+        action: UpdateAction,
+        side: MarketSide,
+        price: P,
+        size: A,
+    ) -> bool {
+        // A snapshot clears the opposite side first
+        if action == UpdateAction::Snapshot {
+            return false;
+        }
+
+        let (side_store, opposite) = match side {
+            MarketSide::Bid => (bids, offers),
+            MarketSide::Offer => (offers, bids),
+        };
+
+        Self::best_price(opposite, side.opposite()).is_some_and(|best| side.is_better(price, best))
+            && self
+                .update_mode
+                .leaves_level(action, side_store.contains_key(&size))
+    }
+
+    fn check_cross(
+        &self,
+        bids: &BTreeMap<A, P>,
+        offers: &BTreeMap<A, P>,
+        action: UpdateAction,
+        side: MarketSide,
+        price: P,
+        size: A,
+    ) -> Result<(), MarketDataError> {
+        if self.cross_policy == CrossPolicy::Reject
+            && self.crosses(bids, offers, action, side, price, size)
+        {
+            Err(MarketDataError::CrossedBook)
+        } else {
+            Ok(())
+        }
+    }
+
+    // Removes the opposite tiers an update crosses if the policy is to treat them as stale
+    fn remove_stale_crossed(&mut self, action: UpdateAction, side: MarketSide, price: P, size: A) {
+        if self.cross_policy == CrossPolicy::RemoveStale
+            && self.crosses(&self.bids, &self.offers, action, side, price, size)
+        {
+            let (opposite, opposite_times) = match side {
+                MarketSide::Bid => (&mut self.offers, &mut self.offer_times),
+                MarketSide::Offer => (&mut self.bids, &mut self.bid_times),
+            };
+
+            Self::remove_crossed(opposite, side, price);
+            opposite_times.retain(|size, _| opposite.contains_key(size));
+        }
+    }
+
+    // Removes the tiers opposite a price which it crosses
+    fn remove_crossed(opposite: &mut BTreeMap<A, P>, side: MarketSide, price: P) {
+        opposite.retain(|_, &mut tier_price| !side.is_better(price, tier_price));
+    }
+
+    // The best price of any tier on a side, which need not be the price of the smallest tier
+    fn best_price(tiers: &BTreeMap<A, P>, side: MarketSide) -> Option<P> {
+        tiers.values().copied().reduce(|best, price| {
+            if side.is_better(price, best) {
+                price
+            } else {
+                best
+            }
+        })
+    }
+
+    fn refresh_cross_status(&mut self) {
+        let status = BidOffer::new_with_price(
+            Self::best_price(&self.bids, MarketSide::Bid),
+            Self::best_price(&self.offers, MarketSide::Offer),
+        )
+        .get_cross_status();
+
+        if status != self.cross_status {
+            self.cross_status = status;
+
+            for callback in self.callbacks.borrow().iter() {
+                callback.cross_status_changed(status);
+            }
+        }
+    }
+
+    fn apply_update(&mut self, action: UpdateAction, side: MarketSide, price: P, size: A) {
+        let now = self.expiry.now();
+
+        apply_level(
+            self.update_mode,
+            &mut self.bids,
+            &mut self.offers,
+            action,
+            side,
+            size,
+            price,
+        );
+        apply_level(
+            self.update_mode,
+            &mut self.bid_times,
+            &mut self.offer_times,
+            action,
+            side,
+            size,
+            now,
+        );
+        self.refresh_stale(now);
+    }
+
+    fn side_store(&self, side: MarketSide) -> &BTreeMap<A, P> {
+        match side {
+            MarketSide::Bid => &self.bids,
+            MarketSide::Offer => &self.offers,
+        }
+    }
+
+    /// Replace the whole book with the tiers in a snapshot.  Every tier is validated before anything is changed, so if
+    /// any is rejected the book is left as it was.  A crossed snapshot is only rejected if the cross policy is to reject
+    /// crossing updates, as there is no stale side to remove.
+    ///
+    /// # Parameters
+    ///
+    /// * `tiers` - The (side, price, size) of every tier in the book
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, L2FullAmountMarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L2FullAmountMarketData::<i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 10, 50).unwrap();
+    /// market_data.apply_snapshot(&[(MarketSide::Bid, 9, 50), (MarketSide::Offer, 11, 50)]).unwrap();
+    ///
+    /// assert_eq!(market_data.get_price(50), BidOffer::new_with_price(Some(9), Some(11)));
+    /// ```
+    pub fn apply_snapshot(&mut self, tiers: &[(MarketSide, P, A)]) -> Result<(), MarketDataError> {
+        let mut bids = BTreeMap::new();
+        let mut offers = BTreeMap::new();
+
+        for &(side, price, size) in tiers {
+            let (price, size) = match self.reference.get_spec() {
+                Some(spec) => (
+                    spec.validate_price(&side, price)?,
+                    spec.validate_size(size)?,
+                ),
+                None => (price, size),
+            };
+
+            match side {
+                MarketSide::Bid => bids.insert(size, price),
+                MarketSide::Offer => offers.insert(size, price),
+            };
+        }
+
+        if self.cross_policy == CrossPolicy::Reject {
+            if let (Some(bid), Some(offer)) = (
+                Self::best_price(&bids, MarketSide::Bid),
+                Self::best_price(&offers, MarketSide::Offer),
+            ) {
+                if bid > offer {
+                    return Err(MarketDataError::CrossedBook);
+                }
+            }
+        }
+
+        let bid_now = self.expiry.touch(MarketSide::Bid);
+        let offer_now = self.expiry.touch(MarketSide::Offer);
+
+        self.bid_times = bids.keys().map(|&size| (size, bid_now)).collect();
+        self.offer_times = offers.keys().map(|&size| (size, offer_now)).collect();
+        self.bids = bids;
+        self.offers = offers;
+        self.refresh_cross_status();
+        self.publish_to_subscribers();
+
+        Ok(())
+    }
+
+    /// Set the instrument specification that prices and sizes are validated against on update, in place of the
+    /// specification of any attached instrument.  A value of None means the instrument's specification is used.
+    ///
+    /// # Parameters
+    ///
+    /// * `spec` - The instrument specification
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{
+    ///     InstrumentSpec, L2FullAmountMarketData, MarketDataError, MarketSide, SpecPolicy, TickSize, UpdateAction,
+    /// };
+    ///
+    /// let mut market_data = L2FullAmountMarketData::<i32, i32>::new();
+    ///
+    /// market_data.set_instrument_spec(Some(
+    ///     InstrumentSpec::new(SpecPolicy::Reject)
+    ///         .with_tick_size(TickSize::Fixed(5))
+    ///         .with_lot_size(100),
+    /// ));
+    ///
+    /// assert_eq!(market_data.update(UpdateAction::Add, MarketSide::Bid, 10, 200), Ok(()));
+    /// assert_eq!(
+    ///     market_data.update(UpdateAction::Add, MarketSide::Bid, 11, 200),
+    ///     Err(MarketDataError::OffTickPrice)
+    /// );
+    /// assert_eq!(
+    ///     market_data.update(UpdateAction::Add, MarketSide::Bid, 10, 250),
+    ///     Err(MarketDataError::OffLotSize)
+    /// );
+    /// ```
+    pub fn set_instrument_spec(&mut self, spec: Option<InstrumentSpec<P, A>>) {
+        self.reference.set_book_spec(spec);
+    }
+
+    /// Get the instrument specification set on the book, which prices and sizes are validated against in place
+    /// of that of any attached instrument
+    pub fn get_instrument_spec(&self) -> &Option<InstrumentSpec<P, A>> {
+        self.reference.get_book_spec()
+    }
+
+    /// Attach the reference data of the instrument the book is pricing.  Updates are validated against the specification
+    /// of the instrument unless one has been set on the book, which takes precedence.
+    ///
+    /// # Parameters
+    ///
+    /// * `instrument` - The instrument, a value of None detaches the instrument
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use pricing::market_data::{
+    ///     AssetClass, Instrument, InstrumentSpec, L2FullAmountMarketData, MarketDataError, MarketSide, SpecPolicy, TickSize, UpdateAction,
+    /// };
+    ///
+    /// let mut market_data = L2FullAmountMarketData::<i32, i32>::new();
+    ///
+    /// market_data.set_instrument(Some(Rc::new(
+    ///     Instrument::new("EURUSD", AssetClass::ForeignExchange, "USD")
+    ///         .with_spec(InstrumentSpec::new(SpecPolicy::Reject).with_tick_size(TickSize::Fixed(5))),
+    /// )));
+    ///
+    /// assert_eq!(market_data.get_instrument().unwrap().get_symbol(), "EURUSD");
+    /// assert_eq!(market_data.update(UpdateAction::Add, MarketSide::Bid, 11, 100), Err(MarketDataError::OffTickPrice));
+    /// ```
+    pub fn set_instrument(&mut self, instrument: Option<Rc<Instrument<P, A>>>) {
+        self.reference.set_instrument(instrument);
+    }
+
+    /// Get the reference data of the instrument the book is pricing
+    pub fn get_instrument(&self) -> Option<&Rc<Instrument<P, A>>> {
+        self.reference.get_instrument()
+    }
+
+    /// Set what happens to an add or update which would leave a tier through the best price of the opposite tiers, which
+    /// defaults to keeping it and flagging the book as crossed.  Subscribers are told when the book becomes or stops
+    /// being crossed or locked.
+    ///
+    /// # Parameters
+    ///
+    /// * `policy` - The policy for crossing updates
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, CrossPolicy, CrossStatus, L2FullAmountMarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L2FullAmountMarketData::<i32, i32>::new();
+    ///
+    /// market_data.set_cross_policy(CrossPolicy::RemoveStale);
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 12, 50).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 13, 100).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 13, 100).unwrap();
+    ///
+    /// assert_eq!(market_data.get_cross_status(), CrossStatus::Locked);
+    /// assert_eq!(market_data.get_price(50), BidOffer::new_with_price(Some(13), Some(13)));
+    /// ```
+    pub fn set_cross_policy(&mut self, policy: CrossPolicy) {
+        self.cross_policy = policy;
+    }
+
+    /// Get what happens to an add or update which would cross the book
+    pub fn get_cross_policy(&self) -> CrossPolicy {
+        self.cross_policy
+    }
+
+    /// Get whether the best bid and offer of any tier currently overlap
+    pub fn get_cross_status(&self) -> CrossStatus {
+        self.cross_status
+    }
+
+    pub fn clear(&mut self) {
+        self.bids.clear();
+        self.offers.clear();
+        self.bid_times.clear();
+        self.offer_times.clear();
+        self.refresh_cross_status();
+        self.publish_to_subscribers();
+    }
+
+    /// Returns the price of the smallest tier for the size, or no price if the market status does not have firm prices
+    ///
+    /// # Parameters
+    ///
+    /// * `size` - The size to price
+    pub fn get_price(&self, size: A) -> BidOffer<P> {
+        if self.session.get_status().has_firm_prices() {
+            self.calc_price(size)
+        } else {
+            BidOffer::new()
+        }
+    }
+
+    fn calc_price(&self, size: A) -> BidOffer<P> {
+        BidOffer::new_with_price(
+            self.bids
+                .iter()
+                .find(|(&current_size, _)| current_size >= size)
+                .map(|(_, &current_price)| current_price),
+            self.offers
+                .iter()
+                .find(|(&current_size, _)| current_size >= size)
+                .map(|(_, &current_price)| current_price),
+        )
+    }
+
+    /// Set the clock used to timestamp updates, which defaults to the system clock
+    pub fn set_clock(&mut self, clock: Rc<dyn Clock>) {
+        self.expiry.set_clock(clock);
+    }
+
+    /// Set how long each tier on a side is valid for after it is updated.  A value of None means tiers never expire.
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the market
+    /// * `ttl` - The time to live of each tier
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use std::time::Duration;
+    /// use pricing::market_data::{BidOffer, L2FullAmountMarketData, ManualClock, MarketSide, UpdateAction};
+    ///
+    /// let clock = Rc::new(ManualClock::new(Duration::from_secs(100)));
+    /// let mut market_data = L2FullAmountMarketData::<i32, i32>::new();
+    ///
+    /// market_data.set_clock(clock.clone());
+    /// market_data.set_time_to_live(MarketSide::Offer, Some(Duration::from_secs(5)));
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 10, 10).unwrap();
+    /// clock.advance(Duration::from_secs(3));
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 12, 20).unwrap();
+    ///
+    /// assert_eq!(market_data.get_price_at(10, Duration::from_secs(105)), BidOffer::new_with_price(None, Some(10)));
+    /// assert_eq!(market_data.get_price_at(10, Duration::from_secs(106)), BidOffer::new_with_price(None, Some(12)));
+    /// ```
+    pub fn set_time_to_live(&mut self, side: MarketSide, ttl: Option<Duration>) {
+        self.expiry.set_time_to_live(side, ttl);
+    }
+
+    /// Get how long each tier on a side is valid for after it is updated
+    pub fn get_time_to_live(&self, side: MarketSide) -> Option<Duration> {
+        self.expiry.get_time_to_live(side)
+    }
+
+    /// Get when the tier for a size was last updated
+    pub fn get_updated_time(&self, side: MarketSide, size: A) -> Option<Duration> {
+        self.side_times(side).get(&size).copied()
+    }
+
+    /// Returns the price of the smallest tier for the size which has not expired by the time passed in
+    ///
+    /// # Parameters
+    ///
+    /// * `size` - The size to price
+    /// * `now` - The time to check expiry against
+    pub fn get_price_at(&self, size: A, now: Duration) -> BidOffer<P> {
+        let side_price = |side, tiers: &BTreeMap<A, P>| {
+            tiers
+                .range(size..)
+                .find(|(tier_size, _)| !self.has_expired(side, tier_size, now))
+                .map(|(_, &price)| price)
+        };
+
+        BidOffer::new_with_price(
+            side_price(MarketSide::Bid, &self.bids),
+            side_price(MarketSide::Offer, &self.offers),
+        )
+    }
+
+    /// Checks for sides with tiers which have expired by the time passed in, notifying subscribers of each side the first
+    /// time it is found to be stale.  A side stops being stale once none of its tiers have expired.
+    ///
+    /// # Parameters
+    ///
+    /// * `now` - The time to check expiry against
+    ///
+    /// # Returns
+    ///
+    /// The sides which have newly become stale
+    pub fn check_stale(&mut self, now: Duration) -> Vec<MarketSide> {
+        let mut stale = Vec::new();
+
+        for side in [MarketSide::Bid, MarketSide::Offer] {
+            let expired = self
+                .side_times(side)
+                .values()
+                .any(|&updated| self.expiry.has_expired(side, updated, now));
+
+            if expired && self.expiry.mark_stale(side) {
+                self.publish_stale(side);
+                stale.push(side);
+            }
+        }
+
+        stale
+    }
+
+    // A side stops being stale once none of its tiers have expired
+    fn refresh_stale(&mut self, now: Duration) {
+        for side in [MarketSide::Bid, MarketSide::Offer] {
+            let expired = self
+                .side_times(side)
+                .values()
+                .any(|&updated| self.expiry.has_expired(side, updated, now));
+
+            self.expiry.refresh_stale(side, expired);
+        }
+    }
+
+    fn side_times(&self, side: MarketSide) -> &BTreeMap<A, Duration> {
+        match side {
+            MarketSide::Bid => &self.bid_times,
+            MarketSide::Offer => &self.offer_times,
+        }
+    }
+
+    fn has_expired(&self, side: MarketSide, size: &A, now: Duration) -> bool {
+        self.side_times(side)
+            .get(size)
+            .is_some_and(|&updated| self.expiry.has_expired(side, updated, now))
+    }
+
+    /// Get the minimal list of tier changes which turns this book into another, for example to publish incremental
+    /// updates from polled snapshots.  The size of each change is the size of the tier.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The book to turn this book into
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L2FullAmountMarketData, L2Update, MarketSide, UpdateAction};
+    ///
+    /// let mut before = L2FullAmountMarketData::<i32, i32>::new();
+    /// before.update(UpdateAction::Add, MarketSide::Offer, 101, 1_000_000).unwrap();
+    /// before.update(UpdateAction::Add, MarketSide::Offer, 102, 5_000_000).unwrap();
+    ///
+    /// let mut after = L2FullAmountMarketData::<i32, i32>::new();
+    /// after.update(UpdateAction::Add, MarketSide::Offer, 101, 1_000_000).unwrap();
+    /// after.update(UpdateAction::Add, MarketSide::Offer, 103, 5_000_000).unwrap();
+    ///
+    /// assert_eq!(
+    ///     before.diff(&after),
+    ///     vec![L2Update::new(UpdateAction::Update, MarketSide::Offer, 103, 5_000_000)]
+    /// );
+    /// ```
+    pub fn diff(&self, other: &Self) -> Vec<L2Update<P, A>> {
+        let mut updates = Vec::new();
+
+        diff_side(&self.bids, &other.bids, |action, size, price| {
+            updates.push(L2Update::new(action, MarketSide::Bid, price, size))
+        });
+        diff_side(&self.offers, &other.offers, |action, size, price| {
+            updates.push(L2Update::new(action, MarketSide::Offer, price, size))
+        });
+
+        updates
+    }
+
+    /// Derives an approximate sweepable book from the tiers.  Each tier after the first adds a level for the size it adds
+    /// to the tier below, priced so that sweeping the book for the tier size gives the tier price.  Where that marginal
+    /// price would be better than the level before it, the level is priced at the level before instead so that the book
+    /// stays in price order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, L2FullAmountMarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L2FullAmountMarketData::<i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 100, 10).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 95, 20).unwrap();
+    ///
+    /// let sweepable = market_data.to_sweepable();
+    ///
+    /// assert_eq!(sweepable.get_levels(MarketSide::Bid).collect::<Vec<_>>(), vec![(100, 10), (90, 10)]);
+    /// assert_eq!(sweepable.get_price(20), BidOffer::new_with_price(Some(95), None));
+    /// ```
+    pub fn to_sweepable(&self) -> L2SweepableMarketData<P, A>
+    where
+        P: Ord + Default + FloatConvert,
+        A: Sub<Output = A> + Div<P, Output = A> + Default + VwapAmount<P> + FloatConvert,
+    {
+        let mut sweepable = L2SweepableMarketData::new();
+
+        for (side, tiers) in [
+            (MarketSide::Bid, &self.bids),
+            (MarketSide::Offer, &self.offers),
+        ] {
+            let mut levels: BTreeMap<P, A> = BTreeMap::new();
+            let mut previous: Option<(A, P)> = None;
+
+            for (&size, &price) in tiers {
+                let (level_price, level_size) = match previous {
+                    None => (price, size),
+                    Some((previous_size, previous_price)) => {
+                        let (lower_size, lower_price) =
+                            (previous_size.to_f64(), previous_price.to_f64());
+                        let marginal = P::from_f64(
+                            (size.to_f64() * price.to_f64() - lower_size * lower_price)
+                                / (size.to_f64() - lower_size),
+                        );
+                        let worst = *match side {
+                            MarketSide::Bid => levels.keys().next(),
+                            MarketSide::Offer => levels.keys().next_back(),
+                        }
+                        .unwrap_or(&marginal);
+
+                        let marginal = match side {
+                            MarketSide::Bid if marginal > worst => worst,
+                            MarketSide::Offer if marginal < worst => worst,
+                            _ => marginal,
+                        };
+
+                        (marginal, size - previous_size)
+                    }
+                };
+
+                let level = levels.entry(level_price).or_default();
+                *level = *level + level_size;
+                previous = Some((size, price));
+            }
+
+            for (price, size) in levels {
+                let _ = sweepable.update(UpdateAction::Add, side, price, size);
+            }
+        }
+
+        sweepable
+    }
+
+    /// Set how an update which does not match the tiers in the book is handled, which defaults to lenient
+    ///
+    /// # Parameters
+    ///
+    /// * `mode` - The update mode
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L2FullAmountMarketData, MarketDataError, MarketSide, UpdateAction, UpdateMode};
+    ///
+    /// let mut market_data = L2FullAmountMarketData::<i32, i32>::new();
+    ///
+    /// market_data.set_update_mode(UpdateMode::Strict);
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 10, 50).unwrap();
+    ///
+    /// assert_eq!(
+    ///     market_data.update(UpdateAction::Add, MarketSide::Bid, 11, 50),
+    ///     Err(MarketDataError::LevelExists)
+    /// );
+    /// ```
+    pub fn set_update_mode(&mut self, mode: UpdateMode) {
+        self.update_mode = mode;
+    }
+
+    /// Get how an update which does not match the tiers in the book is handled
+    pub fn get_update_mode(&self) -> UpdateMode {
+        self.update_mode
+    }
+
+    /// Set the quality of the prices on a side, for example to flag them as indicative
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the market
+    /// * `quality` - The quality of the prices on the side
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L2FullAmountMarketData, MarketSide, PriceQuality, UpdateAction};
+    ///
+    /// let mut market_data = L2FullAmountMarketData::<i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 12, 50).unwrap();
+    /// market_data.set_quality(MarketSide::Offer, PriceQuality::Derived(7));
+    ///
+    /// assert!(!market_data.get_quoted_price(50).is_firm(MarketSide::Offer));
+    /// ```
+    pub fn set_quality(&mut self, side: MarketSide, quality: PriceQuality) {
+        self.quality.set(side, quality);
+    }
+
+    /// Get the quality of the prices on a side, which is stale once `check_stale` has found a tier on the side expired and
+    /// is limited by the market status
+    pub fn get_quality(&self, side: MarketSide) -> PriceQuality {
+        self.session
+            .get_status()
+            .limit_quality(self.quality.get(side, self.expiry.is_stale(side)))
+    }
+
+    /// Get the price for a size along with the quality of each side, whatever the market status
+    ///
+    /// # Parameters
+    ///
+    /// * `size` - The size to get the price for
+    pub fn get_quoted_price(&self, size: A) -> QuotedPrice<P> {
+        QuotedPrice::new(
+            self.calc_price(size),
+            self.get_quality(MarketSide::Bid),
+            self.get_quality(MarketSide::Offer),
+        )
+    }
+
+    /// Move the market to a status, for example from a status message from the venue
+    ///
+    /// # Parameters
+    ///
+    /// * `status` - The status of the market
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, L2FullAmountMarketData, MarketSide, MarketStatus, PriceQuality, UpdateAction};
+    ///
+    /// let mut market_data = L2FullAmountMarketData::<i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 12, 50).unwrap();
+    /// market_data.set_status(MarketStatus::Closed).unwrap();
+    ///
+    /// assert_eq!(market_data.get_price(50), BidOffer::new());
+    /// assert_eq!(market_data.get_quality(MarketSide::Offer), PriceQuality::Closed);
+    /// ```
+    pub fn set_status(&mut self, status: MarketStatus) -> Result<(), MarketDataError> {
+        if let Some(from) = self.session.transition(status)? {
+            self.publish_status(from, status);
+        }
+
+        Ok(())
+    }
+
+    /// Get the status of the market, which defaults to continuous trading
+    pub fn get_status(&self) -> MarketStatus {
+        self.session.get_status()
+    }
+
+    /// Set the schedule which `check_schedule` moves the market status through.  A value of None means the status only
+    /// changes with `set_status`.
+    pub fn set_schedule(&mut self, schedule: Option<TradingSchedule>) {
+        self.session.set_schedule(schedule);
+    }
+
+    /// Get the schedule the market status moves through
+    pub fn get_schedule(&self) -> Option<&TradingSchedule> {
+        self.session.get_schedule()
+    }
+
+    /// Moves the market to the status the schedule gives at the time passed in, notifying subscribers if it changes.  The
+    /// first check after the schedule is set moves straight to the status it gives.  After that a status the market
+    /// cannot move to is ignored.  A halted market only follows the schedule when it closes.
+    ///
+    /// # Parameters
+    ///
+    /// * `now` - The time to check the schedule against
+    ///
+    /// # Returns
+    ///
+    /// The new status if it has changed
+    pub fn check_schedule(&mut self, now: Duration) -> Option<MarketStatus> {
+        let from = self.session.check_schedule(now)?;
+        let status = self.session.get_status();
+
+        self.publish_status(from, status);
+
+        Some(status)
+    }
+
+    /// Subscribe to updates to the book, sides going stale, the book becoming crossed and changes to the market status.  NOTE: this will occur in the
+    /// same thread as the caller, so make sure that this does not cause a recursion issue.
+    ///
+    /// # Parameters
+    ///
+    /// * `callback` - The object which implements the L1MarketCallback trait to callback on
+    pub fn subscribe(&self, callback: Rc<dyn L1MarketCallback>) {
+        self.callbacks.borrow_mut().push(callback);
+    }
+
+    fn publish_to_subscribers(&self) {
+        for callback in self.callbacks.borrow().iter() {
+            callback.market_updated();
+        }
+    }
+
+    fn publish_stale(&self, side: MarketSide) {
+        for callback in self.callbacks.borrow().iter() {
+            callback.market_stale(side);
+        }
+    }
+
+    fn publish_status(&self, from: MarketStatus, to: MarketStatus) {
+        for callback in self.callbacks.borrow().iter() {
+            callback.status_changed(from, to);
+        }
+    }
+
+    /// Get the price for a size, interpolating between tiers and extrapolating above the largest tier.  Sizes below the
+    /// smallest tier get the price of the smallest tier.  If the instrument specification has a tick size the calculated
+    /// prices are rounded onto it, bids down and offers up.  As with `get_price` there is no price if the market status
+    /// does not have firm prices.
+    ///
+    /// # Parameters
+    ///
+    /// * `size` - The size to price
+    /// * `interpolation` - How the price between two tiers is calculated
+    /// * `extrapolation` - How the price above the largest tier is calculated
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{
+    ///     BidOffer, Extrapolation, Interpolation, L2FullAmountMarketData, MarketSide, UpdateAction,
+    /// };
+    ///
+    /// let mut market_data = L2FullAmountMarketData::<f64, i64>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 1.1000, 5_000_000).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 1.1010, 10_000_000).unwrap();
+    ///
+    /// assert_eq!(
+    ///     market_data.get_interpolated_price(6_000_000, Interpolation::None, Extrapolation::None),
+    ///     BidOffer::new_with_price(None, Some(1.1010))
+    /// );
+    ///
+    /// let offer = market_data.get_interpolated_price(6_000_000, Interpolation::Linear, Extrapolation::Flat);
+    /// assert!((offer.get_offer().unwrap() - 1.1002).abs() < 1e-9);
+    /// assert_eq!(
+    ///     market_data.get_interpolated_price(20_000_000, Interpolation::Linear, Extrapolation::Flat),
+    ///     BidOffer::new_with_price(None, Some(1.1010))
+    /// );
+    /// ```
+    pub fn get_interpolated_price(
+        &self,
+        size: A,
+        interpolation: Interpolation,
+        extrapolation: Extrapolation,
+    ) -> BidOffer<P>
+    where
+        P: FloatConvert,
+        A: FloatConvert,
+    {
+        if !self.session.get_status().has_firm_prices() {
+            return BidOffer::new();
+        }
+
+        BidOffer::new_with_price(
+            self.calc_interpolated(MarketSide::Bid, size, interpolation, extrapolation),
+            self.calc_interpolated(MarketSide::Offer, size, interpolation, extrapolation),
+        )
+    }
+
+    fn calc_interpolated(
+        &self,
+        side: MarketSide,
+        size: A,
+        interpolation: Interpolation,
+        extrapolation: Extrapolation,
+    ) -> Option<P>
+    where
+        P: FloatConvert,
+        A: FloatConvert,
+    {
+        let tiers = match side {
+            MarketSide::Bid => &self.bids,
+            MarketSide::Offer => &self.offers,
+        };
+        let upper = tiers.range(size..).next();
+
+        let (lower, upper) = match upper {
+            Some((&upper_size, &upper_price)) if upper_size == size => return Some(upper_price),
+            Some(upper) => match tiers.range(..size).next_back() {
+                Some(lower) if interpolation != Interpolation::None => (lower, upper),
+                _ => return Some(*upper.1),
+            },
+            None => match extrapolation {
+                Extrapolation::None => return None,
+                Extrapolation::Flat => return tiers.values().next_back().copied(),
+                Extrapolation::Linear => {
+                    let mut largest = tiers.iter().rev();
+
+                    match (largest.next(), largest.next()) {
+                        (Some(upper), Some(lower)) => (lower, upper),
+                        (Some((_, &price)), None) => return Some(price),
+                        _ => return None,
+                    }
+                }
+            },
+        };
+
+        let (lower_size, lower_price) = (lower.0.to_f64(), lower.1.to_f64());
+        let (upper_size, upper_price) = (upper.0.to_f64(), upper.1.to_f64());
+        let size = size.to_f64();
+
+        let fraction = match interpolation {
+            Interpolation::LogSize if lower_size > 0.0 => {
+                (size.ln() - lower_size.ln()) / (upper_size.ln() - lower_size.ln())
+            }
+            _ => (size - lower_size) / (upper_size - lower_size),
+        };
+
+        let price = P::from_f64(lower_price + (upper_price - lower_price) * fraction);
+
+        // As when validating an update only a price off the tick is snapped, and a price which cannot be snapped is kept
+        let snapped = match self
+            .reference
+            .get_spec()
+            .and_then(|spec| spec.get_tick_size().as_ref())
+            .and_then(|tick_size| tick_size.get_tick(price))
+        {
+            Some(tick) if !price.is_on_increment(tick) => match side {
+                MarketSide::Bid => price.round_down_to(tick),
+                MarketSide::Offer => price.round_up_to(tick),
+            },
+            _ => None,
+        };
+
+        Some(snapped.unwrap_or(price))
+    }
+}
+
+impl<P, A> Default for L2FullAmountMarketData<P, A>
+where
+    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<u8> + Increment,
+    A: Copy + Ord + PartialOrd + Add<Output = A> + Div<Output = A> + From<u8> + Increment,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P, A> IncrementalBook for L2FullAmountMarketData<P, A>
+where
+    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<u8> + Increment,
+    A: Copy + Ord + PartialOrd + Add<Output = A> + Div<Output = A> + From<u8> + Increment,
+{
+    type Update = L2Update<P, A>;
+    type SnapshotEntry = (MarketSide, P, A);
+
+    fn apply_incremental(&mut self, update: &Self::Update) -> Result<(), MarketDataError> {
+        self.update(
+            update.get_action(),
+            update.get_side(),
+            update.get_price(),
+            update.get_size(),
+        )
+    }
+
+    fn apply_snapshot(&mut self, entries: &[Self::SnapshotEntry]) -> Result<(), MarketDataError> {
+        Self::apply_snapshot(self, entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market_data::test_callback::TestCallback;
+    use crate::market_data::{AssetClass, ManualClock, SpecPolicy, TickSize};
+
+    #[test]
+    fn default_sweepable() {
+        let test = L2SweepableMarketData::default();
+
+        assert_eq!(test.get_price(1), BidOffer::default());
+    }
+
+    #[test]
+    fn default_full_amount() {
+        let test = L2FullAmountMarketData::<i32, i32>::default();
+
+        assert_eq!(test.get_price(1), BidOffer::default());
+    }
+
+    #[test]
+    fn sweepable_get_basic_price() {
+        let mut test = L2SweepableMarketData::new();
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 12, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 15, 20)
+            .unwrap();
+
+        assert_eq!(
+            test.get_price(1),
+            BidOffer::new_with_price(Some(12), Some(15))
+        );
+        assert_eq!(
+            test.get_price(10),
+            BidOffer::new_with_price(Some(12), Some(15))
+        );
+        assert_eq!(test.get_price(11), BidOffer::new_with_price(None, Some(15)));
+        assert_eq!(test.get_price(20), BidOffer::new_with_price(None, Some(15)));
+        assert_eq!(test.get_price(21), BidOffer::new_with_price(None, None));
+    }
+
+    #[test]
+    fn sweepable_get_vwap_price() {
+        let mut test = L2SweepableMarketData::new();
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 12, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Bid, 10, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Bid, 8, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Bid, 6, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 16, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 20, 20)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 24, 10)
+            .unwrap();
+
+        assert_eq!(
+            test.get_price(20),
+            BidOffer::new_with_price(Some(11), Some(18))
+        );
+
+        assert_eq!(
+            test.get_price(40),
+            BidOffer::new_with_price(Some(9), Some(20))
+        );
+    }
+
+    #[test]
+    fn sweepable_vwap_large_notional() {
+        let mut test: L2SweepableMarketData<i32, i32> = L2SweepableMarketData::new();
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 150_000, 1_000_000)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Bid, 140_000, 1_000_000)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 160_000, 2_000_000_000)
+            .unwrap();
+
+        assert_eq!(
+            test.get_price(2_000_000),
+            BidOffer::new_with_price(Some(145_000), Some(160_000))
+        );
+        assert_eq!(
+            test.get_price(2_000_000_000),
+            BidOffer::new_with_price(None, Some(160_000))
+        );
+    }
+
+    #[test]
+    fn sweepable_top_of_book() {
+        let mut test = L2SweepableMarketData::new();
+
+        let callback = Rc::new(TestCallback::new());
+        test.get_top_of_book().subscribe(callback.clone());
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 12, 10)
+            .unwrap();
+        assert!(callback.is_called());
+        assert_eq!(
+            test.get_top_of_book().get_price(10),
+            BidOffer::new_with_price(Some(12), None)
+        );
+
+        callback.reset();
+        test.update(UpdateAction::Add, MarketSide::Bid, 11, 10)
+            .unwrap();
+        test.update(UpdateAction::Update, MarketSide::Bid, 11, 20)
+            .unwrap();
+        assert!(!callback.is_called());
+
+        test.update(UpdateAction::Update, MarketSide::Bid, 12, 20)
+            .unwrap();
+        assert!(callback.is_called());
+        assert_eq!(*test.get_top_of_book().get_max_bid(), Some(20));
+
+        callback.reset();
+        test.update(UpdateAction::Remove, MarketSide::Bid, 12, 0)
+            .unwrap();
+        assert!(callback.is_called());
+        assert_eq!(*test.get_top_of_book().get_bid(), Some(11));
+
+        callback.reset();
+        test.clear();
+        assert!(callback.is_called());
+        assert_eq!(*test.get_top_of_book().get_bid(), None);
+    }
+
+    #[test]
+    fn full_amount_get_basic_price() {
+        let mut test: L2FullAmountMarketData<i32, i32> = L2FullAmountMarketData::new();
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 12, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 15, 20)
+            .unwrap();
+
+        assert_eq!(
+            test.get_price(1),
+            BidOffer::new_with_price(Some(12), Some(15))
+        );
+        assert_eq!(
+            test.get_price(10),
+            BidOffer::new_with_price(Some(12), Some(15))
+        );
+        assert_eq!(test.get_price(11), BidOffer::new_with_price(None, Some(15)));
+        assert_eq!(test.get_price(20), BidOffer::new_with_price(None, Some(15)));
+        assert_eq!(test.get_price(21), BidOffer::new_with_price(None, None));
+    }
+
+    #[test]
+    fn full_amount_get_vwap_price() {
+        let mut test = L2FullAmountMarketData::new();
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 12, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Bid, 10, 20)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Bid, 8, 30)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Bid, 6, 40)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 16, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 20, 30)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 24, 40)
+            .unwrap();
+
+        assert_eq!(
+            test.get_price(20),
+            BidOffer::new_with_price(Some(10), Some(20))
+        );
+
+        assert_eq!(
+            test.get_price(40),
+            BidOffer::new_with_price(Some(6), Some(24))
+        );
+    }
+
+    #[test]
+    fn sweepable_full_amount_conversion() {
+        let mut test = L2SweepableMarketData::<i32, i32>::new();
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 12, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Bid, 8, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 16, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 20, 30)
+            .unwrap();
+
+        let full_amount = test.to_full_amount(&[10, 20, 40]);
+
+        assert_eq!(
+            full_amount.get_price(20),
+            BidOffer::new_with_price(Some(10), Some(18))
+        );
+        assert_eq!(
+            full_amount.get_price(40),
+            BidOffer::new_with_price(None, Some(19))
+        );
+
+        let sweepable = full_amount.to_sweepable();
+
+        assert_eq!(
+            sweepable.get_levels(MarketSide::Bid).collect::<Vec<_>>(),
+            vec![(12, 10), (8, 10)]
+        );
+        assert_eq!(
+            sweepable.get_levels(MarketSide::Offer).collect::<Vec<_>>(),
+            vec![(16, 10), (20, 30)]
+        );
+    }
+
+    #[test]
+    fn full_amount_to_sweepable_keeps_price_order() {
+        let mut test = L2FullAmountMarketData::<i32, i32>::new();
+
+        test.update(UpdateAction::Add, MarketSide::Offer, 110, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 105, 20)
+            .unwrap();
+
+        assert_eq!(
+            test.to_sweepable()
+                .get_levels(MarketSide::Offer)
+                .collect::<Vec<_>>(),
+            vec![(110, 20)]
+        );
+    }
+
+    #[test]
+    fn full_amount_interpolated_price() {
+        let mut test = L2FullAmountMarketData::<i32, i32>::new();
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 100, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Bid, 90, 20)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 110, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 120, 20)
+            .unwrap();
+
+        assert_eq!(
+            test.get_interpolated_price(15, Interpolation::None, Extrapolation::None),
+            BidOffer::new_with_price(Some(90), Some(120))
+        );
+
+        assert_eq!(
+            test.get_interpolated_price(5, Interpolation::Linear, Extrapolation::None),
+            BidOffer::new_with_price(Some(100), Some(110))
+        );
+        assert_eq!(
+            test.get_interpolated_price(15, Interpolation::Linear, Extrapolation::None),
+            BidOffer::new_with_price(Some(95), Some(115))
+        );
+        assert_eq!(
+            test.get_interpolated_price(20, Interpolation::Linear, Extrapolation::None),
+            BidOffer::new_with_price(Some(90), Some(120))
+        );
+        assert_eq!(
+            test.get_interpolated_price(30, Interpolation::Linear, Extrapolation::None),
+            BidOffer::new()
+        );
+
+        assert_eq!(
+            test.get_interpolated_price(15, Interpolation::LogSize, Extrapolation::Linear),
+            BidOffer::new_with_price(Some(94), Some(116))
+        );
+        assert_eq!(
+            test.get_interpolated_price(40, Interpolation::LogSize, Extrapolation::Linear),
+            BidOffer::new_with_price(Some(80), Some(130))
+        );
+
+        assert_eq!(
+            test.get_interpolated_price(40, Interpolation::Linear, Extrapolation::Flat),
+            BidOffer::new_with_price(Some(90), Some(120))
+        );
+
+        test.set_instrument_spec(Some(
+            InstrumentSpec::new(SpecPolicy::Reject).with_tick_size(TickSize::Fixed(4)),
+        ));
+        assert_eq!(
+            test.get_interpolated_price(15, Interpolation::Linear, Extrapolation::Flat),
+            BidOffer::new_with_price(Some(92), Some(116))
+        );
+    }
+
+    #[test]
+    fn full_amount_instrument_spec_snap() {
+        let mut test = L2FullAmountMarketData::new();
+
+        test.set_instrument_spec(Some(
+            InstrumentSpec::new(crate::market_data::SpecPolicy::Snap)
+                .with_tick_size(crate::market_data::TickSize::Fixed(2))
+                .with_lot_size(10),
+        ));
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 13, 15)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 15, 25)
+            .unwrap();
+
+        assert_eq!(
+            test.get_price(10),
+            BidOffer::new_with_price(Some(12), Some(16))
+        );
+        assert_eq!(test.get_price(15), BidOffer::new_with_price(None, Some(16)));
+
+        test.update(UpdateAction::Remove, MarketSide::Offer, 0, 29)
+            .unwrap();
+
+        assert_eq!(test.get_price(10), BidOffer::new_with_price(Some(12), None));
+    }
+
+    #[test]
+    fn sweepable_modify_delete_clear() {
+        let mut test = L2SweepableMarketData::new();
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 12, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Bid, 10, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 16, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 20, 10)
+            .unwrap();
+
+        assert_eq!(
+            test.get_price(20),
+            BidOffer::new_with_price(Some(11), Some(18))
+        );
+        assert_eq!(test.get_price(40), BidOffer::default());
+
+        test.update(UpdateAction::Update, MarketSide::Bid, 12, 20)
+            .unwrap();
+        test.update(UpdateAction::Update, MarketSide::Offer, 16, 20)
+            .unwrap();
+        test.update(UpdateAction::Update, MarketSide::Bid, 10, 20)
+            .unwrap();
+        test.update(UpdateAction::Update, MarketSide::Offer, 20, 20)
+            .unwrap();
+
+        assert_eq!(
+            test.get_price(20),
+            BidOffer::new_with_price(Some(12), Some(16))
         );
         assert_eq!(
             test.get_price(40),
             BidOffer::new_with_price(Some(11), Some(18))
         );
 
-        test.update(UpdateAction::Remove, MarketSide::Bid, 10, 20);
-        test.update(UpdateAction::Remove, MarketSide::Offer, 20, 20);
+        test.update(UpdateAction::Remove, MarketSide::Bid, 10, 20)
+            .unwrap();
+        test.update(UpdateAction::Remove, MarketSide::Offer, 20, 20)
+            .unwrap();
 
         assert_eq!(
             test.get_price(20),
@@ -398,26 +2943,34 @@ mod tests {
     fn full_amount_modify_delete_clear() {
         let mut test = L2FullAmountMarketData::new();
 
-        test.update(UpdateAction::Add, MarketSide::Bid, 12, 10);
-        test.update(UpdateAction::Add, MarketSide::Bid, 10, 20);
-        test.update(UpdateAction::Add, MarketSide::Offer, 16, 10);
-        test.update(UpdateAction::Add, MarketSide::Offer, 20, 20);
+        test.update(UpdateAction::Add, MarketSide::Bid, 12, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Bid, 10, 20)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 16, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 20, 20)
+            .unwrap();
 
         assert_eq!(
             test.get_price(10),
             BidOffer::new_with_price(Some(12), Some(16))
         );
 
-        test.update(UpdateAction::Update, MarketSide::Bid, 13, 10);
-        test.update(UpdateAction::Update, MarketSide::Offer, 15, 10);
+        test.update(UpdateAction::Update, MarketSide::Bid, 13, 10)
+            .unwrap();
+        test.update(UpdateAction::Update, MarketSide::Offer, 15, 10)
+            .unwrap();
 
         assert_eq!(
             test.get_price(10),
             BidOffer::new_with_price(Some(13), Some(15))
         );
 
-        test.update(UpdateAction::Remove, MarketSide::Bid, 13, 10);
-        test.update(UpdateAction::Remove, MarketSide::Offer, 15, 10);
+        test.update(UpdateAction::Remove, MarketSide::Bid, 13, 10)
+            .unwrap();
+        test.update(UpdateAction::Remove, MarketSide::Offer, 15, 10)
+            .unwrap();
 
         assert_eq!(
             test.get_price(10),
@@ -429,4 +2982,620 @@ mod tests {
         assert_eq!(test.get_price(20), BidOffer::default());
         assert_eq!(test.get_price(40), BidOffer::default());
     }
+
+    #[test]
+    fn diff_turns_one_book_into_another() {
+        let mut before = L2SweepableMarketData::<i32, i32>::new();
+        let mut after = L2SweepableMarketData::<i32, i32>::new();
+
+        before
+            .apply_snapshot(&[
+                (MarketSide::Bid, 12, 10),
+                (MarketSide::Bid, 11, 10),
+                (MarketSide::Offer, 15, 10),
+            ])
+            .unwrap();
+        after
+            .apply_snapshot(&[
+                (MarketSide::Bid, 12, 20),
+                (MarketSide::Bid, 10, 10),
+                (MarketSide::Offer, 15, 10),
+            ])
+            .unwrap();
+
+        let updates = before.diff(&after);
+
+        assert_eq!(
+            updates,
+            vec![
+                L2Update::new(UpdateAction::Remove, MarketSide::Bid, 11, 10),
+                L2Update::new(UpdateAction::Add, MarketSide::Bid, 10, 10),
+                L2Update::new(UpdateAction::Update, MarketSide::Bid, 12, 20),
+            ]
+        );
+
+        before.apply_batch(&updates).unwrap();
+        assert_eq!(before.diff(&after), vec![]);
+
+        let mut full_amount = L2FullAmountMarketData::<i32, i32>::new();
+        full_amount
+            .apply_snapshot(&[(MarketSide::Bid, 12, 10), (MarketSide::Bid, 11, 20)])
+            .unwrap();
+
+        assert_eq!(
+            full_amount.diff(&L2FullAmountMarketData::new()),
+            vec![
+                L2Update::new(UpdateAction::Remove, MarketSide::Bid, 12, 10),
+                L2Update::new(UpdateAction::Remove, MarketSide::Bid, 11, 20),
+            ]
+        );
+    }
+
+    #[test]
+    fn sweepable_cross_policies() {
+        let mut test = L2SweepableMarketData::<i32, i32>::new();
+
+        test.apply_snapshot(&[
+            (MarketSide::Bid, 10, 50),
+            (MarketSide::Offer, 12, 50),
+            (MarketSide::Offer, 13, 50),
+        ])
+        .unwrap();
+
+        test.set_cross_policy(CrossPolicy::Reject);
+        assert_eq!(
+            test.update(UpdateAction::Add, MarketSide::Bid, 13, 50),
+            Err(MarketDataError::CrossedBook)
+        );
+        assert_eq!(
+            test.apply_batch(&[
+                L2Update::new(UpdateAction::Remove, MarketSide::Offer, 12, 0),
+                L2Update::new(UpdateAction::Add, MarketSide::Bid, 13, 50),
+                L2Update::new(UpdateAction::Add, MarketSide::Bid, 14, 50),
+            ]),
+            Err(MarketDataError::CrossedBook)
+        );
+        assert_eq!(
+            test.apply_snapshot(&[(MarketSide::Bid, 13, 50), (MarketSide::Offer, 12, 50)]),
+            Err(MarketDataError::CrossedBook)
+        );
+        assert_eq!(test.get_levels(MarketSide::Offer).count(), 2);
+
+        test.apply_batch(&[
+            L2Update::new(UpdateAction::Remove, MarketSide::Offer, 12, 0),
+            L2Update::new(UpdateAction::Add, MarketSide::Bid, 13, 50),
+        ])
+        .unwrap();
+        assert_eq!(test.get_cross_status(), CrossStatus::Locked);
+
+        test.set_cross_policy(CrossPolicy::RemoveStale);
+        test.update(UpdateAction::Add, MarketSide::Offer, 9, 50)
+            .unwrap();
+        assert_eq!(test.get_levels(MarketSide::Bid).count(), 0);
+        assert_eq!(test.get_cross_status(), CrossStatus::Normal);
+
+        test.set_cross_policy(CrossPolicy::KeepAndFlag);
+        test.update(UpdateAction::Add, MarketSide::Bid, 10, 50)
+            .unwrap();
+        assert_eq!(test.get_cross_status(), CrossStatus::Crossed);
+    }
+
+    #[test]
+    fn sweepable_strict_mode() {
+        let mut test = L2SweepableMarketData::<i32, i32>::new();
+
+        test.set_update_mode(UpdateMode::Strict);
+        test.update(UpdateAction::Add, MarketSide::Bid, 10, 50)
+            .unwrap();
+
+        assert_eq!(
+            test.update(UpdateAction::Add, MarketSide::Bid, 10, 60),
+            Err(MarketDataError::LevelExists)
+        );
+        assert_eq!(
+            test.update(UpdateAction::Update, MarketSide::Bid, 9, 60),
+            Err(MarketDataError::UnknownLevel)
+        );
+        assert_eq!(
+            test.update(UpdateAction::Remove, MarketSide::Offer, 10, 0),
+            Err(MarketDataError::UnknownLevel)
+        );
+        assert_eq!(
+            test.update(UpdateAction::Update, MarketSide::Bid, 10, 0),
+            Err(MarketDataError::InvalidSize)
+        );
+
+        // A batch is checked against the levels left by the updates before it
+        assert_eq!(
+            test.apply_batch(&[
+                L2Update::new(UpdateAction::Remove, MarketSide::Bid, 10, 0),
+                L2Update::new(UpdateAction::Update, MarketSide::Bid, 10, 60),
+            ]),
+            Err(MarketDataError::UnknownLevel)
+        );
+        test.apply_batch(&[
+            L2Update::new(UpdateAction::Remove, MarketSide::Bid, 10, 0),
+            L2Update::new(UpdateAction::Add, MarketSide::Bid, 10, 60),
+        ])
+        .unwrap();
+        assert_eq!(
+            test.get_levels(MarketSide::Bid).collect::<Vec<_>>(),
+            vec![(10, 60)]
+        );
+    }
+
+    #[test]
+    fn full_amount_update_modes() {
+        let mut test = L2FullAmountMarketData::<i32, i32>::new();
+
+        test.update(UpdateAction::Update, MarketSide::Offer, 12, 50)
+            .unwrap();
+        assert_eq!(test.get_price(50), BidOffer::new());
+
+        test.set_update_mode(UpdateMode::Upsert);
+        test.update(UpdateAction::Update, MarketSide::Offer, 12, 50)
+            .unwrap();
+        assert_eq!(test.get_price(50), BidOffer::new_with_price(None, Some(12)));
+
+        test.set_update_mode(UpdateMode::Strict);
+        assert_eq!(
+            test.update(UpdateAction::Add, MarketSide::Bid, 10, -50),
+            Err(MarketDataError::InvalidSize)
+        );
+        assert_eq!(
+            test.apply_batch(&[
+                L2Update::new(UpdateAction::Add, MarketSide::Bid, 10, 100),
+                L2Update::new(UpdateAction::Add, MarketSide::Bid, 9, 100),
+            ]),
+            Err(MarketDataError::LevelExists)
+        );
+        assert_eq!(test.get_price(100), BidOffer::new_with_price(None, None));
+    }
+
+    #[test]
+    fn sweepable_fair_values() {
+        let mut test = L2SweepableMarketData::<i32, i32>::new();
+
+        test.apply_snapshot(&[
+            (MarketSide::Bid, 100, 30),
+            (MarketSide::Bid, 90, 10),
+            (MarketSide::Offer, 110, 10),
+        ])
+        .unwrap();
+
+        assert_eq!(test.get_weighted_mid(), Some(103));
+        assert_eq!(test.get_microprice(), Some(108));
+        assert_eq!(test.get_mid_at_size(10), Some(105));
+
+        // Only the bid can fill the size
+        assert_eq!(test.get_mid_at_size(40), Some(97));
+        test.set_one_sided_policy(OneSidedPolicy::None);
+        assert_eq!(test.get_mid_at_size(40), None);
+        test.set_one_sided_policy(OneSidedPolicy::LastMid);
+        assert_eq!(test.get_mid_at_size(40), Some(105));
+
+        test.clear();
+        assert_eq!(test.get_microprice(), Some(105));
+    }
+
+    #[test]
+    fn sweepable_depth_metrics() {
+        let mut test = L2SweepableMarketData::<i32, i32>::new();
+
+        test.set_instrument_spec(Some(
+            InstrumentSpec::new(SpecPolicy::Reject)
+                .with_tick_size(TickSize::Banded(vec![(0, 1), (100, 5)])),
+        ));
+        test.apply_snapshot(&[
+            (MarketSide::Bid, 98, 10),
+            (MarketSide::Bid, 95, 20),
+            (MarketSide::Offer, 110, 30),
+            (MarketSide::Offer, 120, 40),
+        ])
+        .unwrap();
+
+        assert_eq!(test.get_level_count(MarketSide::Offer), 2);
+        assert_eq!(test.get_imbalance(2), Some(-0.4));
+        // The mid of 104 is 2 ticks from 110 and 4 from 98
+        assert_eq!(test.get_spread_ticks(), Some(4.0));
+        assert_eq!(
+            test.get_depth_within_ticks(MarketSide::Offer, 2.0),
+            Some(30)
+        );
+        assert_eq!(test.get_depth_within_ticks(MarketSide::Bid, 2.0), Some(0));
+        assert_eq!(test.get_depth_within_ticks(MarketSide::Bid, 4.0), Some(10));
+
+        test.update(UpdateAction::Remove, MarketSide::Offer, 110, 0)
+            .unwrap();
+        test.update(UpdateAction::Remove, MarketSide::Offer, 120, 0)
+            .unwrap();
+        assert_eq!(test.get_spread_bps(), None);
+        assert_eq!(test.get_depth_within_bps(MarketSide::Bid, 100.0), None);
+    }
+
+    #[test]
+    fn sweepable_new_actions() {
+        let mut test = L2SweepableMarketData::<i32, i32>::new();
+
+        test.apply_snapshot(&[
+            (MarketSide::Bid, 10, 50),
+            (MarketSide::Bid, 9, 50),
+            (MarketSide::Offer, 12, 50),
+        ])
+        .unwrap();
+
+        test.update(UpdateAction::ClearSide, MarketSide::Bid, 0, 0)
+            .unwrap();
+        assert_eq!(test.get_levels(MarketSide::Bid).count(), 0);
+        assert_eq!(test.get_price(50), BidOffer::new_with_price(None, Some(12)));
+
+        test.set_update_mode(UpdateMode::Strict);
+        test.update(UpdateAction::Replace, MarketSide::Offer, 12, 20)
+            .unwrap();
+        test.update(UpdateAction::Replace, MarketSide::Offer, 13, 20)
+            .unwrap();
+        assert_eq!(
+            test.get_levels(MarketSide::Offer).collect::<Vec<_>>(),
+            vec![(12, 20), (13, 20)]
+        );
+
+        test.apply_batch(&[
+            L2Update::new(UpdateAction::Snapshot, MarketSide::Bid, 11, 30),
+            L2Update::new(UpdateAction::Add, MarketSide::Offer, 12, 30),
+        ])
+        .unwrap();
+        assert_eq!(
+            test.get_levels(MarketSide::Bid).collect::<Vec<_>>(),
+            vec![(11, 30)]
+        );
+        assert_eq!(
+            test.get_levels(MarketSide::Offer).collect::<Vec<_>>(),
+            vec![(12, 30)]
+        );
+
+        // The snapshot clears the offer at 12 first, so the strict add is not of an existing level
+        test.set_cross_policy(CrossPolicy::Reject);
+        test.update(UpdateAction::Snapshot, MarketSide::Offer, 10, 10)
+            .unwrap();
+        assert_eq!(test.get_price(10), BidOffer::new_with_price(None, Some(10)));
+    }
+
+    #[test]
+    fn full_amount_new_actions() {
+        let mut test = L2FullAmountMarketData::<i32, i32>::new();
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 10, 50)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 12, 50)
+            .unwrap();
+        test.update(UpdateAction::Replace, MarketSide::Offer, 13, 50)
+            .unwrap();
+        assert_eq!(
+            test.get_price(50),
+            BidOffer::new_with_price(Some(10), Some(13))
+        );
+
+        test.update(UpdateAction::ClearSide, MarketSide::Offer, 0, 0)
+            .unwrap();
+        assert_eq!(test.get_price(50), BidOffer::new_with_price(Some(10), None));
+
+        test.update(UpdateAction::Snapshot, MarketSide::Offer, 11, 100)
+            .unwrap();
+        assert_eq!(test.get_price(50), BidOffer::new_with_price(None, Some(11)));
+    }
+
+    #[test]
+    fn sweepable_quality() {
+        let clock = Rc::new(ManualClock::new(Duration::from_secs(100)));
+        let mut test = L2SweepableMarketData::<i32, i32>::new();
+
+        test.set_clock(clock.clone());
+        test.set_time_to_live(MarketSide::Offer, Some(Duration::from_secs(5)));
+        test.update(UpdateAction::Add, MarketSide::Bid, 10, 50)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 12, 50)
+            .unwrap();
+
+        test.set_quality(MarketSide::Bid, PriceQuality::Closed);
+        assert_eq!(
+            test.get_top_of_book().get_quality(MarketSide::Bid),
+            PriceQuality::Closed
+        );
+
+        test.check_stale(Duration::from_secs(106));
+        let quoted = test.get_quoted_price(50);
+        assert_eq!(quoted.get_quality(MarketSide::Bid), PriceQuality::Closed);
+        assert_eq!(quoted.get_quality(MarketSide::Offer), PriceQuality::Stale);
+        assert_eq!(
+            quoted.get_price(),
+            BidOffer::new_with_price(Some(10), Some(12))
+        );
+        assert_eq!(quoted.get_firm_price(), BidOffer::new());
+    }
+
+    #[test]
+    fn full_amount_status() {
+        let hours = |hours: u64| Duration::from_secs(hours * 60 * 60);
+        let mut test = L2FullAmountMarketData::<i32, i32>::new();
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 10, 50)
+            .unwrap();
+        test.set_quality(MarketSide::Bid, PriceQuality::Derived(3));
+        test.set_schedule(Some(TradingSchedule::new(vec![
+            (hours(8), MarketStatus::Auction),
+            (hours(9), MarketStatus::Continuous),
+            (hours(16), MarketStatus::Closed),
+        ])));
+
+        assert_eq!(test.check_schedule(hours(8)), Some(MarketStatus::Auction));
+        assert_eq!(test.get_price(50), BidOffer::new());
+        assert_eq!(
+            test.get_interpolated_price(50, Interpolation::Linear, Extrapolation::Flat),
+            BidOffer::new()
+        );
+        assert_eq!(test.get_quality(MarketSide::Bid), PriceQuality::Derived(3));
+        assert_eq!(
+            test.get_quality(MarketSide::Offer),
+            PriceQuality::Indicative
+        );
+
+        test.set_status(MarketStatus::Halted).unwrap();
+        assert_eq!(test.check_schedule(hours(9)), None);
+        assert_eq!(test.check_schedule(hours(16)), Some(MarketStatus::Closed));
+        assert_eq!(
+            test.get_quoted_price(50).get_price(),
+            BidOffer::new_with_price(Some(10), None)
+        );
+    }
+
+    #[test]
+    fn sweepable_instrument_spec() {
+        let mut test = L2SweepableMarketData::<i32, i32>::new();
+
+        test.set_instrument(Some(Rc::new(
+            Instrument::new("VOD.L", AssetClass::Equity, "GBP").with_spec(
+                InstrumentSpec::new(SpecPolicy::Reject)
+                    .with_tick_size(TickSize::Fixed(5))
+                    .with_lot_size(100),
+            ),
+        )));
+
+        assert_eq!(
+            test.update(UpdateAction::Add, MarketSide::Bid, 11, 100),
+            Err(MarketDataError::OffTickPrice)
+        );
+        assert_eq!(
+            test.update(UpdateAction::Add, MarketSide::Bid, 10, 150),
+            Err(MarketDataError::OffLotSize)
+        );
+
+        // The specification set on the book takes precedence and outlives the instrument
+        test.set_instrument_spec(Some(
+            InstrumentSpec::new(SpecPolicy::Reject).with_tick_size(TickSize::Fixed(2)),
+        ));
+        test.set_instrument(None);
+
+        assert_eq!(
+            test.update(UpdateAction::Add, MarketSide::Bid, 12, 150),
+            Ok(())
+        );
+        assert_eq!(
+            test.update(UpdateAction::Add, MarketSide::Bid, 11, 150),
+            Err(MarketDataError::OffTickPrice)
+        );
+
+        test.set_instrument_spec(None);
+
+        assert_eq!(
+            test.update(UpdateAction::Add, MarketSide::Bid, 11, 150),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn sweepable_stale_until_expired_levels_updated() {
+        let clock = Rc::new(ManualClock::new(Duration::from_secs(100)));
+        let mut test = L2SweepableMarketData::<i32, i32>::new();
+
+        test.set_clock(clock.clone());
+        test.set_time_to_live(MarketSide::Bid, Some(Duration::from_secs(5)));
+        test.update(UpdateAction::Add, MarketSide::Bid, 10, 50)
+            .unwrap();
+
+        clock.set(Duration::from_secs(106));
+        assert_eq!(
+            test.check_stale(Duration::from_secs(106)),
+            vec![MarketSide::Bid]
+        );
+
+        // An update to a missing level is ignored, and a new level leaves the expired one in the book
+        test.update(UpdateAction::Update, MarketSide::Bid, 7, 50)
+            .unwrap();
+        assert_eq!(test.get_quality(MarketSide::Bid), PriceQuality::Stale);
+        test.update(UpdateAction::Add, MarketSide::Bid, 8, 20)
+            .unwrap();
+        assert_eq!(test.get_quality(MarketSide::Bid), PriceQuality::Stale);
+
+        test.update(UpdateAction::Update, MarketSide::Bid, 10, 40)
+            .unwrap();
+        assert_eq!(test.get_quality(MarketSide::Bid), PriceQuality::Firm);
+
+        clock.set(Duration::from_secs(112));
+        assert_eq!(
+            test.check_stale(Duration::from_secs(112)),
+            vec![MarketSide::Bid]
+        );
+        test.update(UpdateAction::Remove, MarketSide::Bid, 8, 20)
+            .unwrap();
+        assert_eq!(test.get_quality(MarketSide::Bid), PriceQuality::Stale);
+        test.update(UpdateAction::Remove, MarketSide::Bid, 10, 40)
+            .unwrap();
+        assert_eq!(test.get_quality(MarketSide::Bid), PriceQuality::Firm);
+    }
+
+    #[test]
+    fn diff_replays_without_crossing() {
+        for policy in [
+            CrossPolicy::Reject,
+            CrossPolicy::RemoveStale,
+            CrossPolicy::KeepAndFlag,
+        ] {
+            let mut before = L2SweepableMarketData::<i32, i32>::new();
+            let mut after = L2SweepableMarketData::<i32, i32>::new();
+
+            before.set_cross_policy(policy);
+            before.set_update_mode(UpdateMode::Strict);
+            before
+                .apply_snapshot(&[(MarketSide::Bid, 10, 50), (MarketSide::Offer, 11, 50)])
+                .unwrap();
+            after
+                .apply_snapshot(&[(MarketSide::Bid, 12, 50), (MarketSide::Offer, 13, 50)])
+                .unwrap();
+
+            for update in before.diff(&after) {
+                before.apply_incremental(&update).unwrap();
+                assert_eq!(before.get_cross_status(), CrossStatus::Normal);
+            }
+
+            assert_eq!(before.diff(&after), vec![]);
+        }
+    }
+
+    #[test]
+    fn full_amount_interpolated_price_on_tick() {
+        let mut test = L2FullAmountMarketData::<f64, i64>::new();
+
+        test.update(UpdateAction::Add, MarketSide::Offer, 1.1000, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 1.1010, 20)
+            .unwrap();
+        test.set_instrument_spec(Some(
+            InstrumentSpec::new(SpecPolicy::Reject).with_tick_size(TickSize::Fixed(0.0)),
+        ));
+
+        let offer = test
+            .get_interpolated_price(15, Interpolation::Linear, Extrapolation::Flat)
+            .get_offer()
+            .unwrap();
+        assert!((offer - 1.1005).abs() < 1e-9);
+
+        test.set_instrument_spec(Some(
+            InstrumentSpec::new(SpecPolicy::Reject).with_tick_size(TickSize::Fixed(0.0005)),
+        ));
+
+        let offer = test
+            .get_interpolated_price(15, Interpolation::Linear, Extrapolation::Flat)
+            .get_offer()
+            .unwrap();
+        assert!((offer - 1.1005).abs() < 1e-9);
+
+        let offer = test
+            .get_interpolated_price(12, Interpolation::Linear, Extrapolation::Flat)
+            .get_offer()
+            .unwrap();
+        assert!((offer - 1.1005).abs() < 1e-9);
+    }
+
+    #[test]
+    fn full_amount_stale_notifies_subscribers() {
+        let clock = Rc::new(ManualClock::new(Duration::from_secs(100)));
+        let mut test = L2FullAmountMarketData::<i32, i32>::new();
+        let callback = Rc::new(TestCallback::new());
+
+        test.set_clock(clock.clone());
+        test.set_time_to_live(MarketSide::Offer, Some(Duration::from_secs(5)));
+        test.subscribe(callback.clone());
+        test.update(UpdateAction::Add, MarketSide::Offer, 10, 50)
+            .unwrap();
+        assert!(callback.is_called());
+
+        callback.reset();
+        assert_eq!(
+            test.check_stale(Duration::from_secs(106)),
+            vec![MarketSide::Offer]
+        );
+        assert_eq!(callback.get_stale(), vec![MarketSide::Offer]);
+        assert_eq!(test.check_stale(Duration::from_secs(107)), vec![]);
+        assert_eq!(callback.get_stale(), vec![MarketSide::Offer]);
+
+        test.set_time_to_live(MarketSide::Bid, Some(Duration::MAX));
+        test.update(UpdateAction::Add, MarketSide::Bid, 9, 50)
+            .unwrap();
+        assert_eq!(
+            test.get_price_at(50, Duration::MAX),
+            BidOffer::new_with_price(Some(9), None)
+        );
+    }
+
+    #[test]
+    fn full_amount_cross_policies() {
+        let mut test = L2FullAmountMarketData::<i32, i32>::new();
+
+        test.apply_snapshot(&[
+            (MarketSide::Bid, 10, 50),
+            (MarketSide::Offer, 12, 50),
+            (MarketSide::Offer, 13, 100),
+        ])
+        .unwrap();
+
+        test.set_cross_policy(CrossPolicy::Reject);
+        assert_eq!(
+            test.update(UpdateAction::Add, MarketSide::Bid, 13, 100),
+            Err(MarketDataError::CrossedBook)
+        );
+        assert_eq!(
+            test.apply_batch(&[
+                L2Update::new(UpdateAction::Remove, MarketSide::Offer, 12, 50),
+                L2Update::new(UpdateAction::Add, MarketSide::Bid, 13, 100),
+                L2Update::new(UpdateAction::Add, MarketSide::Bid, 14, 200),
+            ]),
+            Err(MarketDataError::CrossedBook)
+        );
+        assert_eq!(
+            test.apply_snapshot(&[(MarketSide::Bid, 13, 50), (MarketSide::Offer, 12, 50)]),
+            Err(MarketDataError::CrossedBook)
+        );
+        assert_eq!(
+            test.get_price(50),
+            BidOffer::new_with_price(Some(10), Some(12))
+        );
+
+        test.apply_batch(&[
+            L2Update::new(UpdateAction::Remove, MarketSide::Offer, 12, 50),
+            L2Update::new(UpdateAction::Add, MarketSide::Bid, 13, 100),
+        ])
+        .unwrap();
+        assert_eq!(test.get_cross_status(), CrossStatus::Locked);
+
+        test.set_cross_policy(CrossPolicy::RemoveStale);
+        test.update(UpdateAction::Add, MarketSide::Offer, 9, 50)
+            .unwrap();
+        assert_eq!(test.get_price(1), BidOffer::new_with_price(None, Some(9)));
+        assert_eq!(test.get_cross_status(), CrossStatus::Normal);
+
+        test.set_cross_policy(CrossPolicy::KeepAndFlag);
+        test.update(UpdateAction::Add, MarketSide::Bid, 10, 50)
+            .unwrap();
+        assert_eq!(test.get_cross_status(), CrossStatus::Crossed);
+
+        test.clear();
+        assert_eq!(test.get_cross_status(), CrossStatus::Normal);
+    }
+
+    #[test]
+    fn sweepable_impact_on_crossed_book() {
+        let mut test = L2SweepableMarketData::<u32, u32>::new();
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 10, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 8, 10)
+            .unwrap();
+        assert_eq!(test.get_cross_status(), CrossStatus::Crossed);
+
+        let impact = test.estimate_impact(MarketSide::Bid, 10).unwrap();
+
+        assert_eq!(impact.get_touch_slippage(), 0.0);
+        assert_eq!(impact.get_mid_slippage(), Some(-1.0));
+        assert!(impact.get_mid_slippage_bps().is_some_and(|bps| bps < 0.0));
+    }
 }
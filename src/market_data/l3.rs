@@ -1,18 +1,152 @@
-use super::{BidOffer, MarketSide, UpdateAction};
+use super::{
+    fair_value, instrument::InstrumentReference, l2::diff_side, metrics, vwap, BidOffer,
+    CrossPolicy, CrossStatus, FloatConvert, Increment, IncrementalBook, Instrument, InstrumentSpec,
+    L1MarketDataWithMax, L2MarketCallback, L2SweepableMarketData, L2Update, MarketDataError,
+    MarketImpact, MarketSide, MarketStatus, OneSidedPolicy, TradingSchedule, UpdateAction,
+    VwapAmount,
+};
 use std::{
+    cell::RefCell,
     collections::BTreeMap,
-    ops::{Add, AddAssign, Div, Mul, Sub, SubAssign},
+    ops::{Add, AddAssign, Bound, Div, Sub, SubAssign},
+    rc::Rc,
+    time::Duration,
 };
 
+/// A change to a single order of an L3 book.
+///
+/// # Generic Parameters
+///
+/// * `I` - The order id type that should be used.
+/// * `P` - The price type that should be used.
+/// * `A` - The amount type that should be used.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct L3Update<I, P, A> {
+    action: UpdateAction,
+    side: MarketSide,
+    id: I,
+    price: P,
+    size: A,
+}
+
+impl<I, P, A> L3Update<I, P, A>
+where
+    I: Copy,
+    P: Copy,
+    A: Copy,
+{
+    /// Use the new function to create a new L3Update
+    ///
+    /// # Parameters
+    ///
+    /// * `action` - The update action
+    /// * `side` - The side of the market
+    /// * `id` - The order id
+    /// * `price` - The price of the order
+    /// * `size` - The size of the order
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L3Update, MarketSide, UpdateAction};
+    ///
+    /// let update = L3Update::new(UpdateAction::Add, MarketSide::Bid, 1, 10, 100);
+    ///
+    /// assert_eq!(update.get_id(), 1);
+    /// assert_eq!(update.get_price(), 10);
+    /// ```
+    pub fn new(action: UpdateAction, side: MarketSide, id: I, price: P, size: A) -> Self {
+        Self {
+            action,
+            side,
+            id,
+            price,
+            size,
+        }
+    }
+
+    /// Get the update action
+    pub fn get_action(&self) -> UpdateAction {
+        self.action
+    }
+
+    /// Get the side of the market
+    pub fn get_side(&self) -> MarketSide {
+        self.side
+    }
+
+    /// Get the order id
+    pub fn get_id(&self) -> I {
+        self.id
+    }
+
+    /// Get the price of the order
+    pub fn get_price(&self) -> P {
+        self.price
+    }
+
+    /// Get the size of the order
+    pub fn get_size(&self) -> A {
+        self.size
+    }
+}
+
+/// A way in which the internal state of an L3 book is inconsistent
+///
+/// # Generic Parameters
+///
+/// * `I` - The order id type that should be used.
+/// * `P` - The price type that should be used.
+/// * `A` - The amount type that should be used.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum L3Inconsistency<I, P, A> {
+    /// The size of a level is not the sum of the sizes of its orders
+    LevelSize {
+        side: MarketSide,
+        price: P,
+        level_size: A,
+        order_size: A,
+    },
+    /// A level has no orders
+    EmptyLevel { side: MarketSide, price: P },
+    /// The order index points to a level which does not hold the order
+    MissingOrder { id: I, side: MarketSide, price: P },
+    /// A level holds an order which the order index does not point to
+    UnindexedOrder { id: I, side: MarketSide, price: P },
+    /// The best bid is above the best offer
+    Crossed { bid: P, offer: P },
+}
+
+/// The result of checking the internal state of an L3 book, listing every inconsistency found
+#[derive(Clone, PartialEq, Debug)]
+pub struct L3ValidationReport<I, P, A> {
+    inconsistencies: Vec<L3Inconsistency<I, P, A>>,
+}
+
+impl<I, P, A> L3ValidationReport<I, P, A> {
+    /// Returns true if no inconsistencies were found
+    pub fn is_valid(&self) -> bool {
+        self.inconsistencies.is_empty()
+    }
+
+    /// Get the inconsistencies found, in the order they were checked
+    pub fn get_inconsistencies(&self) -> &[L3Inconsistency<I, P, A>] {
+        &self.inconsistencies
+    }
+}
+
+#[derive(Clone)]
 struct MarketLiquidity<A> {
     size: A,
 }
 
+#[derive(Clone)]
 struct MarketLevel<I, A> {
     size: A,
     prices: BTreeMap<I, MarketLiquidity<A>>,
 }
 
+#[derive(Clone)]
 struct MarketLiquidityMap<P> {
     side: MarketSide,
     price: P,
@@ -21,7 +155,7 @@ struct MarketLiquidityMap<P> {
 pub struct L3MarketData<I, P, A>
 where
     I: Ord + Copy,
-    P: Ord + Copy + Add<Output = P> + Div<Output = P> + From<i32> + Mul<A, Output = A>,
+    P: Ord + Copy + Add<Output = P> + Div<Output = P> + From<u8> + Increment,
     A: Default
         + PartialOrd
         + AddAssign
@@ -29,17 +163,27 @@ where
         + Copy
         + Sub<Output = A>
         + Add<Output = A>
-        + Div<A, Output = P>,
+        + Div<Output = A>
+        + From<u8>
+        + Increment
+        + VwapAmount<P>,
 {
     bids: BTreeMap<P, MarketLevel<I, A>>,
     offers: BTreeMap<P, MarketLevel<I, A>>,
     prices: BTreeMap<I, MarketLiquidityMap<P>>,
+    reference: InstrumentReference<P, A>,
+    top_of_book: L1MarketDataWithMax<P, A>,
+    cross_policy: CrossPolicy,
+    one_sided_policy: OneSidedPolicy,
+    last_mid: Option<P>,
+
+    level_callbacks: RefCell<Vec<Rc<dyn L2MarketCallback<P, A>>>>,
 }
 
 impl<I, P, A> L3MarketData<I, P, A>
 where
     I: Ord + Copy,
-    P: Ord + Copy + Add<Output = P> + Div<Output = P> + From<i32> + Mul<A, Output = A>,
+    P: Ord + Copy + Add<Output = P> + Div<Output = P> + From<u8> + Increment,
     A: Default
         + PartialOrd
         + AddAssign
@@ -47,13 +191,22 @@ where
         + Copy
         + Sub<Output = A>
         + Add<Output = A>
-        + Div<A, Output = P>,
+        + Div<Output = A>
+        + From<u8>
+        + Increment
+        + VwapAmount<P>,
 {
     pub fn new() -> Self {
         Self {
             bids: BTreeMap::new(),
             offers: BTreeMap::new(),
             prices: BTreeMap::new(),
+            reference: InstrumentReference::new(),
+            top_of_book: L1MarketDataWithMax::new(),
+            cross_policy: CrossPolicy::KeepAndFlag,
+            one_sided_policy: OneSidedPolicy::AvailableSide,
+            last_mid: None,
+            level_callbacks: RefCell::new(Vec::new()),
         }
     }
 
@@ -64,9 +217,454 @@ where
         id: I,
         price: P,
         size: A,
-    ) -> Result<(), ()> {
+    ) -> Result<(), MarketDataError> {
+        let (price, size) = self.validate_update(action, side, id, price, size)?;
+        let mut levels = Vec::new();
+
+        self.apply_tracked(&mut levels, action, side, id, price, size)?;
+
+        self.publish_level_updates(levels);
+        self.refresh_top_of_book();
+
+        Ok(())
+    }
+
+    /// Apply a batch of updates as a single change.  The whole batch is checked against the book before any of it is
+    /// applied, so if any update is rejected, including an update or remove of an order which the batch does not leave in
+    /// the book, the book is left as it was.  Subscribers to the levels are sent one change per level and the top of book
+    /// is only refreshed once.  The cross policy is applied to each update in turn, and an update which fails once the
+    /// updates before it have been applied, such as a rejected crossing update or an update of an order removed as stale
+    /// by an earlier crossing update, also leaves the book as it was.
+    ///
+    /// # Parameters
+    ///
+    /// * `updates` - The updates to apply in order
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, L3MarketData, L3Update, MarketDataError, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data
+    ///     .apply_batch(&[
+    ///         L3Update::new(UpdateAction::Add, MarketSide::Bid, 1, 10, 50),
+    ///         L3Update::new(UpdateAction::Add, MarketSide::Bid, 2, 10, 30),
+    ///     ])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     market_data.apply_batch(&[
+    ///         L3Update::new(UpdateAction::Remove, MarketSide::Bid, 1, 10, 0),
+    ///         L3Update::new(UpdateAction::Remove, MarketSide::Bid, 1, 10, 0),
+    ///     ]),
+    ///     Err(MarketDataError::UnknownOrder)
+    /// );
+    /// assert_eq!(market_data.get_price(80), BidOffer::new_with_price(Some(10), None));
+    /// ```
+    pub fn apply_batch(&mut self, updates: &[L3Update<I, P, A>]) -> Result<(), MarketDataError> {
+        // The side of each order the batch has touched so far, or None if the batch has removed it
+        let mut orders: BTreeMap<I, Option<MarketSide>> = BTreeMap::new();
+        // The sides the batch has cleared, whose orders not yet touched are no longer in the book
+        let mut cleared: Vec<MarketSide> = Vec::new();
+        let mut validated = Vec::with_capacity(updates.len());
+
+        for update in updates {
+            let (action, side, id) = (update.get_action(), update.get_side(), update.get_id());
+            let existing = orders.get(&id).copied().unwrap_or_else(|| {
+                self.prices
+                    .get(&id)
+                    .map(|liquidity_map| liquidity_map.side)
+                    .filter(|existing_side| !cleared.contains(existing_side))
+            });
+
+            let order_side = match (action, existing) {
+                (UpdateAction::Update | UpdateAction::Remove, Some(existing_side)) => existing_side,
+                (UpdateAction::Update | UpdateAction::Remove, None) => {
+                    return Err(MarketDataError::UnknownOrder)
+                }
+                _ => side,
+            };
+
+            let (price, size) = match self.reference.get_spec() {
+                Some(spec) if !matches!(action, UpdateAction::Remove | UpdateAction::ClearSide) => {
+                    (
+                        spec.validate_price(&order_side, update.get_price())?,
+                        spec.validate_size(update.get_size())?,
+                    )
+                }
+                _ => (update.get_price(), update.get_size()),
+            };
+
+            match action {
+                UpdateAction::Remove => {
+                    orders.insert(id, None);
+                }
+                UpdateAction::ClearSide => {
+                    orders
+                        .values_mut()
+                        .filter(|order| **order == Some(side))
+                        .for_each(|order| *order = None);
+                    cleared.push(side);
+                }
+                UpdateAction::Snapshot => {
+                    orders.clear();
+                    cleared = vec![MarketSide::Bid, MarketSide::Offer];
+                    orders.insert(id, Some(order_side));
+                }
+                _ => {
+                    orders.insert(id, Some(order_side));
+                }
+            }
+            validated.push((action, side, id, price, size));
+        }
+
+        let mut levels: Vec<(MarketSide, P, Option<A>)> = Vec::new();
+        let (bids, offers, prices) = (self.bids.clone(), self.offers.clone(), self.prices.clone());
+
+        for (action, side, id, price, size) in validated {
+            if let Err(error) = self.apply_tracked(&mut levels, action, side, id, price, size) {
+                self.bids = bids;
+                self.offers = offers;
+                self.prices = prices;
+
+                return Err(error);
+            }
+        }
+
+        self.publish_level_updates(levels);
+        self.refresh_top_of_book();
+
+        Ok(())
+    }
+
+    /// Replace the whole book with the orders in a snapshot.  Every order is validated before anything is changed, so if
+    /// any is rejected the book is left as it was.  Subscribers to the levels are sent the changes between the old and new
+    /// book, and the top of book is only refreshed once.
+    ///
+    /// # Parameters
+    ///
+    /// * `orders` - The (side, order id, price, size) of every order in the book
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, L3MarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 1, 10, 50).unwrap();
+    /// market_data.apply_snapshot(&[(MarketSide::Bid, 2, 9, 50), (MarketSide::Offer, 3, 11, 50)]).unwrap();
+    ///
+    /// assert_eq!(market_data.get_price(50), BidOffer::new_with_price(Some(9), Some(11)));
+    /// ```
+    pub fn apply_snapshot(
+        &mut self,
+        orders: &[(MarketSide, I, P, A)],
+    ) -> Result<(), MarketDataError> {
+        let orders = orders
+            .iter()
+            .map(|&(side, id, price, size)| {
+                self.validate_update(UpdateAction::Add, side, id, price, size)
+                    .map(|(price, size)| (side, id, price, size))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if self.cross_policy == CrossPolicy::Reject {
+            let sides: BTreeMap<I, (MarketSide, P)> = orders
+                .iter()
+                .map(|&(side, id, price, _)| (id, (side, price)))
+                .collect();
+            let best = |best_side| {
+                sides
+                    .values()
+                    .filter(move |(side, _)| *side == best_side)
+                    .map(|&(_, price)| price)
+            };
+
+            if let (Some(bid), Some(offer)) =
+                (best(MarketSide::Bid).max(), best(MarketSide::Offer).min())
+            {
+                if bid > offer {
+                    return Err(MarketDataError::CrossedBook);
+                }
+            }
+        }
+
+        let mut levels: Vec<_> = if self.level_callbacks.borrow().is_empty() {
+            Vec::new()
+        } else {
+            [MarketSide::Bid, MarketSide::Offer]
+                .into_iter()
+                .flat_map(|side| {
+                    self.get_levels(side)
+                        .map(move |(price, size)| (side, price, Some(size)))
+                })
+                .collect()
+        };
+
+        self.bids.clear();
+        self.offers.clear();
+        self.prices.clear();
+
+        for (side, id, price, size) in orders {
+            // A repeated order id replaces the earlier order rather than being counted twice
+            self.remove_order(id);
+
+            let side_store = match side {
+                MarketSide::Bid => &mut self.bids,
+                MarketSide::Offer => &mut self.offers,
+            };
+
+            Self::add_price(side_store, id, price, size);
+            self.prices.insert(id, MarketLiquidityMap { side, price });
+        }
+
+        if !self.level_callbacks.borrow().is_empty() {
+            for side in [MarketSide::Bid, MarketSide::Offer] {
+                let new_levels: Vec<_> = self
+                    .get_levels(side)
+                    .filter(|(price, _)| {
+                        !levels.iter().any(|(level_side, level_price, _)| {
+                            *level_side == side && level_price == price
+                        })
+                    })
+                    .map(|(price, _)| (side, price, None))
+                    .collect();
+
+                levels.extend(new_levels);
+            }
+        }
+
+        self.publish_level_updates(levels);
+        self.refresh_top_of_book();
+
+        Ok(())
+    }
+
+    /// Check that the internal state of the book is consistent: the size of each level is the sum of its orders, the order
+    /// index and the levels agree, no level is empty and the book is not crossed.  The book maintains these itself, so
+    /// any inconsistency found points to a bug.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L3MarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 1, 10, 50).unwrap();
+    /// market_data.update(UpdateAction::Update, MarketSide::Bid, 1, 10, 20).unwrap();
+    ///
+    /// assert!(market_data.validate().is_valid());
+    /// ```
+    pub fn validate(&self) -> L3ValidationReport<I, P, A> {
+        let mut inconsistencies = Vec::new();
+
+        for side in [MarketSide::Bid, MarketSide::Offer] {
+            for (&price, level) in self.side_store(side) {
+                if level.prices.is_empty() {
+                    inconsistencies.push(L3Inconsistency::EmptyLevel { side, price });
+                    continue;
+                }
+
+                let order_size = level
+                    .prices
+                    .values()
+                    .fold(A::default(), |total, liquidity| total + liquidity.size);
+
+                if order_size != level.size {
+                    inconsistencies.push(L3Inconsistency::LevelSize {
+                        side,
+                        price,
+                        level_size: level.size,
+                        order_size,
+                    });
+                }
+
+                for &id in level.prices.keys() {
+                    let indexed = self.prices.get(&id).is_some_and(|liquidity_map| {
+                        liquidity_map.side == side && liquidity_map.price == price
+                    });
+
+                    if !indexed {
+                        inconsistencies.push(L3Inconsistency::UnindexedOrder { id, side, price });
+                    }
+                }
+            }
+        }
+
+        for (&id, liquidity_map) in &self.prices {
+            let held = self
+                .side_store(liquidity_map.side)
+                .get(&liquidity_map.price)
+                .is_some_and(|level| level.prices.contains_key(&id));
+
+            if !held {
+                inconsistencies.push(L3Inconsistency::MissingOrder {
+                    id,
+                    side: liquidity_map.side,
+                    price: liquidity_map.price,
+                });
+            }
+        }
+
+        if let (Some(&bid), Some(&offer)) =
+            (self.bids.keys().next_back(), self.offers.keys().next())
+        {
+            if bid > offer {
+                inconsistencies.push(L3Inconsistency::Crossed { bid, offer });
+            }
+        }
+
+        L3ValidationReport { inconsistencies }
+    }
+
+    fn validate_update(
+        &self,
+        action: UpdateAction,
+        side: MarketSide,
+        id: I,
+        price: P,
+        size: A,
+    ) -> Result<(P, A), MarketDataError> {
+        match self.reference.get_spec() {
+            Some(spec) if !matches!(action, UpdateAction::Remove | UpdateAction::ClearSide) => {
+                // An update keeps the side of the order already in the book
+                let order_side = match action {
+                    UpdateAction::Update => self
+                        .prices
+                        .get(&id)
+                        .map_or(&side, |liquidity_map| &liquidity_map.side),
+                    _ => &side,
+                };
+
+                Ok((
+                    spec.validate_price(order_side, price)?,
+                    spec.validate_size(size)?,
+                ))
+            }
+            _ => Ok((price, size)),
+        }
+    }
+
+    // Applies an update along with the cross policy, adding the levels it changes to those to publish.  Only the size of
+    // a level before the first change to it is kept.
+    fn apply_tracked(
+        &mut self,
+        levels: &mut Vec<(MarketSide, P, Option<A>)>,
+        action: UpdateAction,
+        side: MarketSide,
+        id: I,
+        price: P,
+        size: A,
+    ) -> Result<(), MarketDataError> {
+        let stale = self.resolve_cross(action, side, id, price)?;
+        let updates = stale
+            .into_iter()
+            .map(|(stale_side, stale_id, stale_price)| {
+                (
+                    UpdateAction::Remove,
+                    stale_side,
+                    stale_id,
+                    stale_price,
+                    A::default(),
+                )
+            })
+            .chain(std::iter::once((action, side, id, price, size)))
+            .collect::<Vec<_>>();
+
+        for (action, side, id, price, size) in updates {
+            for level in self.affected_levels(action, side, id, price) {
+                if !levels.iter().any(|(level_side, level_price, _)| {
+                    *level_side == level.0 && *level_price == level.1
+                }) {
+                    levels.push(level);
+                }
+            }
+
+            self.apply_update(action, side, id, price, size)?;
+        }
+
+        Ok(())
+    }
+
+    // Applies the cross policy to an update which sets an order, returning the (side, order id, price) of the opposite orders it
+    // crosses if they are to be removed as stale
+    fn resolve_cross(
+        &self,
+        action: UpdateAction,
+        side: MarketSide,
+        id: I,
+        price: P,
+    ) -> Result<Vec<(MarketSide, I, P)>, MarketDataError> {
+        // An update keeps the side of the order already in the book
+        let order_side = match action {
+            UpdateAction::Add | UpdateAction::Replace => side,
+            UpdateAction::Update => match self.prices.get(&id) {
+                Some(liquidity_map) => liquidity_map.side,
+                None => return Ok(Vec::new()),
+            },
+            // A snapshot clears the opposite side first
+            UpdateAction::Remove | UpdateAction::ClearSide | UpdateAction::Snapshot => {
+                return Ok(Vec::new())
+            }
+        };
+
+        let crossed: Vec<_> = match order_side {
+            MarketSide::Bid => self
+                .offers
+                .range(..price)
+                .flat_map(|(&offer, level)| {
+                    level
+                        .prices
+                        .keys()
+                        .map(move |&order_id| (MarketSide::Offer, order_id, offer))
+                })
+                .filter(|&(_, order_id, _)| order_id != id)
+                .collect(),
+            MarketSide::Offer => self
+                .bids
+                .range((Bound::Excluded(price), Bound::Unbounded))
+                .flat_map(|(&bid, level)| {
+                    level
+                        .prices
+                        .keys()
+                        .map(move |&order_id| (MarketSide::Bid, order_id, bid))
+                })
+                .filter(|&(_, order_id, _)| order_id != id)
+                .collect(),
+        };
+
+        match self.cross_policy {
+            _ if crossed.is_empty() => Ok(crossed),
+            CrossPolicy::Reject => Err(MarketDataError::CrossedBook),
+            CrossPolicy::RemoveStale => Ok(crossed),
+            CrossPolicy::KeepAndFlag => Ok(Vec::new()),
+        }
+    }
+
+    fn apply_update(
+        &mut self,
+        action: UpdateAction,
+        side: MarketSide,
+        id: I,
+        price: P,
+        size: A,
+    ) -> Result<(), MarketDataError> {
         match action {
-            UpdateAction::Add => {
+            UpdateAction::Add | UpdateAction::Replace | UpdateAction::Snapshot => {
+                if action == UpdateAction::Snapshot {
+                    self.bids.clear();
+                    self.offers.clear();
+                    self.prices.clear();
+                } else {
+                    // An add of an order already in the book replaces it, so the old order is not left behind in its
+                    // level
+                    self.remove_order(id);
+                }
+
                 let side_store = match side {
                     MarketSide::Bid => &mut self.bids,
                     MarketSide::Offer => &mut self.offers,
@@ -89,16 +687,19 @@ where
                     if let Some(level) = side_store.get_mut(&liquidity_map.price) {
                         if liquidity_map.price == price {
                             if let Some(liquidity) = level.prices.get_mut(&id) {
-                                level.size += size - liquidity.size;
+                                // Take the old size off first so the level never goes below zero, which would
+                                // underflow an unsigned amount
+                                level.size -= liquidity.size;
+                                level.size += size;
                                 liquidity.size = size;
                             } else {
-                                return Err(());
+                                return Err(MarketDataError::UnknownOrder);
                             }
                         } else {
                             replace_price = true;
                         }
                     } else {
-                        return Err(());
+                        return Err(MarketDataError::UnknownOrder);
                     }
 
                     if replace_price {
@@ -109,23 +710,134 @@ where
 
                     Ok(())
                 } else {
-                    Err(())
+                    Err(MarketDataError::UnknownOrder)
                 }
             }
             UpdateAction::Remove => {
-                if let Some(liquidity_map) = self.prices.remove(&id) {
-                    let side_store = match liquidity_map.side {
-                        MarketSide::Bid => &mut self.bids,
-                        MarketSide::Offer => &mut self.offers,
-                    };
-
-                    Self::remove_price(side_store, id, liquidity_map.price);
-
+                if self.remove_order(id) {
                     Ok(())
                 } else {
-                    Err(())
+                    Err(MarketDataError::UnknownOrder)
                 }
             }
+            UpdateAction::ClearSide => {
+                match side {
+                    MarketSide::Bid => self.bids.clear(),
+                    MarketSide::Offer => self.offers.clear(),
+                }
+                self.prices
+                    .retain(|_, liquidity_map| liquidity_map.side != side);
+
+                Ok(())
+            }
+        }
+    }
+
+    // Removes an order from the book, returning false if it was not there
+    fn remove_order(&mut self, id: I) -> bool {
+        match self.prices.remove(&id) {
+            Some(liquidity_map) => {
+                let side_store = match liquidity_map.side {
+                    MarketSide::Bid => &mut self.bids,
+                    MarketSide::Offer => &mut self.offers,
+                };
+
+                Self::remove_price(side_store, id, liquidity_map.price);
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn side_store(&self, side: MarketSide) -> &BTreeMap<P, MarketLevel<I, A>> {
+        match side {
+            MarketSide::Bid => &self.bids,
+            MarketSide::Offer => &self.offers,
+        }
+    }
+
+    // The price levels an update can change along with their size beforehand, only needed if there are subscribers
+    fn affected_levels(
+        &self,
+        action: UpdateAction,
+        side: MarketSide,
+        id: I,
+        price: P,
+    ) -> Vec<(MarketSide, P, Option<A>)> {
+        if self.level_callbacks.borrow().is_empty() {
+            return Vec::new();
+        }
+
+        let mut levels = Vec::new();
+
+        let existing = self
+            .prices
+            .get(&id)
+            .map(|liquidity_map| (liquidity_map.side, liquidity_map.price));
+
+        if let Some(existing) = existing {
+            if matches!(
+                action,
+                UpdateAction::Add
+                    | UpdateAction::Update
+                    | UpdateAction::Remove
+                    | UpdateAction::Replace
+            ) {
+                levels.push(existing);
+            }
+        }
+
+        match action {
+            UpdateAction::Add | UpdateAction::Replace => levels.push((side, price)),
+            UpdateAction::ClearSide => {
+                levels.extend(self.side_store(side).keys().map(|&price| (side, price)))
+            }
+            UpdateAction::Snapshot => {
+                for book_side in [MarketSide::Bid, MarketSide::Offer] {
+                    levels.extend(
+                        self.side_store(book_side)
+                            .keys()
+                            .map(|&price| (book_side, price)),
+                    );
+                }
+                levels.push((side, price));
+            }
+            UpdateAction::Update => {
+                if let Some((existing_side, existing_price)) = existing {
+                    if existing_price != price {
+                        levels.push((existing_side, price));
+                    }
+                }
+            }
+            UpdateAction::Remove => {}
+        }
+
+        levels
+            .into_iter()
+            .map(|(side, price)| {
+                let size = self.side_store(side).get(&price).map(|level| level.size);
+                (side, price, size)
+            })
+            .collect()
+    }
+
+    fn publish_level_updates(&self, levels: Vec<(MarketSide, P, Option<A>)>) {
+        for (side, price, before) in levels {
+            let after = self.side_store(side).get(&price).map(|level| level.size);
+
+            let update = match (before, after) {
+                (None, Some(size)) => L2Update::new(UpdateAction::Add, side, price, size),
+                (Some(before), Some(size)) if before != size => {
+                    L2Update::new(UpdateAction::Update, side, price, size)
+                }
+                (Some(size), None) => L2Update::new(UpdateAction::Remove, side, price, size),
+                _ => continue,
+            };
+
+            for callback in self.level_callbacks.borrow().iter() {
+                callback.level_updated(&update);
+            }
         }
     }
 
@@ -155,17 +867,865 @@ where
         }
     }
 
+    /// Set the instrument specification that prices and sizes are validated against on update, in place of the
+    /// specification of any attached instrument.  A value of None means the instrument's specification is used.
+    ///
+    /// # Parameters
+    ///
+    /// * `spec` - The instrument specification
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{
+    ///     InstrumentSpec, L3MarketData, MarketDataError, MarketSide, SpecPolicy, UpdateAction,
+    /// };
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.set_instrument_spec(Some(InstrumentSpec::new(SpecPolicy::Reject).with_min_size(100)));
+    ///
+    /// assert_eq!(
+    ///     market_data.update(UpdateAction::Add, MarketSide::Bid, 1, 10, 50),
+    ///     Err(MarketDataError::BelowMinimumSize)
+    /// );
+    /// ```
+    pub fn set_instrument_spec(&mut self, spec: Option<InstrumentSpec<P, A>>) {
+        self.reference.set_book_spec(spec);
+    }
+
+    /// Get the instrument specification set on the book, which prices and sizes are validated against in place
+    /// of that of any attached instrument
+    pub fn get_instrument_spec(&self) -> &Option<InstrumentSpec<P, A>> {
+        self.reference.get_book_spec()
+    }
+
+    /// Attach the reference data of the instrument the book is pricing.  Updates are validated against the specification
+    /// of the instrument unless one has been set on the book, which takes precedence.
+    ///
+    /// # Parameters
+    ///
+    /// * `instrument` - The instrument, a value of None detaches the instrument
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use pricing::market_data::{
+    ///     AssetClass, Instrument, InstrumentSpec, L3MarketData, MarketDataError, MarketSide, SpecPolicy, TickSize, UpdateAction,
+    /// };
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.set_instrument(Some(Rc::new(
+    ///     Instrument::new("VOD.L", AssetClass::Equity, "GBP")
+    ///         .with_spec(InstrumentSpec::new(SpecPolicy::Reject).with_tick_size(TickSize::Fixed(5))),
+    /// )));
+    ///
+    /// assert_eq!(market_data.get_instrument().unwrap().get_symbol(), "VOD.L");
+    /// assert_eq!(market_data.update(UpdateAction::Add, MarketSide::Bid, 1, 11, 100), Err(MarketDataError::OffTickPrice));
+    /// ```
+    pub fn set_instrument(&mut self, instrument: Option<Rc<Instrument<P, A>>>) {
+        self.reference.set_instrument(instrument);
+    }
+
+    /// Get the reference data of the instrument the book is pricing
+    pub fn get_instrument(&self) -> Option<&Rc<Instrument<P, A>>> {
+        self.reference.get_instrument()
+    }
+
+    /// Get the top of book, which is maintained as the book is updated with the best bid and offer prices, and the total
+    /// size at those prices as the max sizes.  Subscribers to the top of book are only called when it actually changes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L3MarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 1, 10, 50).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 2, 10, 30).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 3, 9, 100).unwrap();
+    ///
+    /// assert_eq!(*market_data.get_top_of_book().get_bid(), Some(10));
+    /// assert_eq!(*market_data.get_top_of_book().get_max_bid(), Some(80));
+    /// assert_eq!(*market_data.get_top_of_book().get_offer(), None);
+    /// ```
+    pub fn get_top_of_book(&self) -> &L1MarketDataWithMax<P, A> {
+        &self.top_of_book
+    }
+
+    /// Set what happens to an add or update which would leave the order through the best opposite price, which defaults
+    /// to keeping it and flagging the book as crossed.  Subscribers to the top of book are told when the book becomes or
+    /// stops being crossed or locked.
+    ///
+    /// # Parameters
+    ///
+    /// * `policy` - The policy for crossing updates
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{CrossPolicy, CrossStatus, L3MarketData, MarketDataError, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.set_cross_policy(CrossPolicy::Reject);
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 1, 11, 50).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 2, 11, 50).unwrap();
+    ///
+    /// assert_eq!(market_data.get_cross_status(), CrossStatus::Locked);
+    /// assert_eq!(
+    ///     market_data.update(UpdateAction::Add, MarketSide::Bid, 3, 12, 50),
+    ///     Err(MarketDataError::CrossedBook)
+    /// );
+    /// ```
+    pub fn set_cross_policy(&mut self, policy: CrossPolicy) {
+        self.cross_policy = policy;
+    }
+
+    /// Get what happens to an add or update which would cross the book
+    pub fn get_cross_policy(&self) -> CrossPolicy {
+        self.cross_policy
+    }
+
+    /// Get whether the best bid and offer currently overlap
+    pub fn get_cross_status(&self) -> CrossStatus {
+        self.top_of_book.get_cross_status()
+    }
+
+    /// Move the market to a status, for example from a status message from the venue.  The status is held by the top of
+    /// book, which notifies its subscribers if it changes.
+    ///
+    /// # Parameters
+    ///
+    /// * `status` - The status of the market
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, L3MarketData, MarketSide, MarketStatus, UpdateAction};
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 1, 10, 50).unwrap();
+    /// market_data.set_status(MarketStatus::Halted).unwrap();
+    ///
+    /// assert_eq!(market_data.get_price(50), BidOffer::new());
+    /// assert_eq!(market_data.get_top_of_book().get_status(), MarketStatus::Halted);
+    /// ```
+    pub fn set_status(&mut self, status: MarketStatus) -> Result<(), MarketDataError> {
+        self.top_of_book.set_status(status)
+    }
+
+    /// Get the status of the market, which defaults to continuous trading
+    pub fn get_status(&self) -> MarketStatus {
+        self.top_of_book.get_status()
+    }
+
+    /// Set the schedule which `check_schedule` moves the market status through.  A value of None means the status only
+    /// changes with `set_status`.
+    pub fn set_schedule(&mut self, schedule: Option<TradingSchedule>) {
+        self.top_of_book.set_schedule(schedule);
+    }
+
+    /// Get the schedule the market status moves through
+    pub fn get_schedule(&self) -> Option<&TradingSchedule> {
+        self.top_of_book.get_schedule()
+    }
+
+    /// Moves the market to the status the schedule gives at the time passed in, notifying the subscribers to the top of
+    /// book if it changes.  The first check after the schedule is set moves straight to the status it gives.  After that
+    /// a status the market cannot move to is ignored.  A halted market only follows the schedule when it closes.
+    ///
+    /// # Parameters
+    ///
+    /// * `now` - The time to check the schedule against
+    ///
+    /// # Returns
+    ///
+    /// The new status if it has changed
+    pub fn check_schedule(&mut self, now: Duration) -> Option<MarketStatus> {
+        self.top_of_book.check_schedule(now)
+    }
+
+    fn refresh_top_of_book(&mut self) {
+        let bid = self.bids.iter().next_back();
+        let offer = self.offers.iter().next();
+
+        // The top of book has no instrument specification, so the update cannot be rejected
+        let _ = self.top_of_book.update_with_max(
+            bid.map(|(&price, _)| price),
+            offer.map(|(&price, _)| price),
+            bid.map(|(_, level)| level.size),
+            offer.map(|(_, level)| level.size),
+        );
+
+        if let (Some((&bid, _)), Some((&offer, _))) = (bid, offer) {
+            self.last_mid = Some(Self::mid(bid, offer));
+        }
+    }
+
+    /// Get the price levels on a side of the book, best price first, as (price, total size at the price)
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the market
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L3MarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 1, 10, 50).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 2, 9, 30).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 3, 10, 20).unwrap();
+    ///
+    /// assert_eq!(market_data.get_levels(MarketSide::Bid).collect::<Vec<_>>(), vec![(10, 70), (9, 30)]);
+    /// ```
+    pub fn get_levels(&self, side: MarketSide) -> Box<dyn Iterator<Item = (P, A)> + '_> {
+        let levels = self
+            .side_store(side)
+            .iter()
+            .map(|(&price, level)| (price, level.size));
+
+        match side {
+            MarketSide::Bid => Box::new(levels.rev()),
+            MarketSide::Offer => Box::new(levels),
+        }
+    }
+
+    /// Get the minimal list of order changes which turns this book into another, for example to publish incremental
+    /// updates from polled snapshots.  An order which has moved to the other side of the book is removed and added again.
+    /// The removals come first, then the orders which move away from the other side of the book and finally the orders
+    /// which move towards it or are added, so that applying the changes in order never crosses the book on the way.
+    /// For changes to the price levels rather than the orders, diff the books from `to_l2` instead.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The book to turn this book into
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L3MarketData, L3Update, MarketSide, UpdateAction};
+    ///
+    /// let mut before = L3MarketData::<u64, i32, i32>::new();
+    /// before.update(UpdateAction::Add, MarketSide::Bid, 1, 10, 50).unwrap();
+    /// before.update(UpdateAction::Add, MarketSide::Bid, 2, 10, 30).unwrap();
+    ///
+    /// let mut after = L3MarketData::<u64, i32, i32>::new();
+    /// after.update(UpdateAction::Add, MarketSide::Bid, 1, 9, 50).unwrap();
+    ///
+    /// assert_eq!(
+    ///     before.diff(&after),
+    ///     vec![
+    ///         L3Update::new(UpdateAction::Remove, MarketSide::Bid, 2, 10, 30),
+    ///         L3Update::new(UpdateAction::Update, MarketSide::Bid, 1, 9, 50),
+    ///     ]
+    /// );
+    /// ```
+    pub fn diff(&self, other: &Self) -> Vec<L3Update<I, P, A>> {
+        let from = self.get_orders();
+        let to = other.get_orders();
+        let mut removes = Vec::new();
+        let mut away = Vec::new();
+        let mut towards = Vec::new();
+
+        diff_side(&from, &to, |action, id, (side, price, size)| {
+            let update = L3Update::new(action, side, id, price, size);
+
+            match (action, from.get(&id)) {
+                (UpdateAction::Update, Some(&(from_side, from_price, from_size)))
+                    if from_side != side =>
+                {
+                    removes.push(L3Update::new(
+                        UpdateAction::Remove,
+                        from_side,
+                        id,
+                        from_price,
+                        from_size,
+                    ));
+                    towards.push(L3Update::new(UpdateAction::Add, side, id, price, size));
+                }
+                (UpdateAction::Remove, _) => removes.push(update),
+                (UpdateAction::Update, Some(&(_, from_price, _)))
+                    if !side.is_better(price, from_price) =>
+                {
+                    away.push(update)
+                }
+                _ => towards.push(update),
+            }
+        });
+
+        removes.into_iter().chain(away).chain(towards).collect()
+    }
+
+    // Every order in the book by id as (side, price, size)
+    fn get_orders(&self) -> BTreeMap<I, (MarketSide, P, A)> {
+        self.prices
+            .iter()
+            .filter_map(|(&id, liquidity_map)| {
+                self.side_store(liquidity_map.side)
+                    .get(&liquidity_map.price)
+                    .and_then(|level| level.prices.get(&id))
+                    .map(|liquidity| {
+                        (
+                            id,
+                            (liquidity_map.side, liquidity_map.price, liquidity.size),
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    /// Aggregates the orders into a market by price L2 book
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, L3MarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 1, 10, 50).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 2, 10, 30).unwrap();
+    ///
+    /// assert_eq!(market_data.to_l2().get_price(80), BidOffer::new_with_price(Some(10), None));
+    /// ```
+    pub fn to_l2(&self) -> L2SweepableMarketData<P, A>
+    where
+        P: Default,
+        A: Div<P, Output = A>,
+    {
+        let mut l2 = L2SweepableMarketData::new();
+
+        for side in [MarketSide::Bid, MarketSide::Offer] {
+            for (price, size) in self.get_levels(side) {
+                // The L2 book has no instrument specification, so the update cannot be rejected
+                let _ = l2.update(UpdateAction::Add, side, price, size);
+            }
+        }
+
+        l2
+    }
+
+    /// Subscribe to the changes to the price levels of the book, which are published as the L2 updates that would be
+    /// needed to keep a market by price book in line with this one.  NOTE: this will occur in the same thread as the
+    /// caller, so make sure that this does not cause a recursion issue.
+    ///
+    /// # Parameters
+    ///
+    /// * `callback` - The object which implements the L2MarketCallback trait to callback on
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    /// use pricing::market_data::{L2MarketCallback, L2Update, L3MarketData, MarketSide, UpdateAction};
+    ///
+    /// struct TestCallback {
+    ///     updates: RefCell<Vec<L2Update<i32, i32>>>,
+    /// }
+    ///
+    /// impl L2MarketCallback<i32, i32> for TestCallback {
+    ///     fn level_updated(&self, update: &L2Update<i32, i32>) {
+    ///         self.updates.borrow_mut().push(*update);
+    ///     }
+    /// }
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    /// let callback = Rc::new(TestCallback { updates: RefCell::new(Vec::new()) });
+    /// market_data.subscribe_levels(callback.clone());
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 1, 10, 50).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 2, 10, 30).unwrap();
+    ///
+    /// assert_eq!(
+    ///     *callback.updates.borrow(),
+    ///     vec![
+    ///         L2Update::new(UpdateAction::Add, MarketSide::Bid, 10, 50),
+    ///         L2Update::new(UpdateAction::Update, MarketSide::Bid, 10, 80),
+    ///     ]
+    /// );
+    /// ```
+    pub fn subscribe_levels(&self, callback: Rc<dyn L2MarketCallback<P, A>>) {
+        self.level_callbacks.borrow_mut().push(callback);
+    }
+
     pub fn clear(&mut self) {
+        let levels: Vec<_> = if self.level_callbacks.borrow().is_empty() {
+            Vec::new()
+        } else {
+            [MarketSide::Bid, MarketSide::Offer]
+                .into_iter()
+                .flat_map(|side| {
+                    self.get_levels(side)
+                        .map(move |(price, size)| (side, price, Some(size)))
+                })
+                .collect()
+        };
+
         self.bids.clear();
         self.offers.clear();
         self.prices.clear();
+
+        self.publish_level_updates(levels);
+        self.refresh_top_of_book();
+    }
+
+    /// Returns the price for the size by sweeping the orders on each side, or no price if the market status does not have
+    /// firm prices
+    ///
+    /// # Parameters
+    ///
+    /// * `size` - The size to price
+    pub fn get_price(&self, size: A) -> BidOffer<P> {
+        if self.get_status().has_firm_prices() {
+            self.calc_price(size)
+        } else {
+            BidOffer::new()
+        }
+    }
+
+    fn calc_price(&self, size: A) -> BidOffer<P> {
+        BidOffer::new_with_price(
+            self.calc_vwap(size, self.bids.iter().rev()),
+            self.calc_vwap(size, self.offers.iter()),
+        )
+    }
+
+    /// Set what the fair value measures give when only one side of the book has a price, which defaults to the price of
+    /// that side
+    ///
+    /// # Parameters
+    ///
+    /// * `policy` - The one sided policy
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L3MarketData, MarketSide, OneSidedPolicy, UpdateAction};
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.set_one_sided_policy(OneSidedPolicy::LastMid);
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 1, 10, 50).unwrap();
+    /// assert_eq!(market_data.get_microprice(), None);
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 2, 12, 50).unwrap();
+    /// market_data.update(UpdateAction::Remove, MarketSide::Offer, 2, 12, 0).unwrap();
+    /// assert_eq!(market_data.get_microprice(), Some(11));
+    /// ```
+    pub fn set_one_sided_policy(&mut self, policy: OneSidedPolicy) {
+        self.one_sided_policy = policy;
+    }
+
+    /// Get what the fair value measures give when only one side of the book has a price
+    pub fn get_one_sided_policy(&self) -> OneSidedPolicy {
+        self.one_sided_policy
+    }
+
+    /// Get the average of the best bid and offer weighted by the size at each, which leans towards the side with more
+    /// size
+    ///
+    /// This is an estimate of value rather than a price to trade on, so it is given whatever the market status.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L3MarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 1, 100, 30).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 2, 110, 10).unwrap();
+    ///
+    /// assert_eq!(market_data.get_weighted_mid(), Some(103));
+    /// ```
+    pub fn get_weighted_mid(&self) -> Option<P>
+    where
+        P: FloatConvert,
+        A: FloatConvert,
+    {
+        self.one_sided_policy.resolve(
+            self.get_touch(MarketSide::Bid),
+            self.get_touch(MarketSide::Offer),
+            self.last_mid,
+            fair_value::weighted_mid,
+        )
+    }
+
+    /// Get the microprice, the average of the best bid and offer weighted by the size on the opposite side, which leans
+    /// towards the side with less size as that is the side the price is more likely to move to
+    ///
+    /// This is an estimate of value rather than a price to trade on, so it is given whatever the market status.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L3MarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 1, 100, 30).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 2, 110, 10).unwrap();
+    ///
+    /// assert_eq!(market_data.get_microprice(), Some(108));
+    /// ```
+    pub fn get_microprice(&self) -> Option<P>
+    where
+        P: FloatConvert,
+        A: FloatConvert,
+    {
+        self.one_sided_policy.resolve(
+            self.get_touch(MarketSide::Bid),
+            self.get_touch(MarketSide::Offer),
+            self.last_mid,
+            fair_value::microprice,
+        )
+    }
+
+    /// Get the mid of the prices to trade a size on each side
+    ///
+    /// Unlike `get_price` the mid is given whatever the market status, as it is an estimate of value rather than a price
+    /// to trade on.
+    ///
+    /// # Parameters
+    ///
+    /// * `size` - The size to price
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L3MarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 1, 100, 10).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 3, 90, 10).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 2, 110, 20).unwrap();
+    ///
+    /// assert_eq!(market_data.get_mid_at_size(20), Some(102));
+    /// ```
+    pub fn get_mid_at_size(&self, size: A) -> Option<P> {
+        let price = self.calc_price(size);
+
+        self.one_sided_policy.resolve(
+            price.get_bid().map(|bid| (bid, ())),
+            price.get_offer().map(|offer| (offer, ())),
+            self.last_mid,
+            |(bid, _), (offer, _)| Self::mid(bid, offer),
+        )
+    }
+
+    // The (price, size) of the best level on a side
+    fn get_touch(&self, side: MarketSide) -> Option<(P, A)> {
+        match side {
+            MarketSide::Bid => self
+                .bids
+                .iter()
+                .next_back()
+                .map(|(&price, level)| (price, level.size)),
+            MarketSide::Offer => self
+                .offers
+                .iter()
+                .next()
+                .map(|(&price, level)| (price, level.size)),
+        }
+    }
+
+    fn mid(bid: P, offer: P) -> P {
+        let two: P = 2.into();
+        (bid + offer) / two
+    }
+
+    /// Get the imbalance between the size on the best levels of each side, from -1 when there is only offer size to 1
+    /// when there is only bid size.  None if there is no size on either side.
+    ///
+    /// # Parameters
+    ///
+    /// * `levels` - The number of levels from the best on each side to include
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L3MarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 1, 100, 30).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 2, 101, 10).unwrap();
+    ///
+    /// assert_eq!(market_data.get_imbalance(1), Some(0.5));
+    /// ```
+    pub fn get_imbalance(&self, levels: usize) -> Option<f64>
+    where
+        A: FloatConvert,
+    {
+        metrics::imbalance(
+            self.get_levels(MarketSide::Bid),
+            self.get_levels(MarketSide::Offer),
+            levels,
+        )
+    }
+
+    /// Get the total size on a side at prices within a number of basis points of the mid.  None unless both sides have a
+    /// price.
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the market
+    /// * `bps` - The distance from the mid in basis points
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L3MarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 1, 9990, 30).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 3, 9900, 50).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 2, 10010, 10).unwrap();
+    ///
+    /// assert_eq!(market_data.get_depth_within_bps(MarketSide::Bid, 10.0), Some(30));
+    /// assert_eq!(market_data.get_depth_within_bps(MarketSide::Bid, 100.0), Some(80));
+    /// ```
+    pub fn get_depth_within_bps(&self, side: MarketSide, bps: f64) -> Option<A>
+    where
+        P: FloatConvert,
+    {
+        let mid = self.get_touch_mid()?;
+
+        Some(metrics::depth_within(self.get_levels(side), |price| {
+            metrics::bps(price, mid, mid).is_some_and(|distance| distance.abs() <= bps)
+        }))
+    }
+
+    /// Get the total size on a side at prices within a number of ticks of the mid, using the tick size of the instrument
+    /// specification.  None unless both sides have a price and there is a tick size.
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the market
+    /// * `ticks` - The distance from the mid in ticks
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L3MarketData, InstrumentSpec, MarketSide, SpecPolicy, TickSize, UpdateAction};
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.set_instrument_spec(Some(InstrumentSpec::new(SpecPolicy::Reject).with_tick_size(TickSize::Fixed(5))));
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 2, 105, 30).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 3, 120, 50).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 1, 95, 10).unwrap();
+    ///
+    /// assert_eq!(market_data.get_depth_within_ticks(MarketSide::Offer, 1.0), Some(30));
+    /// assert_eq!(market_data.get_depth_within_ticks(MarketSide::Offer, 4.0), Some(80));
+    /// ```
+    pub fn get_depth_within_ticks(&self, side: MarketSide, ticks: f64) -> Option<A>
+    where
+        P: FloatConvert,
+    {
+        let mid = self.get_touch_mid()?;
+        let tick_size = self.reference.get_spec()?.get_tick_size().as_ref()?;
+
+        Some(metrics::depth_within(self.get_levels(side), |price| {
+            let distance = if price < mid {
+                tick_size.ticks_between(price, mid)
+            } else {
+                tick_size.ticks_between(mid, price)
+            };
+
+            distance.is_some_and(|distance| distance <= ticks)
+        }))
+    }
+
+    /// Get the spread between the best bid and offer in ticks, using the tick size of the instrument specification.  None
+    /// unless both sides have a price and there is a tick size, and negative if the book is crossed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L3MarketData, InstrumentSpec, MarketSide, SpecPolicy, TickSize, UpdateAction};
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.set_instrument_spec(Some(InstrumentSpec::new(SpecPolicy::Reject).with_tick_size(TickSize::Fixed(5))));
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 1, 95, 10).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 2, 110, 10).unwrap();
+    ///
+    /// assert_eq!(market_data.get_spread_ticks(), Some(3.0));
+    /// ```
+    pub fn get_spread_ticks(&self) -> Option<f64>
+    where
+        P: FloatConvert,
+    {
+        let (bid, _) = self.get_touch(MarketSide::Bid)?;
+        let (offer, _) = self.get_touch(MarketSide::Offer)?;
+        let tick_size = self.reference.get_spec()?.get_tick_size().as_ref()?;
+
+        if bid <= offer {
+            tick_size.ticks_between(bid, offer)
+        } else {
+            tick_size.ticks_between(offer, bid).map(|ticks| -ticks)
+        }
+    }
+
+    /// Get the spread between the best bid and offer in basis points of the mid.  None unless both sides have a price, and
+    /// negative if the book is crossed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L3MarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 1, 9990, 10).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 2, 10010, 10).unwrap();
+    ///
+    /// assert_eq!(market_data.get_spread_bps(), Some(20.0));
+    /// ```
+    pub fn get_spread_bps(&self) -> Option<f64>
+    where
+        P: FloatConvert,
+    {
+        let (bid, _) = self.get_touch(MarketSide::Bid)?;
+        let (offer, _) = self.get_touch(MarketSide::Offer)?;
+
+        metrics::bps(bid, offer, Self::mid(bid, offer))
+    }
+
+    /// Get the number of price levels on a side of the book
+    pub fn get_level_count(&self, side: MarketSide) -> usize {
+        self.side_store(side).len()
+    }
+
+    /// Get the number of orders on a side of the book
+    pub fn get_order_count(&self, side: MarketSide) -> usize {
+        self.side_store(side)
+            .values()
+            .map(|level| level.prices.len())
+            .sum()
+    }
+
+    /// Get the average size of the orders at a price level, or None if there is no level at the price
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the market
+    /// * `price` - The price of the level
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L3MarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 1, 10, 50).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 2, 10, 30).unwrap();
+    ///
+    /// assert_eq!(market_data.get_order_count(MarketSide::Bid), 2);
+    /// assert_eq!(market_data.get_average_order_size(MarketSide::Bid, 10), Some(40));
+    /// ```
+    pub fn get_average_order_size(&self, side: MarketSide, price: P) -> Option<A> {
+        let level = self.side_store(side).get(&price)?;
+        // Count in the amount type itself, as not every amount can be converted from the length of the level
+        let count = level
+            .prices
+            .values()
+            .fold(A::default(), |count, _| count + A::from(1));
+
+        Some(level.size / count)
+    }
+
+    /// Estimate the impact of sweeping a size from a side of the book, which is None if the side cannot fill the size
+    ///
+    /// The book is used whatever the market status, so the impact of an order can be estimated before the market opens.
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the book to sweep, which is the offers to buy and the bids to sell
+    /// * `size` - The size to sweep
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L3MarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 1, 100, 50).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Bid, 3, 90, 50).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 2, 102, 50).unwrap();
+    ///
+    /// let impact = market_data.estimate_impact(MarketSide::Bid, 100).unwrap();
+    ///
+    /// assert_eq!(impact.get_price(), 95);
+    /// assert_eq!(impact.get_touch_slippage(), 5.0);
+    /// assert_eq!(impact.get_mid_slippage(), Some(6.0));
+    /// assert_eq!(market_data.estimate_impact(MarketSide::Offer, 100), None);
+    /// ```
+    pub fn estimate_impact(&self, side: MarketSide, size: A) -> Option<MarketImpact<P>> {
+        let price = match side {
+            MarketSide::Bid => self.calc_vwap(size, self.bids.iter().rev()),
+            MarketSide::Offer => self.calc_vwap(size, self.offers.iter()),
+        }?;
+        let (touch, _) = self.get_touch(side)?;
+
+        Some(MarketImpact::new(side, price, touch, self.get_touch_mid()))
     }
 
-    pub fn get_price(&self, size: A) -> BidOffer<P> {
-        BidOffer::new_with_price(
-            self.calc_vwap(size, self.bids.iter().rev()),
-            self.calc_vwap(size, self.offers.iter()),
-        )
+    /// Estimate the impact of sweeping each of a range of sizes from a side of the book, as (size, impact)
+    ///
+    /// # Parameters
+    ///
+    /// * `side` - The side of the book to sweep
+    /// * `sizes` - The sizes to sweep
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L3MarketData, MarketSide, UpdateAction};
+    ///
+    /// let mut market_data = L3MarketData::<u64, i32, i32>::new();
+    ///
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 2, 102, 50).unwrap();
+    /// market_data.update(UpdateAction::Add, MarketSide::Offer, 3, 106, 50).unwrap();
+    ///
+    /// let curve = market_data.get_impact_curve(MarketSide::Offer, &[50, 100, 150]);
+    /// let slippage: Vec<_> = curve
+    ///     .iter()
+    ///     .map(|(size, impact)| (*size, impact.map(|impact| impact.get_touch_slippage())))
+    ///     .collect();
+    ///
+    /// assert_eq!(slippage, vec![(50, Some(0.0)), (100, Some(2.0)), (150, None)]);
+    /// ```
+    pub fn get_impact_curve(
+        &self,
+        side: MarketSide,
+        sizes: &[A],
+    ) -> Vec<(A, Option<MarketImpact<P>>)> {
+        sizes
+            .iter()
+            .map(|&size| (size, self.estimate_impact(side, size)))
+            .collect()
+    }
+
+    fn get_touch_mid(&self) -> Option<P> {
+        let (bid, _) = self.get_touch(MarketSide::Bid)?;
+        let (offer, _) = self.get_touch(MarketSide::Offer)?;
+
+        Some(Self::mid(bid, offer))
     }
 
     fn calc_vwap<'a, T>(&self, size: A, iter: T) -> Option<P>
@@ -175,33 +1735,14 @@ where
         A: 'a,
         P: 'a,
     {
-        let mut current_size = A::default();
-        let mut current_total = A::default();
-
-        for (&next_price, next_size) in iter {
-            let mut incremental_size = next_size.size;
-
-            if next_size.size + current_size > size {
-                incremental_size = size - current_size;
-            }
-
-            current_total += next_price * incremental_size;
-            current_size += incremental_size;
-
-            // Should always be equal at this point but just in case of some weird rounding issues
-            if current_size >= size {
-                return Some(current_total / current_size);
-            }
-        }
-
-        None
+        vwap::sweep(size, iter.map(|(&price, level)| (price, level.size)))
     }
 }
 
 impl<I, P, A> Default for L3MarketData<I, P, A>
 where
     I: Ord + Copy,
-    P: Ord + Copy + Add<Output = P> + Div<Output = P> + From<i32> + Mul<A, Output = A>,
+    P: Ord + Copy + Add<Output = P> + Div<Output = P> + From<u8> + Increment,
     A: Default
         + PartialOrd
         + AddAssign
@@ -209,16 +1750,77 @@ where
         + Copy
         + Sub<Output = A>
         + Add<Output = A>
-        + Div<A, Output = P>,
+        + Div<Output = A>
+        + From<u8>
+        + Increment
+        + VwapAmount<P>,
 {
     fn default() -> Self {
         Self::new()
     }
 }
 
+impl<I, P, A> IncrementalBook for L3MarketData<I, P, A>
+where
+    I: Ord + Copy,
+    P: Ord + Copy + Add<Output = P> + Div<Output = P> + From<u8> + Increment,
+    A: Default
+        + PartialOrd
+        + AddAssign
+        + SubAssign
+        + Copy
+        + Sub<Output = A>
+        + Add<Output = A>
+        + Div<Output = A>
+        + From<u8>
+        + Increment
+        + VwapAmount<P>,
+{
+    type Update = L3Update<I, P, A>;
+    type SnapshotEntry = (MarketSide, I, P, A);
+
+    fn apply_incremental(&mut self, update: &Self::Update) -> Result<(), MarketDataError> {
+        self.update(
+            update.get_action(),
+            update.get_side(),
+            update.get_id(),
+            update.get_price(),
+            update.get_size(),
+        )
+    }
+
+    fn apply_snapshot(&mut self, entries: &[Self::SnapshotEntry]) -> Result<(), MarketDataError> {
+        L3MarketData::apply_snapshot(self, entries)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::market_data::test_callback::TestCallback;
+    use std::cell::RefCell;
+
+    struct LevelCallback {
+        updates: RefCell<Vec<L2Update<i32, i32>>>,
+    }
+
+    impl LevelCallback {
+        fn new() -> Self {
+            Self {
+                updates: RefCell::new(Vec::new()),
+            }
+        }
+
+        fn take(&self) -> Vec<L2Update<i32, i32>> {
+            self.updates.borrow_mut().drain(..).collect()
+        }
+    }
+
+    impl L2MarketCallback<i32, i32> for LevelCallback {
+        fn level_updated(&self, update: &L2Update<i32, i32>) {
+            self.updates.borrow_mut().push(*update);
+        }
+    }
 
     #[test]
     fn simple_add_price() {
@@ -347,6 +1949,165 @@ mod tests {
         assert_eq!(test.get_price(10), BidOffer::new_with_price(None, None));
     }
 
+    #[test]
+    fn instrument_spec() {
+        let mut test = L3MarketData::new();
+
+        test.set_instrument_spec(Some(
+            InstrumentSpec::new(crate::market_data::SpecPolicy::Snap)
+                .with_tick_size(crate::market_data::TickSize::Fixed(5))
+                .with_lot_size(10),
+        ));
+
+        assert_eq!(
+            test.update(UpdateAction::Add, MarketSide::Bid, 123, 12, 15),
+            Ok(())
+        );
+        assert_eq!(
+            test.update(UpdateAction::Add, MarketSide::Offer, 124, 12, 20),
+            Ok(())
+        );
+        assert_eq!(
+            test.get_price(10),
+            BidOffer::new_with_price(Some(10), Some(15))
+        );
+
+        // The side of an update comes from the order rather than the message
+        assert_eq!(
+            test.update(UpdateAction::Update, MarketSide::Bid, 124, 13, 20),
+            Ok(())
+        );
+        assert_eq!(test.get_price(20), BidOffer::new_with_price(None, Some(15)));
+
+        assert_eq!(
+            test.update(UpdateAction::Update, MarketSide::Bid, 125, 13, 20),
+            Err(MarketDataError::UnknownOrder)
+        );
+        assert_eq!(
+            test.update(UpdateAction::Remove, MarketSide::Bid, 125, 13, 20),
+            Err(MarketDataError::UnknownOrder)
+        );
+    }
+
+    #[test]
+    fn top_of_book() {
+        let mut test = L3MarketData::new();
+
+        let callback = Rc::new(TestCallback::new());
+        test.get_top_of_book().subscribe(callback.clone());
+
+        test.update(UpdateAction::Add, MarketSide::Offer, 1, 15, 10)
+            .unwrap();
+        assert!(callback.is_called());
+
+        callback.reset();
+        test.update(UpdateAction::Add, MarketSide::Offer, 2, 16, 10)
+            .unwrap();
+        assert!(!callback.is_called());
+
+        test.update(UpdateAction::Add, MarketSide::Offer, 3, 15, 5)
+            .unwrap();
+        assert!(callback.is_called());
+        assert_eq!(*test.get_top_of_book().get_max_offer(), Some(15));
+
+        callback.reset();
+        test.update(UpdateAction::Update, MarketSide::Offer, 1, 17, 10)
+            .unwrap();
+        assert!(callback.is_called());
+        assert_eq!(
+            test.get_top_of_book().get_price(5),
+            BidOffer::new_with_price(None, Some(15))
+        );
+        assert_eq!(*test.get_top_of_book().get_max_offer(), Some(5));
+
+        callback.reset();
+        test.update(UpdateAction::Remove, MarketSide::Offer, 3, 0, 0)
+            .unwrap();
+        assert!(callback.is_called());
+        assert_eq!(*test.get_top_of_book().get_offer(), Some(16));
+    }
+
+    #[test]
+    fn level_updates() {
+        let mut test = L3MarketData::new();
+        let mut mirror = L2SweepableMarketData::new();
+
+        let callback = Rc::new(LevelCallback::new());
+        test.subscribe_levels(callback.clone());
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 1, 12, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Bid, 2, 12, 5)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 3, 15, 10)
+            .unwrap();
+
+        assert_eq!(
+            callback.take(),
+            vec![
+                L2Update::new(UpdateAction::Add, MarketSide::Bid, 12, 10),
+                L2Update::new(UpdateAction::Update, MarketSide::Bid, 12, 15),
+                L2Update::new(UpdateAction::Add, MarketSide::Offer, 15, 10),
+            ]
+        );
+
+        test.update(UpdateAction::Update, MarketSide::Bid, 1, 11, 10)
+            .unwrap();
+        test.update(UpdateAction::Update, MarketSide::Bid, 2, 12, 5)
+            .unwrap();
+        test.update(UpdateAction::Remove, MarketSide::Bid, 2, 0, 0)
+            .unwrap();
+
+        assert_eq!(
+            callback.take(),
+            vec![
+                L2Update::new(UpdateAction::Update, MarketSide::Bid, 12, 5),
+                L2Update::new(UpdateAction::Add, MarketSide::Bid, 11, 10),
+                L2Update::new(UpdateAction::Remove, MarketSide::Bid, 12, 5),
+            ]
+        );
+
+        test.update(UpdateAction::Add, MarketSide::Offer, 4, 16, 10)
+            .unwrap();
+
+        for update in [
+            L2Update::new(UpdateAction::Add, MarketSide::Bid, 12, 10),
+            L2Update::new(UpdateAction::Update, MarketSide::Bid, 12, 15),
+            L2Update::new(UpdateAction::Add, MarketSide::Offer, 15, 10),
+            L2Update::new(UpdateAction::Update, MarketSide::Bid, 12, 5),
+            L2Update::new(UpdateAction::Add, MarketSide::Bid, 11, 10),
+            L2Update::new(UpdateAction::Remove, MarketSide::Bid, 12, 5),
+        ]
+        .into_iter()
+        .chain(callback.take())
+        {
+            mirror
+                .update(
+                    update.get_action(),
+                    update.get_side(),
+                    update.get_price(),
+                    update.get_size(),
+                )
+                .unwrap();
+        }
+
+        for size in [5, 10, 15, 20, 25] {
+            assert_eq!(mirror.get_price(size), test.get_price(size));
+            assert_eq!(test.to_l2().get_price(size), test.get_price(size));
+        }
+
+        test.clear();
+
+        assert_eq!(
+            callback.take(),
+            vec![
+                L2Update::new(UpdateAction::Remove, MarketSide::Bid, 11, 10),
+                L2Update::new(UpdateAction::Remove, MarketSide::Offer, 15, 10),
+                L2Update::new(UpdateAction::Remove, MarketSide::Offer, 16, 10),
+            ]
+        );
+    }
+
     #[test]
     fn multi_price_on_level() {
         let mut test = L3MarketData::new();
@@ -449,4 +2210,522 @@ mod tests {
             BidOffer::new_with_price(Some(11), Some(16))
         );
     }
+
+    #[test]
+    fn snapshot_publishes_level_changes() {
+        let mut test = L3MarketData::new();
+        let callback = Rc::new(LevelCallback::new());
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 1, 12, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Bid, 2, 11, 10)
+            .unwrap();
+        test.subscribe_levels(callback.clone());
+
+        test.apply_snapshot(&[
+            (MarketSide::Bid, 1, 12, 10),
+            (MarketSide::Bid, 3, 12, 5),
+            (MarketSide::Offer, 4, 15, 10),
+            (MarketSide::Offer, 4, 16, 10),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            callback.take(),
+            vec![
+                L2Update::new(UpdateAction::Update, MarketSide::Bid, 12, 15),
+                L2Update::new(UpdateAction::Remove, MarketSide::Bid, 11, 10),
+                L2Update::new(UpdateAction::Add, MarketSide::Offer, 16, 10),
+            ]
+        );
+        assert_eq!(
+            test.get_price(10),
+            BidOffer::new_with_price(Some(12), Some(16))
+        );
+        assert_eq!(*test.get_top_of_book().get_max_bid(), Some(15));
+    }
+
+    #[test]
+    fn rejected_snapshot_leaves_book() {
+        let mut test = L3MarketData::<u64, i32, i32>::new();
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 1, 12, 10)
+            .unwrap();
+        test.set_instrument_spec(Some(
+            InstrumentSpec::new(crate::market_data::SpecPolicy::Reject).with_lot_size(10),
+        ));
+
+        assert_eq!(
+            test.apply_snapshot(&[(MarketSide::Bid, 2, 11, 10), (MarketSide::Bid, 3, 10, 5)]),
+            Err(MarketDataError::OffLotSize)
+        );
+        assert_eq!(
+            test.get_levels(MarketSide::Bid).collect::<Vec<_>>(),
+            vec![(12, 10)]
+        );
+    }
+
+    #[test]
+    fn batch_publishes_once() {
+        let mut test = L3MarketData::new();
+        let callback = Rc::new(LevelCallback::new());
+        let top_of_book = Rc::new(TestCallback::new());
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 1, 12, 10)
+            .unwrap();
+        test.subscribe_levels(callback.clone());
+        test.get_top_of_book().subscribe(top_of_book.clone());
+
+        test.apply_batch(&[
+            L3Update::new(UpdateAction::Add, MarketSide::Bid, 2, 12, 5),
+            L3Update::new(UpdateAction::Update, MarketSide::Bid, 1, 12, 20),
+            L3Update::new(UpdateAction::Add, MarketSide::Offer, 3, 15, 10),
+            L3Update::new(UpdateAction::Remove, MarketSide::Offer, 3, 15, 0),
+            L3Update::new(UpdateAction::Add, MarketSide::Offer, 3, 16, 10),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            callback.take(),
+            vec![
+                L2Update::new(UpdateAction::Update, MarketSide::Bid, 12, 25),
+                L2Update::new(UpdateAction::Add, MarketSide::Offer, 16, 10),
+            ]
+        );
+        assert!(top_of_book.is_called());
+        assert_eq!(*test.get_top_of_book().get_offer(), Some(16));
+    }
+
+    #[test]
+    fn rejected_batch_rolls_back() {
+        let mut test = L3MarketData::<u64, i32, i32>::new();
+        let callback = Rc::new(LevelCallback::new());
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 1, 12, 10)
+            .unwrap();
+        test.subscribe_levels(callback.clone());
+
+        assert_eq!(
+            test.apply_batch(&[
+                L3Update::new(UpdateAction::Remove, MarketSide::Bid, 1, 12, 0),
+                L3Update::new(UpdateAction::Add, MarketSide::Bid, 2, 11, 10),
+                L3Update::new(UpdateAction::Update, MarketSide::Bid, 1, 12, 5),
+            ]),
+            Err(MarketDataError::UnknownOrder)
+        );
+        assert_eq!(callback.take(), vec![]);
+        assert_eq!(
+            test.get_levels(MarketSide::Bid).collect::<Vec<_>>(),
+            vec![(12, 10)]
+        );
+
+        test.set_instrument_spec(Some(
+            InstrumentSpec::new(crate::market_data::SpecPolicy::Reject).with_lot_size(10),
+        ));
+        assert_eq!(
+            test.apply_batch(&[
+                L3Update::new(UpdateAction::Add, MarketSide::Bid, 2, 11, 10),
+                L3Update::new(UpdateAction::Update, MarketSide::Bid, 2, 11, 15),
+            ]),
+            Err(MarketDataError::OffLotSize)
+        );
+        assert_eq!(
+            test.get_levels(MarketSide::Bid).collect::<Vec<_>>(),
+            vec![(12, 10)]
+        );
+    }
+
+    #[test]
+    fn diff_turns_one_book_into_another() {
+        let mut before = L3MarketData::<u64, i32, i32>::new();
+        let mut after = L3MarketData::<u64, i32, i32>::new();
+
+        before
+            .apply_snapshot(&[
+                (MarketSide::Bid, 1, 12, 10),
+                (MarketSide::Bid, 2, 11, 10),
+                (MarketSide::Offer, 3, 15, 10),
+                (MarketSide::Offer, 4, 16, 10),
+            ])
+            .unwrap();
+        after
+            .apply_snapshot(&[
+                (MarketSide::Bid, 1, 12, 20),
+                (MarketSide::Bid, 3, 13, 10),
+                (MarketSide::Offer, 4, 16, 10),
+                (MarketSide::Offer, 5, 17, 10),
+            ])
+            .unwrap();
+
+        let updates = before.diff(&after);
+
+        assert_eq!(
+            updates,
+            vec![
+                L3Update::new(UpdateAction::Remove, MarketSide::Bid, 2, 11, 10),
+                L3Update::new(UpdateAction::Remove, MarketSide::Offer, 3, 15, 10),
+                L3Update::new(UpdateAction::Update, MarketSide::Bid, 1, 12, 20),
+                L3Update::new(UpdateAction::Add, MarketSide::Bid, 3, 13, 10),
+                L3Update::new(UpdateAction::Add, MarketSide::Offer, 5, 17, 10),
+            ]
+        );
+
+        before.apply_batch(&updates).unwrap();
+
+        assert_eq!(before.diff(&after), vec![]);
+        for side in [MarketSide::Bid, MarketSide::Offer] {
+            assert_eq!(
+                before.get_levels(side).collect::<Vec<_>>(),
+                after.get_levels(side).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn remove_stale_crossed_orders() {
+        let mut test = L3MarketData::<u64, i32, i32>::new();
+        let callback = Rc::new(LevelCallback::new());
+
+        test.update(UpdateAction::Add, MarketSide::Offer, 1, 11, 50)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 2, 11, 30)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 3, 12, 40)
+            .unwrap();
+        test.subscribe_levels(callback.clone());
+
+        test.set_cross_policy(CrossPolicy::RemoveStale);
+        test.update(UpdateAction::Add, MarketSide::Bid, 4, 12, 20)
+            .unwrap();
+
+        assert_eq!(
+            callback.take(),
+            vec![
+                L2Update::new(UpdateAction::Remove, MarketSide::Offer, 11, 80),
+                L2Update::new(UpdateAction::Add, MarketSide::Bid, 12, 20),
+            ]
+        );
+        assert_eq!(test.get_cross_status(), CrossStatus::Locked);
+        assert_eq!(test.diff(&L3MarketData::new()).len(), 2);
+    }
+
+    #[test]
+    fn rejected_crossing_batch_rolls_back() {
+        let mut test = L3MarketData::<u64, i32, i32>::new();
+
+        test.set_cross_policy(CrossPolicy::Reject);
+        test.update(UpdateAction::Add, MarketSide::Offer, 1, 11, 50)
+            .unwrap();
+
+        assert_eq!(
+            test.apply_batch(&[
+                L3Update::new(UpdateAction::Add, MarketSide::Bid, 2, 10, 50),
+                L3Update::new(UpdateAction::Update, MarketSide::Bid, 2, 12, 50),
+            ]),
+            Err(MarketDataError::CrossedBook)
+        );
+        assert_eq!(test.get_levels(MarketSide::Bid).count(), 0);
+        assert_eq!(
+            test.apply_snapshot(&[(MarketSide::Bid, 2, 12, 50), (MarketSide::Offer, 1, 11, 50)]),
+            Err(MarketDataError::CrossedBook)
+        );
+        assert_eq!(test.get_price(50), BidOffer::new_with_price(None, Some(11)));
+    }
+
+    #[test]
+    fn validate_finds_inconsistencies() {
+        let mut test = L3MarketData::<u64, i32, i32>::new();
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 1, 10, 50)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Bid, 2, 10, 30)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 3, 12, 40)
+            .unwrap();
+        test.update(UpdateAction::Update, MarketSide::Bid, 2, 10, 10)
+            .unwrap();
+        assert!(test.validate().is_valid());
+
+        test.bids.get_mut(&10).unwrap().size = 70;
+        test.offers.insert(
+            13,
+            MarketLevel {
+                size: 0,
+                prices: BTreeMap::new(),
+            },
+        );
+        test.prices.get_mut(&3).unwrap().price = 11;
+        test.offers.insert(
+            9,
+            MarketLevel {
+                size: 5,
+                prices: BTreeMap::from([(4, MarketLiquidity { size: 5 })]),
+            },
+        );
+
+        assert_eq!(
+            test.validate().get_inconsistencies(),
+            [
+                L3Inconsistency::LevelSize {
+                    side: MarketSide::Bid,
+                    price: 10,
+                    level_size: 70,
+                    order_size: 60
+                },
+                L3Inconsistency::UnindexedOrder {
+                    id: 4,
+                    side: MarketSide::Offer,
+                    price: 9
+                },
+                L3Inconsistency::UnindexedOrder {
+                    id: 3,
+                    side: MarketSide::Offer,
+                    price: 12
+                },
+                L3Inconsistency::EmptyLevel {
+                    side: MarketSide::Offer,
+                    price: 13
+                },
+                L3Inconsistency::MissingOrder {
+                    id: 3,
+                    side: MarketSide::Offer,
+                    price: 11
+                },
+                L3Inconsistency::Crossed { bid: 10, offer: 9 },
+            ]
+        );
+    }
+
+    #[test]
+    fn fair_values_use_level_sizes() {
+        let mut test = L3MarketData::<u64, i32, i32>::new();
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 1, 100, 10)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Bid, 2, 100, 20)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 3, 110, 10)
+            .unwrap();
+
+        assert_eq!(test.get_weighted_mid(), Some(103));
+        assert_eq!(test.get_microprice(), Some(108));
+        assert_eq!(test.get_mid_at_size(30), Some(100));
+    }
+
+    #[test]
+    fn order_metrics() {
+        let mut test = L3MarketData::<u64, i32, i32>::new();
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 1, 10, 50)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Bid, 2, 10, 30)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Bid, 3, 9, 20)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 4, 12, 100)
+            .unwrap();
+
+        assert_eq!(test.get_level_count(MarketSide::Bid), 2);
+        assert_eq!(test.get_order_count(MarketSide::Bid), 3);
+        assert_eq!(test.get_order_count(MarketSide::Offer), 1);
+        assert_eq!(test.get_average_order_size(MarketSide::Bid, 10), Some(40));
+        assert_eq!(test.get_average_order_size(MarketSide::Bid, 11), None);
+        assert_eq!(test.get_imbalance(1), Some(-0.1111111111111111));
+        assert_eq!(test.get_imbalance(2), Some(0.0));
+    }
+
+    #[test]
+    fn new_actions() {
+        let mut test = L3MarketData::<u64, i32, i32>::new();
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 1, 10, 50)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Bid, 2, 10, 30)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 3, 12, 50)
+            .unwrap();
+
+        // The replaced order goes to the back of the queue at its new size
+        test.update(UpdateAction::Replace, MarketSide::Bid, 1, 10, 20)
+            .unwrap();
+        assert_eq!(
+            test.get_levels(MarketSide::Bid).collect::<Vec<_>>(),
+            vec![(10, 50)]
+        );
+        test.update(UpdateAction::Replace, MarketSide::Bid, 4, 9, 10)
+            .unwrap();
+        assert_eq!(test.get_order_count(MarketSide::Bid), 3);
+
+        test.update(UpdateAction::ClearSide, MarketSide::Bid, 0, 0, 0)
+            .unwrap();
+        assert_eq!(test.get_levels(MarketSide::Bid).count(), 0);
+        assert_eq!(
+            test.update(UpdateAction::Remove, MarketSide::Bid, 1, 10, 0),
+            Err(MarketDataError::UnknownOrder)
+        );
+        assert!(test.validate().is_valid());
+
+        assert_eq!(
+            test.apply_batch(&[
+                L3Update::new(UpdateAction::Snapshot, MarketSide::Bid, 5, 11, 10),
+                L3Update::new(UpdateAction::Update, MarketSide::Offer, 3, 12, 10),
+            ]),
+            Err(MarketDataError::UnknownOrder)
+        );
+        test.apply_batch(&[
+            L3Update::new(UpdateAction::Snapshot, MarketSide::Bid, 5, 11, 10),
+            L3Update::new(UpdateAction::Add, MarketSide::Offer, 3, 12, 10),
+        ])
+        .unwrap();
+        assert_eq!(
+            test.get_price(10),
+            BidOffer::new_with_price(Some(11), Some(12))
+        );
+        assert_eq!(test.get_order_count(MarketSide::Offer), 1);
+        assert!(test.validate().is_valid());
+    }
+
+    #[test]
+    fn diff_replays_without_crossing() {
+        for policy in [
+            CrossPolicy::Reject,
+            CrossPolicy::RemoveStale,
+            CrossPolicy::KeepAndFlag,
+        ] {
+            let mut before = L3MarketData::<u64, i32, i32>::new();
+            let mut after = L3MarketData::<u64, i32, i32>::new();
+
+            before.set_cross_policy(policy);
+            before
+                .apply_snapshot(&[
+                    (MarketSide::Bid, 1, 10, 50),
+                    (MarketSide::Offer, 2, 11, 50),
+                    (MarketSide::Offer, 3, 12, 50),
+                ])
+                .unwrap();
+            after
+                .apply_snapshot(&[
+                    (MarketSide::Bid, 1, 12, 50),
+                    (MarketSide::Offer, 2, 13, 50),
+                    (MarketSide::Bid, 3, 11, 50),
+                ])
+                .unwrap();
+
+            for update in before.diff(&after) {
+                before.apply_incremental(&update).unwrap();
+                assert_eq!(before.get_cross_status(), CrossStatus::Normal);
+            }
+
+            assert_eq!(before.diff(&after), vec![]);
+        }
+    }
+
+    #[test]
+    fn failed_batch_leaves_book_for_each_cross_policy() {
+        let batch = [
+            L3Update::new(UpdateAction::Add, MarketSide::Bid, 5, 8, 10),
+            L3Update::new(UpdateAction::Add, MarketSide::Offer, 2, 9, 50),
+            L3Update::new(UpdateAction::Update, MarketSide::Bid, 1, 10, 20),
+        ];
+
+        for (policy, expected) in [
+            (CrossPolicy::Reject, Err(MarketDataError::CrossedBook)),
+            // The offer crosses the bid, which is removed as stale before it is updated
+            (CrossPolicy::RemoveStale, Err(MarketDataError::UnknownOrder)),
+            (CrossPolicy::KeepAndFlag, Ok(())),
+        ] {
+            let mut test = L3MarketData::<u64, i32, i32>::new();
+
+            test.set_cross_policy(policy);
+            test.update(UpdateAction::Add, MarketSide::Bid, 1, 10, 50)
+                .unwrap();
+
+            assert_eq!(test.apply_batch(&batch), expected);
+
+            if expected.is_err() {
+                assert_eq!(
+                    test.get_levels(MarketSide::Bid).collect::<Vec<_>>(),
+                    vec![(10, 50)]
+                );
+                assert_eq!(test.get_levels(MarketSide::Offer).count(), 0);
+            } else {
+                assert_eq!(
+                    test.get_levels(MarketSide::Bid).collect::<Vec<_>>(),
+                    vec![(10, 20), (8, 10)]
+                );
+                assert_eq!(test.get_cross_status(), CrossStatus::Crossed);
+            }
+        }
+    }
+
+    #[test]
+    fn unsigned_size_decrease() {
+        let mut test = L3MarketData::<u64, i32, u32>::new();
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 1, 10, 50)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Bid, 2, 10, 30)
+            .unwrap();
+        test.update(UpdateAction::Update, MarketSide::Bid, 1, 10, 20)
+            .unwrap();
+
+        assert_eq!(
+            test.get_levels(MarketSide::Bid).collect::<Vec<_>>(),
+            vec![(10, 50)]
+        );
+        assert_eq!(test.get_average_order_size(MarketSide::Bid, 10), Some(25));
+        assert!(test.validate().is_valid());
+    }
+
+    #[test]
+    fn add_existing_order_replaces_it() {
+        let mut test = L3MarketData::<u64, i32, i32>::new();
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 1, 10, 50)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Bid, 1, 9, 30)
+            .unwrap();
+
+        assert_eq!(
+            test.get_levels(MarketSide::Bid).collect::<Vec<_>>(),
+            vec![(9, 30)]
+        );
+        assert_eq!(test.get_order_count(MarketSide::Bid), 1);
+        assert!(test.validate().is_valid());
+    }
+
+    #[test]
+    fn status() {
+        let hours = |hours: u64| Duration::from_secs(hours * 60 * 60);
+        let mut test = L3MarketData::<u64, i32, i32>::new();
+
+        test.update(UpdateAction::Add, MarketSide::Bid, 1, 10, 50)
+            .unwrap();
+        test.update(UpdateAction::Add, MarketSide::Offer, 2, 12, 50)
+            .unwrap();
+        test.set_schedule(Some(TradingSchedule::new(vec![
+            (hours(7), MarketStatus::PreOpen),
+            (hours(9), MarketStatus::Continuous),
+            (hours(16), MarketStatus::Closed),
+        ])));
+
+        assert_eq!(test.check_schedule(hours(8)), Some(MarketStatus::PreOpen));
+        assert_eq!(test.get_price(50), BidOffer::new());
+        assert_eq!(test.get_mid_at_size(50), Some(11));
+
+        assert_eq!(
+            test.check_schedule(hours(9)),
+            Some(MarketStatus::Continuous)
+        );
+        assert_eq!(
+            test.get_price(50),
+            BidOffer::new_with_price(Some(10), Some(12))
+        );
+
+        assert_eq!(
+            test.set_status(MarketStatus::PreOpen),
+            Err(MarketDataError::InvalidTransition)
+        );
+        assert_eq!(test.get_status(), MarketStatus::Continuous);
+    }
 }
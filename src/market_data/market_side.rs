@@ -1,7 +1,101 @@
+use super::ParseError;
+use std::{fmt, str::FromStr};
+
 /// The side of the market for the price
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub enum MarketSide {
     /// The price is a Bid
     Bid,
     /// The price is an Offer
     Offer,
 }
+
+impl MarketSide {
+    /// Get the other side of the market
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::MarketSide;
+    ///
+    /// assert_eq!(MarketSide::Bid.opposite(), MarketSide::Offer);
+    /// ```
+    pub fn opposite(self) -> Self {
+        match self {
+            MarketSide::Bid => MarketSide::Offer,
+            MarketSide::Offer => MarketSide::Bid,
+        }
+    }
+
+    /// Get the direction in which a better price on the side moves, 1 for bids and -1 for offers
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::MarketSide;
+    ///
+    /// assert_eq!(MarketSide::Bid.sign(), 1);
+    /// assert_eq!(MarketSide::Offer.sign(), -1);
+    /// ```
+    pub fn sign(self) -> i32 {
+        match self {
+            MarketSide::Bid => 1,
+            MarketSide::Offer => -1,
+        }
+    }
+
+    /// Returns true if a price is better than another on the side, meaning higher for bids and lower for offers
+    ///
+    /// # Parameters
+    ///
+    /// * `price` - The price to compare
+    /// * `than` - The price to compare it with
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::MarketSide;
+    ///
+    /// assert!(MarketSide::Bid.is_better(11, 10));
+    /// assert!(MarketSide::Offer.is_better(10, 11));
+    /// assert!(!MarketSide::Offer.is_better(10, 10));
+    /// ```
+    pub fn is_better<P: PartialOrd>(self, price: P, than: P) -> bool {
+        match self {
+            MarketSide::Bid => price > than,
+            MarketSide::Offer => price < than,
+        }
+    }
+}
+
+impl fmt::Display for MarketSide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarketSide::Bid => write!(f, "Bid"),
+            MarketSide::Offer => write!(f, "Offer"),
+        }
+    }
+}
+
+/// Parses the side ignoring case, accepting "Bid" or "B" for bids and "Offer", "Ask", "O" or "A" for offers
+///
+/// # Example
+///
+/// ```
+/// use pricing::market_data::MarketSide;
+///
+/// assert_eq!("ask".parse::<MarketSide>(), Ok(MarketSide::Offer));
+/// assert_eq!(MarketSide::Bid.to_string().parse::<MarketSide>(), Ok(MarketSide::Bid));
+/// assert!("buy".parse::<MarketSide>().is_err());
+/// ```
+impl FromStr for MarketSide {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "bid" | "b" => Ok(MarketSide::Bid),
+            "offer" | "ask" | "o" | "a" => Ok(MarketSide::Offer),
+            _ => Err(ParseError::new("market side", value)),
+        }
+    }
+}
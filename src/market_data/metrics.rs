@@ -0,0 +1,67 @@
+use super::FloatConvert;
+use std::ops::Add;
+
+/// The imbalance between the size on the best levels of each side, from -1 when there is only offer size to 1 when there
+/// is only bid size.  None if there is no size on either side.
+pub(crate) fn imbalance<P, A, B, O>(bids: B, offers: O, levels: usize) -> Option<f64>
+where
+    A: FloatConvert,
+    B: Iterator<Item = (P, A)>,
+    O: Iterator<Item = (P, A)>,
+{
+    let bid_size: f64 = bids.take(levels).map(|(_, size)| size.to_f64()).sum();
+    let offer_size: f64 = offers.take(levels).map(|(_, size)| size.to_f64()).sum();
+    let total = bid_size + offer_size;
+
+    (total > 0.0).then(|| (bid_size - offer_size) / total)
+}
+
+/// The total size of the levels, best first, until the first which is not within range
+pub(crate) fn depth_within<P: Copy, A, L, F>(levels: L, within: F) -> A
+where
+    A: Default + Add<Output = A>,
+    L: Iterator<Item = (P, A)>,
+    F: Fn(P) -> bool,
+{
+    levels
+        .take_while(|(price, _)| within(*price))
+        .fold(A::default(), |total, (_, size)| total + size)
+}
+
+/// The distance from one price to another in basis points of the mid, which is negative if the second is lower
+pub(crate) fn bps<P: FloatConvert>(from: P, to: P, mid: P) -> Option<f64> {
+    let mid = mid.to_f64();
+
+    (mid != 0.0).then(|| (to.to_f64() - from.to_f64()) / mid.abs() * 10_000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calculations() {
+        let bids = [(10, 30), (9, 50)];
+        let offers = [(11, 10), (12, 10)];
+
+        assert_eq!(
+            imbalance(bids.into_iter(), offers.into_iter(), 1),
+            Some(0.5)
+        );
+        assert_eq!(
+            imbalance(bids.into_iter(), offers.into_iter(), 2),
+            Some(0.6)
+        );
+        assert_eq!(
+            imbalance(std::iter::empty::<(i32, i32)>(), std::iter::empty(), 1),
+            None
+        );
+
+        assert_eq!(depth_within(bids.into_iter(), |price| price >= 10), 30);
+        assert_eq!(depth_within(bids.into_iter(), |price| price >= 11), 0);
+
+        assert_eq!(bps(99.0, 101.0, 100.0), Some(200.0));
+        assert_eq!(bps(101.0, 99.0, 100.0), Some(-200.0));
+        assert_eq!(bps(1, 2, 0), None);
+    }
+}
@@ -1,13 +1,51 @@
+pub mod aggregated;
 pub mod bid_offer;
+pub mod consolidated;
+pub mod cross;
+pub mod error;
+pub mod expiry;
+pub mod fair_value;
+pub mod impact;
+pub mod instrument;
+pub mod instrument_spec;
 pub mod l1;
 pub mod l2;
 pub mod l3;
 pub mod market_side;
+pub(crate) mod metrics;
+pub mod numeric;
+pub mod quality;
+pub mod registry;
+pub mod sequenced;
+pub mod session;
+#[cfg(test)]
+mod test_callback;
 pub mod update_action;
+pub mod update_mode;
+pub mod vwap;
 
+pub use aggregated::{AggregatedFullAmountMarketData, LpFilter};
 pub use bid_offer::BidOffer;
+pub use consolidated::ConsolidatedMarketData;
+pub use cross::{CrossPolicy, CrossStatus};
+pub use error::{MarketDataError, ParseError};
+pub use expiry::{Clock, ManualClock, SystemClock};
+pub use fair_value::OneSidedPolicy;
+pub use impact::MarketImpact;
+pub use instrument::{AssetClass, Instrument, QuotingConvention, SettlementConvention};
+pub use instrument_spec::{Increment, InstrumentSpec, SpecPolicy, TickSize};
+pub use l1::{L1MarketCallback, L1MarketData, L1MarketDataWithMax};
+pub use l2::{
+    Extrapolation, Interpolation, L2FullAmountMarketData, L2MarketCallback, L2SweepableMarketData,
+    L2Update,
+};
+pub use l3::{L3Inconsistency, L3MarketData, L3Update, L3ValidationReport};
 pub use market_side::MarketSide;
+pub use numeric::FloatConvert;
+pub use quality::{PriceQuality, QuotedPrice};
+pub use registry::{MarketDataBook, MarketDataRegistry, RegistryCallback};
+pub use sequenced::{IncrementalBook, SequenceOutcome, SequenceStatus, SequencedMarketData};
+pub use session::{MarketStatus, TradingSchedule};
 pub use update_action::UpdateAction;
-pub use l1::{L1MarketCallback, L1MarketData, L1MarketDataWithMax};
-pub use l2::{L2FullAmountMarketData, L2SweepableMarketData};
-pub use l3::L3MarketData;
\ No newline at end of file
+pub use update_mode::UpdateMode;
+pub use vwap::{CompensatedSum, UncheckedVwap, VwapAmount};
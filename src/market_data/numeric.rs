@@ -0,0 +1,61 @@
+/// Conversion to and from `f64`, used where prices and amounts have to be combined in ways which cannot be done exactly
+/// in the type itself, such as interpolating between prices.
+///
+/// Implementations are provided for the primitive integer and floating point types.  Converting back to an integer type
+/// rounds to the nearest value and saturates at the bounds of the type.
+pub trait FloatConvert: Copy {
+    /// Converts the value to an `f64`
+    fn to_f64(self) -> f64;
+
+    /// Converts an `f64` to the type
+    fn from_f64(value: f64) -> Self;
+}
+
+macro_rules! integer_float_convert {
+    ($($t:ty),*) => {
+        $(
+            impl FloatConvert for $t {
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn from_f64(value: f64) -> Self {
+                    value.round() as $t
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! float_float_convert {
+    ($($t:ty),*) => {
+        $(
+            impl FloatConvert for $t {
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn from_f64(value: f64) -> Self {
+                    value as $t
+                }
+            }
+        )*
+    };
+}
+
+integer_float_convert!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+float_float_convert!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        assert_eq!(i32::from_f64(12.6), 13);
+        assert_eq!(u8::from_f64(-1.0), 0);
+        assert_eq!(u8::from_f64(300.0), 255);
+        assert_eq!(15i64.to_f64(), 15.0);
+        assert_eq!(f64::from_f64(1.25), 1.25);
+    }
+}
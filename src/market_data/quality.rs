@@ -0,0 +1,180 @@
+use super::{BidOffer, MarketSide};
+use std::ops::{Add, Div};
+
+/// The quality of the price on one side of a book, which says whether it can be traded on
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub enum PriceQuality {
+    /// The price can be traded on
+    #[default]
+    Firm,
+    /// The price is for information only and cannot be traded on
+    Indicative,
+    /// The price has not been updated within its time to live
+    Stale,
+    /// The market is closed so the price is the last one seen
+    Closed,
+    /// The price has been derived from another source, identified by the id, rather than quoted
+    Derived(u32),
+}
+
+impl PriceQuality {
+    /// Returns true if the price can be traded on
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::PriceQuality;
+    ///
+    /// assert!(PriceQuality::Firm.is_firm());
+    /// assert!(!PriceQuality::Indicative.is_firm());
+    /// assert!(!PriceQuality::Derived(1).is_firm());
+    /// ```
+    pub fn is_firm(self) -> bool {
+        self == PriceQuality::Firm
+    }
+}
+
+/// A price along with the quality of each side, so that a consumer can refuse to trade on a side which is not firm
+///
+/// # Generic Parameters
+///
+/// * `P` - The price type that should be used.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct QuotedPrice<P>
+where
+    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<u8>,
+{
+    price: BidOffer<P>,
+    bid_quality: PriceQuality,
+    offer_quality: PriceQuality,
+}
+
+impl<P> QuotedPrice<P>
+where
+    P: Copy + PartialOrd + Add<Output = P> + Div<Output = P> + From<u8>,
+{
+    /// Use the new function to create a new QuotedPrice
+    ///
+    /// # Parameters
+    ///
+    /// * `price` - The bid/offer
+    /// * `bid_quality` - The quality of the bid
+    /// * `offer_quality` - The quality of the offer
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, MarketSide, PriceQuality, QuotedPrice};
+    ///
+    /// let quoted = QuotedPrice::new(BidOffer::new_with_price(Some(10), Some(12)), PriceQuality::Firm, PriceQuality::Indicative);
+    ///
+    /// assert_eq!(quoted.get_quality(MarketSide::Offer), PriceQuality::Indicative);
+    /// ```
+    pub fn new(price: BidOffer<P>, bid_quality: PriceQuality, offer_quality: PriceQuality) -> Self {
+        Self {
+            price,
+            bid_quality,
+            offer_quality,
+        }
+    }
+
+    /// Get the price whatever its quality
+    pub fn get_price(&self) -> BidOffer<P> {
+        self.price
+    }
+
+    /// Get the quality of a side
+    pub fn get_quality(&self, side: MarketSide) -> PriceQuality {
+        match side {
+            MarketSide::Bid => self.bid_quality,
+            MarketSide::Offer => self.offer_quality,
+        }
+    }
+
+    /// Returns true if there is a price on the side which can be traded on
+    pub fn is_firm(&self, side: MarketSide) -> bool {
+        self.price.get(side).is_some() && self.get_quality(side).is_firm()
+    }
+
+    /// Get the price with any side which is not firm removed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{BidOffer, PriceQuality, QuotedPrice};
+    ///
+    /// let quoted = QuotedPrice::new(BidOffer::new_with_price(Some(10), Some(12)), PriceQuality::Firm, PriceQuality::Stale);
+    ///
+    /// assert_eq!(quoted.get_firm_price(), BidOffer::new_with_price(Some(10), None));
+    /// ```
+    pub fn get_firm_price(&self) -> BidOffer<P> {
+        let firm = |side| {
+            if self.get_quality(side).is_firm() {
+                *self.price.get(side)
+            } else {
+                None
+            }
+        };
+
+        BidOffer::new_with_price(firm(MarketSide::Bid), firm(MarketSide::Offer))
+    }
+}
+
+/// The quality set on each side of a book
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub(crate) struct SideQuality {
+    bid: PriceQuality,
+    offer: PriceQuality,
+}
+
+impl SideQuality {
+    /// Set the quality of a side, returning true if it has changed
+    pub(crate) fn set(&mut self, side: MarketSide, quality: PriceQuality) -> bool {
+        let current = match side {
+            MarketSide::Bid => &mut self.bid,
+            MarketSide::Offer => &mut self.offer,
+        };
+
+        std::mem::replace(current, quality) != quality
+    }
+
+    /// Get the quality of a side, which is stale if the book has flagged the side as stale unless the market is closed
+    pub(crate) fn get(&self, side: MarketSide, stale: bool) -> PriceQuality {
+        let quality = match side {
+            MarketSide::Bid => self.bid,
+            MarketSide::Offer => self.offer,
+        };
+
+        if stale && quality != PriceQuality::Closed {
+            PriceQuality::Stale
+        } else {
+            quality
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn side_quality() {
+        let mut test = SideQuality::default();
+
+        assert!(test.set(MarketSide::Bid, PriceQuality::Indicative));
+        assert!(!test.set(MarketSide::Bid, PriceQuality::Indicative));
+        assert_eq!(test.get(MarketSide::Bid, false), PriceQuality::Indicative);
+        assert_eq!(test.get(MarketSide::Offer, true), PriceQuality::Stale);
+
+        test.set(MarketSide::Offer, PriceQuality::Closed);
+        assert_eq!(test.get(MarketSide::Offer, true), PriceQuality::Closed);
+
+        let quoted = QuotedPrice::new(
+            BidOffer::new_with_price(Some(10), Some(12)),
+            test.get(MarketSide::Bid, false),
+            test.get(MarketSide::Offer, false),
+        );
+        assert!(!quoted.is_firm(MarketSide::Bid));
+        assert_eq!(quoted.get_firm_price(), BidOffer::new());
+    }
+}
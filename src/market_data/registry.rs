@@ -0,0 +1,698 @@
+use super::{
+    Increment, L1MarketData, L1MarketDataWithMax, L2FullAmountMarketData, L2SweepableMarketData,
+    L3MarketData, MarketDataError, MarketSide, MarketStatus, UpdateAction, VwapAmount,
+};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    hash::Hash,
+    ops::{Add, AddAssign, Div, Sub, SubAssign},
+    rc::Rc,
+};
+
+/// Called when an update to an instrument is routed through the registry
+pub trait RegistryCallback<K> {
+    fn instrument_updated(&self, id: &K);
+}
+
+type RegistryCallbacks<K> = Vec<Rc<dyn RegistryCallback<K>>>;
+
+/// A book of any of the kinds of market data which can be held in the registry.
+///
+/// # Generic Parameters
+///
+/// * `I` - The order id type used by L3 books.
+/// * `P` - The price type that should be used.
+/// * `A` - The amount type that should be used.
+pub enum MarketDataBook<I, P, A>
+where
+    I: Ord + Copy,
+    P: Copy + PartialOrd + Ord + Add<Output = P> + Div<Output = P> + Default + From<u8> + Increment,
+    A: Copy
+        + PartialOrd
+        + Ord
+        + Add<Output = A>
+        + Sub<Output = A>
+        + AddAssign
+        + SubAssign
+        + Div<P, Output = A>
+        + Div<Output = A>
+        + Default
+        + From<u8>
+        + Increment
+        + VwapAmount<P>,
+{
+    /// A single level of pricing
+    L1(L1MarketData<P>),
+    /// A single level of pricing with max sizes
+    L1WithMax(L1MarketDataWithMax<P, A>),
+    /// Sweepable depth
+    L2Sweepable(L2SweepableMarketData<P, A>),
+    /// Full amount tiers
+    L2FullAmount(L2FullAmountMarketData<P, A>),
+    /// Order by order depth
+    L3(L3MarketData<I, P, A>),
+}
+
+impl<I, P, A> MarketDataBook<I, P, A>
+where
+    I: Ord + Copy,
+    P: Copy + PartialOrd + Ord + Add<Output = P> + Div<Output = P> + Default + From<u8> + Increment,
+    A: Copy
+        + PartialOrd
+        + Ord
+        + Add<Output = A>
+        + Sub<Output = A>
+        + AddAssign
+        + SubAssign
+        + Div<P, Output = A>
+        + Div<Output = A>
+        + Default
+        + From<u8>
+        + Increment
+        + VwapAmount<P>,
+{
+    /// Get the symbol of the instrument attached to the book
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use pricing::market_data::{AssetClass, Instrument, L2SweepableMarketData, MarketDataBook};
+    ///
+    /// let mut market_data = L2SweepableMarketData::new();
+    /// market_data.set_instrument(Some(Rc::new(Instrument::new("VOD.L", AssetClass::Equity, "GBP"))));
+    ///
+    /// let book = MarketDataBook::<u64, i32, i32>::L2Sweepable(market_data);
+    ///
+    /// assert_eq!(book.get_symbol(), Some("VOD.L"));
+    /// ```
+    pub fn get_symbol(&self) -> Option<&str> {
+        match self {
+            MarketDataBook::L1(book) => book.get_instrument().map(|i| i.get_symbol()),
+            MarketDataBook::L1WithMax(book) => book.get_instrument().map(|i| i.get_symbol()),
+            MarketDataBook::L2Sweepable(book) => book.get_instrument().map(|i| i.get_symbol()),
+            MarketDataBook::L2FullAmount(book) => book.get_instrument().map(|i| i.get_symbol()),
+            MarketDataBook::L3(book) => book.get_instrument().map(|i| i.get_symbol()),
+        }
+    }
+
+    /// Apply a price level update to the book.  For L1 books an Add or Update sets the price on the side (and for
+    /// `L1MarketDataWithMax` the size as the max), a Remove or ClearSide clears the side, and a Snapshot sets the side
+    /// and clears the other.  L3 books need an order id so must be updated with `update_order`.
+    ///
+    /// # Parameters
+    ///
+    /// * `action` - The update action
+    /// * `side` - The side of the market
+    /// * `price` - The price of the level
+    /// * `size` - The size of the level
+    pub fn update(
+        &mut self,
+        action: UpdateAction,
+        side: MarketSide,
+        price: P,
+        size: A,
+    ) -> Result<(), MarketDataError> {
+        let (l1_price, l1_max) = match action {
+            UpdateAction::Remove | UpdateAction::ClearSide => (None, None),
+            _ => (Some(price), Some(size)),
+        };
+
+        match self {
+            MarketDataBook::L1(book) if action == UpdateAction::Snapshot => match side {
+                MarketSide::Bid => book.update(l1_price, None),
+                MarketSide::Offer => book.update(None, l1_price),
+            },
+            MarketDataBook::L1WithMax(book) if action == UpdateAction::Snapshot => match side {
+                MarketSide::Bid => book.update_with_max(l1_price, None, l1_max, None),
+                MarketSide::Offer => book.update_with_max(None, l1_price, None, l1_max),
+            },
+            MarketDataBook::L1(book) => match side {
+                MarketSide::Bid => book.update_bid(l1_price),
+                MarketSide::Offer => book.update_offer(l1_price),
+            },
+            MarketDataBook::L1WithMax(book) => match side {
+                MarketSide::Bid => {
+                    book.update_with_max(l1_price, *book.get_offer(), l1_max, *book.get_max_offer())
+                }
+                MarketSide::Offer => {
+                    book.update_with_max(*book.get_bid(), l1_price, *book.get_max_bid(), l1_max)
+                }
+            },
+            MarketDataBook::L2Sweepable(book) => book.update(action, side, price, size),
+            MarketDataBook::L2FullAmount(book) => book.update(action, side, price, size),
+            MarketDataBook::L3(_) => Err(MarketDataError::UnsupportedUpdate),
+        }
+    }
+
+    /// Move the market for the book to a status
+    ///
+    /// # Parameters
+    ///
+    /// * `status` - The status of the market
+    pub fn set_status(&mut self, status: MarketStatus) -> Result<(), MarketDataError> {
+        match self {
+            MarketDataBook::L1(book) => book.set_status(status),
+            MarketDataBook::L1WithMax(book) => book.set_status(status),
+            MarketDataBook::L2Sweepable(book) => book.set_status(status),
+            MarketDataBook::L2FullAmount(book) => book.set_status(status),
+            MarketDataBook::L3(book) => book.set_status(status),
+        }
+    }
+}
+
+/// A registry of the market data for many instruments, keyed by instrument id.  Updates can be routed through the registry
+/// to the book for the instrument, and subscribers notified of updates to a single instrument or to all of them.
+///
+/// # Generic Parameters
+///
+/// * `K` - The instrument id type.
+/// * `I` - The order id type used by L3 books.
+/// * `P` - The price type that should be used.
+/// * `A` - The amount type that should be used.
+pub struct MarketDataRegistry<K, I, P, A>
+where
+    K: Eq + Hash + Clone,
+    I: Ord + Copy,
+    P: Copy + PartialOrd + Ord + Add<Output = P> + Div<Output = P> + Default + From<u8> + Increment,
+    A: Copy
+        + PartialOrd
+        + Ord
+        + Add<Output = A>
+        + Sub<Output = A>
+        + AddAssign
+        + SubAssign
+        + Div<P, Output = A>
+        + Div<Output = A>
+        + Default
+        + From<u8>
+        + Increment
+        + VwapAmount<P>,
+{
+    books: HashMap<K, MarketDataBook<I, P, A>>,
+    symbols: HashMap<String, K>,
+
+    callbacks: RefCell<HashMap<K, RegistryCallbacks<K>>>,
+    all_callbacks: RefCell<RegistryCallbacks<K>>,
+}
+
+impl<K, I, P, A> MarketDataRegistry<K, I, P, A>
+where
+    K: Eq + Hash + Clone,
+    I: Ord + Copy,
+    P: Copy + PartialOrd + Ord + Add<Output = P> + Div<Output = P> + Default + From<u8> + Increment,
+    A: Copy
+        + PartialOrd
+        + Ord
+        + Add<Output = A>
+        + Sub<Output = A>
+        + AddAssign
+        + SubAssign
+        + Div<P, Output = A>
+        + Div<Output = A>
+        + Default
+        + From<u8>
+        + Increment
+        + VwapAmount<P>,
+{
+    /// Use the new function to create a new empty MarketDataRegistry
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::MarketDataRegistry;
+    ///
+    /// let registry = MarketDataRegistry::<u32, u64, i32, i32>::new();
+    ///
+    /// assert!(registry.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            books: HashMap::new(),
+            symbols: HashMap::new(),
+            callbacks: RefCell::new(HashMap::new()),
+            all_callbacks: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Add the book for an instrument, replacing any existing book.  If the book has an instrument attached it can also
+    /// be looked up by the symbol of the instrument.
+    ///
+    /// # Parameters
+    ///
+    /// * `id` - The instrument id
+    /// * `book` - The book for the instrument
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use pricing::market_data::{
+    ///     AssetClass, Instrument, L2SweepableMarketData, MarketDataBook, MarketDataRegistry,
+    /// };
+    ///
+    /// let mut registry = MarketDataRegistry::<u32, u64, i32, i32>::new();
+    ///
+    /// let mut market_data = L2SweepableMarketData::new();
+    /// market_data.set_instrument(Some(Rc::new(Instrument::new("VOD.L", AssetClass::Equity, "GBP"))));
+    ///
+    /// registry.insert(1, MarketDataBook::L2Sweepable(market_data));
+    ///
+    /// assert!(registry.get(&1).is_some());
+    /// assert_eq!(registry.get_id_by_symbol("VOD.L"), Some(&1));
+    /// ```
+    pub fn insert(&mut self, id: K, book: MarketDataBook<I, P, A>) {
+        self.remove(&id);
+
+        if let Some(symbol) = book.get_symbol() {
+            self.symbols.insert(symbol.to_string(), id.clone());
+        }
+
+        self.books.insert(id, book);
+    }
+
+    /// Remove the book for an instrument, returning it if there was one.  A symbol which has since been given to the book
+    /// for another instrument is kept.
+    pub fn remove(&mut self, id: &K) -> Option<MarketDataBook<I, P, A>> {
+        let book = self.books.remove(id);
+
+        if book.is_some() {
+            self.symbols.retain(|_, symbol_id| symbol_id != id);
+        }
+
+        book
+    }
+
+    /// Get the book for an instrument
+    pub fn get(&self, id: &K) -> Option<&MarketDataBook<I, P, A>> {
+        self.books.get(id)
+    }
+
+    /// Get the book for an instrument to update it directly.  Subscribers to the registry are not notified of updates
+    /// made this way, and the symbol lookup is not updated if a different instrument is attached to the book, so insert
+    /// the book again to change its symbol.
+    pub fn get_mut(&mut self, id: &K) -> Option<&mut MarketDataBook<I, P, A>> {
+        self.books.get_mut(id)
+    }
+
+    /// Get the book for the instrument with a symbol
+    pub fn get_by_symbol(&self, symbol: &str) -> Option<&MarketDataBook<I, P, A>> {
+        self.symbols.get(symbol).and_then(|id| self.books.get(id))
+    }
+
+    /// Get the instrument id for a symbol
+    pub fn get_id_by_symbol(&self, symbol: &str) -> Option<&K> {
+        self.symbols.get(symbol)
+    }
+
+    /// Get the ids of all the instruments in the registry
+    pub fn ids(&self) -> impl Iterator<Item = &K> {
+        self.books.keys()
+    }
+
+    /// Get the number of instruments in the registry
+    pub fn len(&self) -> usize {
+        self.books.len()
+    }
+
+    /// Returns true if there are no instruments in the registry
+    pub fn is_empty(&self) -> bool {
+        self.books.is_empty()
+    }
+
+    /// Route a price level update to the book for an instrument, notifying subscribers if it is applied.
+    ///
+    /// # Parameters
+    ///
+    /// * `id` - The instrument id
+    /// * `action` - The update action
+    /// * `side` - The side of the market
+    /// * `price` - The price of the level
+    /// * `size` - The size of the level
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{
+    ///     BidOffer, L2SweepableMarketData, MarketDataBook, MarketDataError, MarketDataRegistry, MarketSide, UpdateAction,
+    /// };
+    ///
+    /// let mut registry = MarketDataRegistry::<u32, u64, i32, i32>::new();
+    ///
+    /// registry.insert(1, MarketDataBook::L2Sweepable(L2SweepableMarketData::new()));
+    ///
+    /// assert_eq!(registry.update(&1, UpdateAction::Add, MarketSide::Bid, 10, 100), Ok(()));
+    /// assert_eq!(
+    ///     registry.update(&2, UpdateAction::Add, MarketSide::Bid, 10, 100),
+    ///     Err(MarketDataError::UnknownInstrument)
+    /// );
+    ///
+    /// if let Some(MarketDataBook::L2Sweepable(book)) = registry.get(&1) {
+    ///     assert_eq!(book.get_price(100), BidOffer::new_with_price(Some(10), None));
+    /// }
+    /// ```
+    pub fn update(
+        &mut self,
+        id: &K,
+        action: UpdateAction,
+        side: MarketSide,
+        price: P,
+        size: A,
+    ) -> Result<(), MarketDataError> {
+        self.books
+            .get_mut(id)
+            .ok_or(MarketDataError::UnknownInstrument)?
+            .update(action, side, price, size)?;
+
+        self.publish_to_subscribers(id);
+
+        Ok(())
+    }
+
+    /// Route an order update to the L3 book for an instrument, notifying subscribers if it is applied.
+    ///
+    /// # Parameters
+    ///
+    /// * `id` - The instrument id
+    /// * `action` - The update action
+    /// * `side` - The side of the market
+    /// * `order_id` - The id of the order
+    /// * `price` - The price of the order
+    /// * `size` - The size of the order
+    pub fn update_order(
+        &mut self,
+        id: &K,
+        action: UpdateAction,
+        side: MarketSide,
+        order_id: I,
+        price: P,
+        size: A,
+    ) -> Result<(), MarketDataError> {
+        match self.books.get_mut(id) {
+            Some(MarketDataBook::L3(book)) => book.update(action, side, order_id, price, size)?,
+            Some(_) => return Err(MarketDataError::UnsupportedUpdate),
+            None => return Err(MarketDataError::UnknownInstrument),
+        }
+
+        self.publish_to_subscribers(id);
+
+        Ok(())
+    }
+
+    /// Route a market status update to the book for an instrument, notifying subscribers if it is applied.
+    ///
+    /// # Parameters
+    ///
+    /// * `id` - The instrument id
+    /// * `status` - The status of the market
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L1MarketData, MarketDataBook, MarketDataRegistry, MarketStatus};
+    ///
+    /// let mut registry = MarketDataRegistry::<u32, u64, i32, i32>::new();
+    ///
+    /// registry.insert(1, MarketDataBook::L1(L1MarketData::new_with_price(Some(10), Some(12))));
+    /// registry.update_status(&1, MarketStatus::Halted).unwrap();
+    ///
+    /// if let Some(MarketDataBook::L1(book)) = registry.get(&1) {
+    ///     assert_eq!(book.get_status(), MarketStatus::Halted);
+    /// }
+    /// ```
+    pub fn update_status(&mut self, id: &K, status: MarketStatus) -> Result<(), MarketDataError> {
+        self.books
+            .get_mut(id)
+            .ok_or(MarketDataError::UnknownInstrument)?
+            .set_status(status)?;
+
+        self.publish_to_subscribers(id);
+
+        Ok(())
+    }
+
+    /// Subscribe to updates routed through the registry to a single instrument.  NOTE: this will occur in the same thread
+    /// as the caller, so make sure that this does not cause a recursion issue.
+    ///
+    /// # Parameters
+    ///
+    /// * `id` - The instrument id
+    /// * `callback` - The object which implements the RegistryCallback trait to callback on
+    pub fn subscribe(&self, id: K, callback: Rc<dyn RegistryCallback<K>>) {
+        self.callbacks
+            .borrow_mut()
+            .entry(id)
+            .or_default()
+            .push(callback);
+    }
+
+    /// Subscribe to updates routed through the registry to any instrument.  NOTE: this will occur in the same thread as
+    /// the caller, so make sure that this does not cause a recursion issue.
+    ///
+    /// # Parameters
+    ///
+    /// * `callback` - The object which implements the RegistryCallback trait to callback on
+    pub fn subscribe_all(&self, callback: Rc<dyn RegistryCallback<K>>) {
+        self.all_callbacks.borrow_mut().push(callback);
+    }
+
+    fn publish_to_subscribers(&self, id: &K) {
+        if let Some(callbacks) = self.callbacks.borrow().get(id) {
+            for callback in callbacks.iter() {
+                callback.instrument_updated(id);
+            }
+        }
+
+        for callback in self.all_callbacks.borrow().iter() {
+            callback.instrument_updated(id);
+        }
+    }
+}
+
+impl<K, I, P, A> Default for MarketDataRegistry<K, I, P, A>
+where
+    K: Eq + Hash + Clone,
+    I: Ord + Copy,
+    P: Copy + PartialOrd + Ord + Add<Output = P> + Div<Output = P> + Default + From<u8> + Increment,
+    A: Copy
+        + PartialOrd
+        + Ord
+        + Add<Output = A>
+        + Sub<Output = A>
+        + AddAssign
+        + SubAssign
+        + Div<P, Output = A>
+        + Div<Output = A>
+        + Default
+        + From<u8>
+        + Increment
+        + VwapAmount<P>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market_data::{AssetClass, BidOffer, Instrument};
+
+    struct TestCallback {
+        updated: RefCell<Vec<u32>>,
+    }
+
+    impl TestCallback {
+        fn new() -> Self {
+            Self {
+                updated: RefCell::new(Vec::new()),
+            }
+        }
+
+        fn take(&self) -> Vec<u32> {
+            self.updated.borrow_mut().drain(..).collect()
+        }
+    }
+
+    impl RegistryCallback<u32> for TestCallback {
+        fn instrument_updated(&self, id: &u32) {
+            self.updated.borrow_mut().push(*id);
+        }
+    }
+
+    fn registry() -> MarketDataRegistry<u32, u64, i32, i32> {
+        let mut registry = MarketDataRegistry::new();
+
+        let mut l1 = L1MarketData::new();
+        l1.set_instrument(Some(Rc::new(Instrument::new(
+            "UST10Y",
+            AssetClass::FixedIncome,
+            "USD",
+        ))));
+
+        registry.insert(1, MarketDataBook::L1(l1));
+        registry.insert(2, MarketDataBook::L1WithMax(L1MarketDataWithMax::new()));
+        registry.insert(
+            3,
+            MarketDataBook::L2FullAmount(L2FullAmountMarketData::new()),
+        );
+        registry.insert(4, MarketDataBook::L3(L3MarketData::new()));
+
+        registry
+    }
+
+    #[test]
+    fn route_l1() {
+        let mut registry = registry();
+
+        registry
+            .update(&1, UpdateAction::Add, MarketSide::Bid, 10, 100)
+            .unwrap();
+        registry
+            .update(&1, UpdateAction::Update, MarketSide::Offer, 12, 100)
+            .unwrap();
+        registry
+            .update(&2, UpdateAction::Add, MarketSide::Offer, 12, 100)
+            .unwrap();
+
+        match registry.get_by_symbol("UST10Y") {
+            Some(MarketDataBook::L1(book)) => {
+                assert_eq!(
+                    book.get_price(),
+                    BidOffer::new_with_price(Some(10), Some(12))
+                )
+            }
+            _ => panic!("expected an L1 book"),
+        }
+
+        match registry.get(&2) {
+            Some(MarketDataBook::L1WithMax(book)) => {
+                assert_eq!(
+                    book.get_price(100),
+                    BidOffer::new_with_price(None, Some(12))
+                );
+                assert_eq!(book.get_price(101), BidOffer::new());
+            }
+            _ => panic!("expected an L1 book with max"),
+        }
+
+        registry
+            .update(&1, UpdateAction::Remove, MarketSide::Bid, 0, 0)
+            .unwrap();
+
+        match registry.get(&1) {
+            Some(MarketDataBook::L1(book)) => {
+                assert_eq!(book.get_price(), BidOffer::new_with_price(None, Some(12)))
+            }
+            _ => panic!("expected an L1 book"),
+        }
+    }
+
+    #[test]
+    fn route_errors() {
+        let mut registry = registry();
+
+        assert_eq!(
+            registry.update(&4, UpdateAction::Add, MarketSide::Bid, 10, 100),
+            Err(MarketDataError::UnsupportedUpdate)
+        );
+        assert_eq!(
+            registry.update_order(&3, UpdateAction::Add, MarketSide::Bid, 1, 10, 100),
+            Err(MarketDataError::UnsupportedUpdate)
+        );
+        assert_eq!(
+            registry.update_order(&5, UpdateAction::Add, MarketSide::Bid, 1, 10, 100),
+            Err(MarketDataError::UnknownInstrument)
+        );
+        assert_eq!(
+            registry.update_order(&4, UpdateAction::Remove, MarketSide::Bid, 1, 10, 100),
+            Err(MarketDataError::UnknownOrder)
+        );
+        assert_eq!(
+            registry.update_order(&4, UpdateAction::Add, MarketSide::Bid, 1, 10, 100),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn subscriptions() {
+        let mut registry = registry();
+
+        let single = Rc::new(TestCallback::new());
+        let all = Rc::new(TestCallback::new());
+        registry.subscribe(3, single.clone());
+        registry.subscribe_all(all.clone());
+
+        registry
+            .update(&1, UpdateAction::Add, MarketSide::Bid, 10, 100)
+            .unwrap();
+        registry
+            .update(&3, UpdateAction::Add, MarketSide::Bid, 10, 100)
+            .unwrap();
+        registry
+            .update(&4, UpdateAction::Add, MarketSide::Bid, 10, 100)
+            .unwrap_err();
+
+        assert_eq!(single.take(), vec![3]);
+        assert_eq!(all.take(), vec![1, 3]);
+    }
+
+    #[test]
+    fn replace_and_remove() {
+        let mut registry = registry();
+
+        assert_eq!(registry.len(), 4);
+        assert_eq!(registry.get_id_by_symbol("UST10Y"), Some(&1));
+
+        registry.insert(1, MarketDataBook::L1(L1MarketData::new()));
+
+        assert_eq!(registry.get_id_by_symbol("UST10Y"), None);
+        assert!(registry.remove(&1).is_some());
+        assert!(registry.remove(&1).is_none());
+        assert_eq!(registry.len(), 3);
+    }
+
+    #[test]
+    fn remove_keeps_symbol_moved_to_another_book() {
+        let mut registry = registry();
+
+        let mut l1 = L1MarketData::new();
+        l1.set_instrument(Some(Rc::new(Instrument::new(
+            "UST10Y",
+            AssetClass::FixedIncome,
+            "USD",
+        ))));
+        registry.insert(5, MarketDataBook::L1(l1));
+
+        assert_eq!(registry.get_id_by_symbol("UST10Y"), Some(&5));
+        assert!(registry.remove(&1).is_some());
+        assert_eq!(registry.get_id_by_symbol("UST10Y"), Some(&5));
+        assert!(registry.get_by_symbol("UST10Y").is_some());
+
+        assert!(registry.remove(&5).is_some());
+        assert_eq!(registry.get_id_by_symbol("UST10Y"), None);
+    }
+
+    #[test]
+    fn route_status() {
+        let mut registry = registry();
+
+        registry.update_status(&4, MarketStatus::Halted).unwrap();
+
+        if let Some(MarketDataBook::L3(book)) = registry.get(&4) {
+            assert_eq!(book.get_status(), MarketStatus::Halted);
+        } else {
+            panic!("expected an L3 book");
+        }
+
+        assert_eq!(
+            registry.update_status(&4, MarketStatus::PreOpen),
+            Err(MarketDataError::InvalidTransition)
+        );
+        assert_eq!(
+            registry.update_status(&5, MarketStatus::Halted),
+            Err(MarketDataError::UnknownInstrument)
+        );
+    }
+}
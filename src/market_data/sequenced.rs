@@ -0,0 +1,463 @@
+use super::MarketDataError;
+use std::collections::BTreeMap;
+
+/// A book which can be kept up to date by applying a stream of incremental updates
+pub trait IncrementalBook {
+    /// The incremental update the book is kept up to date with
+    type Update: Clone;
+
+    /// A single level or order in a snapshot of the book
+    type SnapshotEntry;
+
+    /// Apply a single incremental update to the book
+    fn apply_incremental(&mut self, update: &Self::Update) -> Result<(), MarketDataError>;
+
+    /// Replace the whole book with a snapshot, leaving it unchanged if the snapshot is rejected
+    fn apply_snapshot(&mut self, entries: &[Self::SnapshotEntry]) -> Result<(), MarketDataError>;
+}
+
+/// Whether every update to a sequenced book has been applied in order
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SequenceStatus {
+    /// Every update has been applied in order
+    Synchronised,
+    /// An update has been missed, so the book cannot be relied upon until the gap is filled or it is resynchronised
+    Unreliable {
+        /// The sequence number of the first missing update
+        expected: u64,
+    },
+    /// A snapshot has been requested, and every update is buffered until it is applied
+    Recovering,
+}
+
+/// What happened to a sequenced update
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SequenceOutcome {
+    /// The update was applied to the book, along with any buffered updates which follow on from it
+    Applied,
+    /// The update has already been seen and was ignored
+    Duplicate,
+    /// There is a gap before the update, so it was buffered until the gap is filled or the book is resynchronised
+    Buffered,
+}
+
+/// A wrapper around a book which applies incremental updates carrying sequence numbers in order, detecting missing and
+/// duplicated updates.  When an update is missed the book is marked as unreliable and later updates are buffered, until
+/// either the missing update arrives or the book is resynchronised from a snapshot.
+///
+/// To recover from a snapshot, call `begin_recovery` when the snapshot is requested so that incremental updates are
+/// buffered while it is in flight, then `resynchronise` when it arrives to apply it and replay the buffered updates which
+/// are newer than it.
+///
+/// # Generic Parameters
+///
+/// * `B` - The book which is kept up to date.
+pub struct SequencedMarketData<B>
+where
+    B: IncrementalBook,
+{
+    book: B,
+    last_sequence: Option<u64>,
+    status: SequenceStatus,
+    buffer: BTreeMap<u64, B::Update>,
+}
+
+impl<B> SequencedMarketData<B>
+where
+    B: IncrementalBook,
+{
+    /// Use the new function to wrap a book.  The first update received sets the starting sequence number.
+    ///
+    /// # Parameters
+    ///
+    /// * `book` - The book to keep up to date
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{L2SweepableMarketData, SequencedMarketData, SequenceStatus};
+    ///
+    /// let market_data = SequencedMarketData::new(L2SweepableMarketData::<i32, i32>::new());
+    ///
+    /// assert_eq!(market_data.get_status(), SequenceStatus::Synchronised);
+    /// assert_eq!(market_data.get_last_sequence(), None);
+    /// ```
+    pub fn new(book: B) -> Self {
+        Self {
+            book,
+            last_sequence: None,
+            status: SequenceStatus::Synchronised,
+            buffer: BTreeMap::new(),
+        }
+    }
+
+    /// Apply an update with its sequence number.  An update which fails to apply to the book still counts as received, so
+    /// the error is returned but the sequence moves on.  Once `u64::MAX` has been applied the sequence has ended and every
+    /// later update is a duplicate.
+    ///
+    /// Updates after a gap are buffered until the gap is filled or the book is resynchronised, and the buffer is not
+    /// bounded, so a caller which sees `get_buffered_count` grow should begin recovery rather than wait for the gap.
+    ///
+    /// # Parameters
+    ///
+    /// * `sequence` - The sequence number of the update
+    /// * `update` - The update
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{
+    ///     L2SweepableMarketData, L2Update, MarketSide, SequenceOutcome, SequenceStatus, SequencedMarketData, UpdateAction,
+    /// };
+    ///
+    /// let mut market_data = SequencedMarketData::new(L2SweepableMarketData::<i32, i32>::new());
+    ///
+    /// let update = L2Update::new(UpdateAction::Add, MarketSide::Bid, 10, 100);
+    ///
+    /// assert_eq!(market_data.update(1, update), Ok(SequenceOutcome::Applied));
+    /// assert_eq!(market_data.update(1, update), Ok(SequenceOutcome::Duplicate));
+    /// assert_eq!(market_data.update(3, update), Ok(SequenceOutcome::Buffered));
+    /// assert_eq!(market_data.get_status(), SequenceStatus::Unreliable { expected: 2 });
+    ///
+    /// assert_eq!(market_data.update(2, update), Ok(SequenceOutcome::Applied));
+    /// assert_eq!(market_data.get_status(), SequenceStatus::Synchronised);
+    /// assert_eq!(market_data.get_last_sequence(), Some(3));
+    /// ```
+    pub fn update(
+        &mut self,
+        sequence: u64,
+        update: B::Update,
+    ) -> Result<SequenceOutcome, MarketDataError> {
+        match self.last_sequence {
+            Some(last) if sequence <= last => return Ok(SequenceOutcome::Duplicate),
+            _ if self.status == SequenceStatus::Recovering => {
+                if self.buffer.contains_key(&sequence) {
+                    return Ok(SequenceOutcome::Duplicate);
+                }
+
+                self.buffer.insert(sequence, update);
+
+                return Ok(SequenceOutcome::Buffered);
+            }
+            Some(last)
+                if last
+                    .checked_add(1)
+                    .is_some_and(|expected| sequence > expected) =>
+            {
+                if self.buffer.contains_key(&sequence) {
+                    return Ok(SequenceOutcome::Duplicate);
+                }
+
+                self.buffer.insert(sequence, update);
+                self.status = SequenceStatus::Unreliable { expected: last + 1 };
+
+                return Ok(SequenceOutcome::Buffered);
+            }
+            _ => {}
+        }
+
+        self.last_sequence = Some(sequence);
+        let result = self.book.apply_incremental(&update);
+        let replayed = self.replay_buffer();
+
+        result.and(replayed).map(|_| SequenceOutcome::Applied)
+    }
+
+    /// Start recovering the book from a snapshot, buffering every update until `resynchronise` is called with it
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{
+    ///     BidOffer, L2SweepableMarketData, L2Update, MarketSide, SequenceOutcome, SequenceStatus, SequencedMarketData,
+    ///     UpdateAction,
+    /// };
+    ///
+    /// let mut market_data = SequencedMarketData::new(L2SweepableMarketData::<i32, i32>::new());
+    ///
+    /// market_data.begin_recovery();
+    ///
+    /// let update = L2Update::new(UpdateAction::Update, MarketSide::Bid, 10, 200);
+    /// assert_eq!(market_data.update(5, update), Ok(SequenceOutcome::Buffered));
+    /// assert_eq!(market_data.update(6, update), Ok(SequenceOutcome::Buffered));
+    /// assert_eq!(market_data.get_status(), SequenceStatus::Recovering);
+    ///
+    /// market_data.resynchronise(5, &[(MarketSide::Bid, 10, 100)]).unwrap();
+    ///
+    /// assert_eq!(market_data.get_status(), SequenceStatus::Synchronised);
+    /// assert_eq!(market_data.get_last_sequence(), Some(6));
+    /// assert_eq!(market_data.get_book().get_price(200), BidOffer::new_with_price(Some(10), None));
+    /// ```
+    pub fn begin_recovery(&mut self) {
+        self.status = SequenceStatus::Recovering;
+    }
+
+    /// Resynchronise the book from a snapshot, replacing everything in it.  Buffered updates newer than the snapshot are
+    /// then replayed, and if they follow on from it without a gap the book is synchronised again.  If the snapshot is
+    /// rejected the book and buffered updates are left as they were.
+    ///
+    /// # Parameters
+    ///
+    /// * `sequence` - The sequence number of the last update included in the snapshot
+    /// * `snapshot` - The levels or orders in the book
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{
+    ///     BidOffer, L2SweepableMarketData, L2Update, MarketSide, SequenceStatus, SequencedMarketData, UpdateAction,
+    /// };
+    ///
+    /// let mut market_data = SequencedMarketData::new(L2SweepableMarketData::<i32, i32>::new());
+    ///
+    /// market_data.update(1, L2Update::new(UpdateAction::Add, MarketSide::Bid, 10, 100)).unwrap();
+    /// market_data.update(4, L2Update::new(UpdateAction::Add, MarketSide::Bid, 11, 100)).unwrap();
+    ///
+    /// market_data.resynchronise(3, &[(MarketSide::Bid, 9, 100)]).unwrap();
+    ///
+    /// assert_eq!(market_data.get_status(), SequenceStatus::Synchronised);
+    /// assert_eq!(market_data.get_book().get_price(200), BidOffer::new_with_price(Some(10), None));
+    /// ```
+    pub fn resynchronise(
+        &mut self,
+        sequence: u64,
+        snapshot: &[B::SnapshotEntry],
+    ) -> Result<(), MarketDataError> {
+        self.book.apply_snapshot(snapshot)?;
+        self.last_sequence = Some(sequence);
+        self.buffer = match sequence.checked_add(1) {
+            Some(next) => self.buffer.split_off(&next),
+            None => BTreeMap::new(),
+        };
+
+        self.replay_buffer()
+    }
+
+    /// Apply the buffered updates which follow on from the last sequence number without a gap
+    fn replay_buffer(&mut self) -> Result<(), MarketDataError> {
+        let mut result = Ok(());
+
+        while let Some(next) = self.next_sequence() {
+            match self.buffer.remove(&next) {
+                Some(update) => {
+                    self.last_sequence = Some(next);
+                    result = result.and(self.book.apply_incremental(&update));
+                }
+                None => break,
+            }
+        }
+
+        self.status = match (self.next_sequence(), self.buffer.is_empty()) {
+            (Some(expected), false) => SequenceStatus::Unreliable { expected },
+            _ => SequenceStatus::Synchronised,
+        };
+
+        result
+    }
+
+    /// Get the sequence number expected next, which is None if nothing has been applied or the sequence has ended
+    fn next_sequence(&self) -> Option<u64> {
+        self.last_sequence?.checked_add(1)
+    }
+
+    /// Get whether every update has been applied in order
+    pub fn get_status(&self) -> SequenceStatus {
+        self.status
+    }
+
+    /// Returns true if no updates have been missed
+    pub fn is_reliable(&self) -> bool {
+        self.status == SequenceStatus::Synchronised
+    }
+
+    /// Get the sequence number of the last update applied to the book
+    pub fn get_last_sequence(&self) -> Option<u64> {
+        self.last_sequence
+    }
+
+    /// Get the number of updates buffered waiting for a gap to be filled
+    pub fn get_buffered_count(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Get the book being kept up to date
+    pub fn get_book(&self) -> &B {
+        &self.book
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market_data::{BidOffer, L3MarketData, L3Update, MarketSide, UpdateAction};
+
+    #[test]
+    fn l3_gap_and_resynchronise() {
+        let mut test = SequencedMarketData::new(L3MarketData::<u64, i32, i32>::new());
+
+        assert_eq!(
+            test.update(
+                10,
+                L3Update::new(UpdateAction::Add, MarketSide::Offer, 1, 15, 10)
+            ),
+            Ok(SequenceOutcome::Applied)
+        );
+        assert_eq!(
+            test.update(
+                12,
+                L3Update::new(UpdateAction::Add, MarketSide::Offer, 2, 14, 10)
+            ),
+            Ok(SequenceOutcome::Buffered)
+        );
+        assert_eq!(
+            test.update(
+                12,
+                L3Update::new(UpdateAction::Add, MarketSide::Offer, 2, 14, 10)
+            ),
+            Ok(SequenceOutcome::Duplicate)
+        );
+        assert_eq!(
+            test.update(
+                14,
+                L3Update::new(UpdateAction::Remove, MarketSide::Offer, 2, 14, 0)
+            ),
+            Ok(SequenceOutcome::Buffered)
+        );
+        assert!(!test.is_reliable());
+        assert_eq!(test.get_buffered_count(), 2);
+        assert_eq!(
+            test.get_book().get_price(10),
+            BidOffer::new_with_price(None, Some(15))
+        );
+
+        // The snapshot includes update 12, so only update 14 is replayed, which leaves a gap at 13
+        test.resynchronise(
+            12,
+            &[
+                (MarketSide::Offer, 1, 15, 10),
+                (MarketSide::Offer, 2, 14, 10),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            test.get_status(),
+            SequenceStatus::Unreliable { expected: 13 }
+        );
+        assert_eq!(
+            test.get_book().get_price(10),
+            BidOffer::new_with_price(None, Some(14))
+        );
+
+        assert_eq!(
+            test.update(
+                13,
+                L3Update::new(UpdateAction::Add, MarketSide::Offer, 3, 16, 10)
+            ),
+            Ok(SequenceOutcome::Applied)
+        );
+        assert!(test.is_reliable());
+        assert_eq!(test.get_last_sequence(), Some(14));
+        assert_eq!(
+            test.get_book().get_price(20),
+            BidOffer::new_with_price(None, Some(15))
+        );
+    }
+
+    #[test]
+    fn failed_update_moves_sequence_on() {
+        let mut test = SequencedMarketData::new(L3MarketData::<u64, i32, i32>::new());
+
+        assert_eq!(
+            test.update(
+                1,
+                L3Update::new(UpdateAction::Update, MarketSide::Bid, 1, 10, 10)
+            ),
+            Err(MarketDataError::UnknownOrder)
+        );
+        assert_eq!(test.get_last_sequence(), Some(1));
+        assert!(test.is_reliable());
+    }
+
+    #[test]
+    fn recovery_buffers_until_snapshot() {
+        let mut test = SequencedMarketData::new(L3MarketData::<u64, i32, i32>::new());
+
+        test.update(
+            1,
+            L3Update::new(UpdateAction::Add, MarketSide::Bid, 1, 10, 10),
+        )
+        .unwrap();
+        test.begin_recovery();
+
+        // Contiguous updates are still buffered while the snapshot is in flight
+        assert_eq!(
+            test.update(
+                2,
+                L3Update::new(UpdateAction::Add, MarketSide::Bid, 2, 11, 10)
+            ),
+            Ok(SequenceOutcome::Buffered)
+        );
+        assert_eq!(
+            test.update(
+                3,
+                L3Update::new(UpdateAction::Remove, MarketSide::Bid, 1, 10, 0)
+            ),
+            Ok(SequenceOutcome::Buffered)
+        );
+        assert_eq!(test.get_last_sequence(), Some(1));
+        assert_eq!(
+            test.get_book().get_price(10),
+            BidOffer::new_with_price(Some(10), None)
+        );
+
+        test.resynchronise(
+            2,
+            &[(MarketSide::Bid, 1, 10, 10), (MarketSide::Bid, 2, 11, 10)],
+        )
+        .unwrap();
+
+        assert!(test.is_reliable());
+        assert_eq!(test.get_last_sequence(), Some(3));
+        assert_eq!(test.get_buffered_count(), 0);
+        assert_eq!(
+            test.get_book()
+                .get_levels(MarketSide::Bid)
+                .collect::<Vec<_>>(),
+            vec![(11, 10)]
+        );
+    }
+
+    #[test]
+    fn sequence_ends_at_max() {
+        let mut test = SequencedMarketData::new(L3MarketData::<u64, i32, i32>::new());
+        let add = |id| L3Update::new(UpdateAction::Add, MarketSide::Bid, id, 10, 10);
+
+        assert_eq!(
+            test.update(u64::MAX - 2, add(1)),
+            Ok(SequenceOutcome::Applied)
+        );
+        assert_eq!(test.update(u64::MAX, add(2)), Ok(SequenceOutcome::Buffered));
+        assert_eq!(
+            test.get_status(),
+            SequenceStatus::Unreliable {
+                expected: u64::MAX - 1
+            }
+        );
+
+        assert_eq!(
+            test.update(u64::MAX - 1, add(3)),
+            Ok(SequenceOutcome::Applied)
+        );
+        assert!(test.is_reliable());
+        assert_eq!(test.get_last_sequence(), Some(u64::MAX));
+        assert_eq!(
+            test.update(u64::MAX, add(4)),
+            Ok(SequenceOutcome::Duplicate)
+        );
+
+        test.resynchronise(u64::MAX, &[(MarketSide::Bid, 1, 10, 10)])
+            .unwrap();
+        assert!(test.is_reliable());
+        assert_eq!(test.get_buffered_count(), 0);
+    }
+}
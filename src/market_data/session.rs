@@ -0,0 +1,293 @@
+use super::{MarketDataError, ParseError, PriceQuality};
+use std::{fmt, str::FromStr, time::Duration};
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The trading status of a market, which moves through pre-open and an opening auction to continuous trading, and can be
+/// halted or go back into auction during the day before closing
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub enum MarketStatus {
+    /// Orders can be entered but nothing trades
+    PreOpen,
+    /// Orders are being collected to be matched at a single price
+    Auction,
+    /// Orders trade as they arrive
+    #[default]
+    Continuous,
+    /// Trading has been stopped by the venue
+    Halted,
+    /// The market is closed for the day
+    Closed,
+}
+
+impl MarketStatus {
+    /// Returns true if the market can move from this status to another.  The pre-open only follows the close, a market
+    /// which is closed cannot be halted, and moving to the same status is always allowed.
+    ///
+    /// # Parameters
+    ///
+    /// * `to` - The status to move to
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::MarketStatus;
+    ///
+    /// assert!(MarketStatus::Closed.can_transition_to(MarketStatus::PreOpen));
+    /// assert!(MarketStatus::Halted.can_transition_to(MarketStatus::Auction));
+    /// assert!(!MarketStatus::Continuous.can_transition_to(MarketStatus::PreOpen));
+    /// assert!(!MarketStatus::Closed.can_transition_to(MarketStatus::Halted));
+    /// ```
+    pub fn can_transition_to(self, to: MarketStatus) -> bool {
+        match (self, to) {
+            _ if self == to => true,
+            (from, MarketStatus::PreOpen) => from == MarketStatus::Closed,
+            (MarketStatus::Closed, MarketStatus::Halted) => false,
+            _ => true,
+        }
+    }
+
+    /// Returns true if prices can be traded on in the status, which is only during continuous trading
+    pub fn has_firm_prices(self) -> bool {
+        self == MarketStatus::Continuous
+    }
+
+    /// Get the quality of a price in the status.  Outside continuous trading a firm price is only indicative, and once
+    /// the market is closed every price is closed.
+    ///
+    /// # Parameters
+    ///
+    /// * `quality` - The quality of the price in continuous trading
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pricing::market_data::{MarketStatus, PriceQuality};
+    ///
+    /// assert_eq!(MarketStatus::Halted.limit_quality(PriceQuality::Firm), PriceQuality::Indicative);
+    /// assert_eq!(MarketStatus::Halted.limit_quality(PriceQuality::Stale), PriceQuality::Stale);
+    /// assert_eq!(MarketStatus::Closed.limit_quality(PriceQuality::Stale), PriceQuality::Closed);
+    /// ```
+    pub fn limit_quality(self, quality: PriceQuality) -> PriceQuality {
+        match (self, quality) {
+            (MarketStatus::Continuous, _) => quality,
+            (MarketStatus::Closed, _) => PriceQuality::Closed,
+            (_, PriceQuality::Firm) => PriceQuality::Indicative,
+            _ => quality,
+        }
+    }
+}
+
+impl fmt::Display for MarketStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarketStatus::PreOpen => write!(f, "PreOpen"),
+            MarketStatus::Auction => write!(f, "Auction"),
+            MarketStatus::Continuous => write!(f, "Continuous"),
+            MarketStatus::Halted => write!(f, "Halted"),
+            MarketStatus::Closed => write!(f, "Closed"),
+        }
+    }
+}
+
+/// Parses the status from its name ignoring case, with "PreOpen" also accepted as "pre_open" or "pre-open"
+///
+/// # Example
+///
+/// ```
+/// use pricing::market_data::MarketStatus;
+///
+/// assert_eq!("pre-open".parse::<MarketStatus>(), Ok(MarketStatus::PreOpen));
+/// assert_eq!(MarketStatus::Halted.to_string().parse::<MarketStatus>(), Ok(MarketStatus::Halted));
+/// ```
+impl FromStr for MarketStatus {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "preopen" | "pre_open" | "pre-open" => Ok(MarketStatus::PreOpen),
+            "auction" => Ok(MarketStatus::Auction),
+            "continuous" => Ok(MarketStatus::Continuous),
+            "halted" => Ok(MarketStatus::Halted),
+            "closed" => Ok(MarketStatus::Closed),
+            _ => Err(ParseError::new("market status", value)),
+        }
+    }
+}
+
+/// The statuses a market moves through each day, as the time of day each starts.  Times of day are the time since
+/// midnight of the clock used by the book, so a schedule in a local time zone needs a clock in that time zone.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use pricing::market_data::{MarketStatus, TradingSchedule};
+///
+/// let hours = |hours: u64| Duration::from_secs(hours * 60 * 60);
+/// let schedule = TradingSchedule::new(vec![
+///     (hours(7), MarketStatus::PreOpen),
+///     (hours(8), MarketStatus::Continuous),
+///     (hours(16), MarketStatus::Closed),
+/// ]);
+///
+/// assert_eq!(schedule.get_status_at(hours(12)), Some(MarketStatus::Continuous));
+/// assert_eq!(schedule.get_status_at(hours(24 + 3)), Some(MarketStatus::Closed));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct TradingSchedule {
+    transitions: Vec<(Duration, MarketStatus)>,
+}
+
+impl TradingSchedule {
+    /// Use the new function to create a new TradingSchedule
+    ///
+    /// # Parameters
+    ///
+    /// * `transitions` - The (time of day, status) at which each status starts, in any order
+    pub fn new(mut transitions: Vec<(Duration, MarketStatus)>) -> Self {
+        transitions.sort_by_key(|&(start, _)| start);
+
+        Self { transitions }
+    }
+
+    /// Get the (time of day, status) at which each status starts, earliest first
+    pub fn get_transitions(&self) -> &[(Duration, MarketStatus)] {
+        &self.transitions
+    }
+
+    /// Get the status the schedule gives at a time.  Before the first status of the day starts the last status of the day
+    /// before carries on.  A schedule with no statuses gives None.
+    ///
+    /// # Parameters
+    ///
+    /// * `now` - The time, of which only the time of day is used
+    pub fn get_status_at(&self, now: Duration) -> Option<MarketStatus> {
+        let time_of_day = Duration::from_nanos((now.as_nanos() % DAY.as_nanos()) as u64);
+
+        self.transitions
+            .iter()
+            .rev()
+            .find(|&&(start, _)| start <= time_of_day)
+            .or_else(|| self.transitions.last())
+            .map(|&(_, status)| status)
+    }
+}
+
+/// The status of the market for a book along with the schedule which drives it
+#[derive(Clone, Debug, Default)]
+pub(crate) struct MarketSession {
+    status: MarketStatus,
+    schedule: Option<TradingSchedule>,
+    // Set when a schedule is set, until the market first moves to the status it gives
+    pending: bool,
+}
+
+impl MarketSession {
+    pub(crate) fn get_status(&self) -> MarketStatus {
+        self.status
+    }
+
+    pub(crate) fn set_schedule(&mut self, schedule: Option<TradingSchedule>) {
+        self.pending = schedule.is_some();
+        self.schedule = schedule;
+    }
+
+    pub(crate) fn get_schedule(&self) -> Option<&TradingSchedule> {
+        self.schedule.as_ref()
+    }
+
+    /// Move to a status, returning the status moved from if it has changed
+    pub(crate) fn transition(
+        &mut self,
+        status: MarketStatus,
+    ) -> Result<Option<MarketStatus>, MarketDataError> {
+        if !self.status.can_transition_to(status) {
+            return Err(MarketDataError::InvalidTransition);
+        }
+
+        let from = std::mem::replace(&mut self.status, status);
+
+        Ok((from != status).then_some(from))
+    }
+
+    /// Move to the status the schedule gives at a time, returning the status moved from if it has changed.  The first
+    /// check after the schedule is set moves straight to the status it gives, as the market may not have been following
+    /// it before.  A halt is lifted by the venue rather than the schedule, so a halted market only follows the schedule
+    /// when it closes.
+    pub(crate) fn check_schedule(&mut self, now: Duration) -> Option<MarketStatus> {
+        let status = self.schedule.as_ref()?.get_status_at(now)?;
+
+        if self.status == MarketStatus::Halted && status != MarketStatus::Closed {
+            return None;
+        }
+
+        if std::mem::take(&mut self.pending) {
+            let from = std::mem::replace(&mut self.status, status);
+
+            return (from != status).then_some(from);
+        }
+
+        self.transition(status).ok().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_follows_schedule() {
+        let hours = |hours: u64| Duration::from_secs(hours * 60 * 60);
+        let mut test = MarketSession::default();
+
+        assert_eq!(test.check_schedule(hours(9)), None);
+
+        test.set_schedule(Some(TradingSchedule::new(vec![
+            (hours(16), MarketStatus::Closed),
+            (hours(7), MarketStatus::PreOpen),
+            (hours(8), MarketStatus::Auction),
+            (hours(9), MarketStatus::Continuous),
+        ])));
+
+        // The first check moves straight to the scheduled status, even though continuous trading cannot usually go
+        // back to the pre-open
+        assert_eq!(
+            test.check_schedule(hours(7)),
+            Some(MarketStatus::Continuous)
+        );
+        assert_eq!(test.get_status(), MarketStatus::PreOpen);
+        assert_eq!(test.check_schedule(hours(8)), Some(MarketStatus::PreOpen));
+        assert_eq!(
+            test.transition(MarketStatus::Continuous),
+            Ok(Some(MarketStatus::Auction))
+        );
+        assert_eq!(test.check_schedule(hours(24 + 7)), None);
+        assert_eq!(
+            test.check_schedule(hours(17)),
+            Some(MarketStatus::Continuous)
+        );
+        assert_eq!(
+            test.check_schedule(hours(24 + 7)),
+            Some(MarketStatus::Closed)
+        );
+        assert_eq!(test.get_status(), MarketStatus::PreOpen);
+
+        assert_eq!(
+            test.transition(MarketStatus::Halted),
+            Ok(Some(MarketStatus::PreOpen))
+        );
+        assert_eq!(test.transition(MarketStatus::Halted), Ok(None));
+        assert_eq!(test.check_schedule(hours(24 + 9)), None);
+        assert_eq!(
+            test.check_schedule(hours(24 + 16)),
+            Some(MarketStatus::Halted)
+        );
+
+        assert_eq!(
+            test.transition(MarketStatus::Halted),
+            Err(MarketDataError::InvalidTransition)
+        );
+        assert_eq!(test.get_status(), MarketStatus::Closed);
+    }
+}
@@ -0,0 +1,41 @@
+use super::{L1MarketCallback, MarketSide};
+use std::cell::RefCell;
+
+/// A callback which records whether the market has been updated and which sides have gone stale, shared by the tests of
+/// the books
+pub(crate) struct TestCallback {
+    called: RefCell<bool>,
+    stale: RefCell<Vec<MarketSide>>,
+}
+
+impl TestCallback {
+    pub(crate) fn new() -> Self {
+        Self {
+            called: RefCell::new(false),
+            stale: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn reset(&self) {
+        *self.called.borrow_mut() = false;
+        self.stale.borrow_mut().clear();
+    }
+
+    pub(crate) fn is_called(&self) -> bool {
+        *self.called.borrow()
+    }
+
+    pub(crate) fn get_stale(&self) -> Vec<MarketSide> {
+        self.stale.borrow().clone()
+    }
+}
+
+impl L1MarketCallback for TestCallback {
+    fn market_updated(&self) {
+        *self.called.borrow_mut() = true;
+    }
+
+    fn market_stale(&self, side: MarketSide) {
+        self.stale.borrow_mut().push(side);
+    }
+}
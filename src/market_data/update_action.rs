@@ -1,4 +1,8 @@
+use super::ParseError;
+use std::{fmt, str::FromStr};
+
 /// The update action for pricing
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub enum UpdateAction {
     /// Add a new price
     Add,
@@ -6,4 +10,50 @@ pub enum UpdateAction {
     Update,
     /// Remove an existing price
     Remove,
+    /// Remove every price on the side, ignoring the price and size of the update
+    ClearSide,
+    /// Set the price whether or not it exists.  For an order this replaces any existing order with the id, which loses
+    /// its place in the queue.
+    Replace,
+    /// Clear the whole book and add the price, which starts a snapshot sent as a series of updates
+    Snapshot,
+}
+
+impl fmt::Display for UpdateAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpdateAction::Add => write!(f, "Add"),
+            UpdateAction::Update => write!(f, "Update"),
+            UpdateAction::Remove => write!(f, "Remove"),
+            UpdateAction::ClearSide => write!(f, "ClearSide"),
+            UpdateAction::Replace => write!(f, "Replace"),
+            UpdateAction::Snapshot => write!(f, "Snapshot"),
+        }
+    }
+}
+
+/// Parses the action from its name ignoring case, with "ClearSide" also accepted as "clear_side"
+///
+/// # Example
+///
+/// ```
+/// use pricing::market_data::UpdateAction;
+///
+/// assert_eq!("clear_side".parse::<UpdateAction>(), Ok(UpdateAction::ClearSide));
+/// assert_eq!(UpdateAction::Replace.to_string().parse::<UpdateAction>(), Ok(UpdateAction::Replace));
+/// ```
+impl FromStr for UpdateAction {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "add" => Ok(UpdateAction::Add),
+            "update" => Ok(UpdateAction::Update),
+            "remove" => Ok(UpdateAction::Remove),
+            "clearside" | "clear_side" => Ok(UpdateAction::ClearSide),
+            "replace" => Ok(UpdateAction::Replace),
+            "snapshot" => Ok(UpdateAction::Snapshot),
+            _ => Err(ParseError::new("update action", value)),
+        }
+    }
 }
@@ -0,0 +1,126 @@
+use super::{MarketDataError, UpdateAction};
+
+/// How a depth book handles an update which does not match the levels already in it
+///
+/// # Example
+///
+/// ```
+/// use pricing::market_data::{L2SweepableMarketData, MarketDataError, MarketSide, UpdateAction, UpdateMode};
+///
+/// let mut market_data = L2SweepableMarketData::<i32, i32>::new();
+///
+/// market_data.set_update_mode(UpdateMode::Strict);
+/// assert_eq!(
+///     market_data.update(UpdateAction::Update, MarketSide::Bid, 10, 50),
+///     Err(MarketDataError::UnknownLevel)
+/// );
+///
+/// market_data.set_update_mode(UpdateMode::Upsert);
+/// market_data.update(UpdateAction::Update, MarketSide::Bid, 10, 50).unwrap();
+/// assert_eq!(market_data.get_levels(MarketSide::Bid).collect::<Vec<_>>(), vec![(10, 50)]);
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum UpdateMode {
+    /// An add replaces an existing level, and an update or remove of a missing level is ignored
+    #[default]
+    Lenient,
+    /// An add of an existing level, an update or remove of a missing level, and a level set with a size which is not
+    /// positive are rejected with an error
+    Strict,
+    /// An add or update sets the level whether or not it exists, and a remove of a missing level is ignored
+    Upsert,
+}
+
+impl UpdateMode {
+    /// Rejects an action which does not match whether its level exists if the mode is strict
+    pub(crate) fn check<A>(
+        self,
+        action: UpdateAction,
+        exists: bool,
+        size: A,
+    ) -> Result<(), MarketDataError>
+    where
+        A: PartialOrd + From<u8>,
+    {
+        if self != UpdateMode::Strict {
+            return Ok(());
+        }
+
+        match action {
+            UpdateAction::Add if exists => Err(MarketDataError::LevelExists),
+            UpdateAction::Update | UpdateAction::Remove if !exists => {
+                Err(MarketDataError::UnknownLevel)
+            }
+            UpdateAction::Add
+            | UpdateAction::Update
+            | UpdateAction::Replace
+            | UpdateAction::Snapshot
+                if size <= A::from(0) =>
+            {
+                Err(MarketDataError::InvalidSize)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns true if the level is in the book after the action, given whether it was before
+    pub(crate) fn leaves_level(self, action: UpdateAction, exists: bool) -> bool {
+        match action {
+            UpdateAction::Add | UpdateAction::Replace | UpdateAction::Snapshot => true,
+            UpdateAction::Update => exists || self == UpdateMode::Upsert,
+            UpdateAction::Remove | UpdateAction::ClearSide => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strict_checks() {
+        let mode = UpdateMode::Strict;
+
+        assert_eq!(mode.check(UpdateAction::Add, false, 10), Ok(()));
+        assert_eq!(
+            mode.check(UpdateAction::Add, true, 10),
+            Err(MarketDataError::LevelExists)
+        );
+        assert_eq!(
+            mode.check(UpdateAction::Update, false, 10),
+            Err(MarketDataError::UnknownLevel)
+        );
+        assert_eq!(
+            mode.check(UpdateAction::Remove, false, 0),
+            Err(MarketDataError::UnknownLevel)
+        );
+        assert_eq!(mode.check(UpdateAction::Remove, true, 0), Ok(()));
+        assert_eq!(
+            mode.check(UpdateAction::Update, true, 0),
+            Err(MarketDataError::InvalidSize)
+        );
+        assert_eq!(
+            mode.check(UpdateAction::Add, false, -5),
+            Err(MarketDataError::InvalidSize)
+        );
+
+        assert_eq!(mode.check(UpdateAction::Replace, true, 10), Ok(()));
+        assert_eq!(
+            mode.check(UpdateAction::Snapshot, false, 0),
+            Err(MarketDataError::InvalidSize)
+        );
+        assert_eq!(mode.check(UpdateAction::ClearSide, false, 0), Ok(()));
+        assert_eq!(
+            UpdateMode::Lenient.check(UpdateAction::Update, false, -5),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn leaves_level() {
+        assert!(!UpdateMode::Lenient.leaves_level(UpdateAction::Update, false));
+        assert!(UpdateMode::Upsert.leaves_level(UpdateAction::Update, false));
+        assert!(UpdateMode::Strict.leaves_level(UpdateAction::Update, true));
+        assert!(!UpdateMode::Upsert.leaves_level(UpdateAction::Remove, true));
+    }
+}
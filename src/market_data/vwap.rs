@@ -0,0 +1,346 @@
+use std::ops::{Add, Div, Mul, Sub};
+
+/// The arithmetic used when calculating a VWAP.  The running notional (price * size) is accumulated in a type which can
+/// be wider than the amount itself, so that large sizes do not silently overflow, and any value which cannot be
+/// represented results in None rather than a wrapped or saturated price.
+///
+/// Implementations are provided for the primitive integer types, which accumulate in an `i128` (or `u128`) using checked
+/// arithmetic, and for the floating point types which use compensated summation to limit the loss of precision.  An
+/// unsigned integer amount can also be used with a signed price of the same width.  Other price and amount types which
+/// differ can implement [`UncheckedVwap`] to use their own arithmetic.
+///
+/// # Generic Parameters
+///
+/// * `P` - The price type the amount is multiplied by.
+pub trait VwapAmount<P>: Copy {
+    /// The type the running notional is accumulated in
+    type Notional: Copy;
+
+    /// The starting value of the running notional
+    fn zero_notional() -> Self::Notional;
+
+    /// Adds price * size to the running notional, returning None if the result cannot be represented
+    fn accumulate(notional: Self::Notional, price: P, size: Self) -> Option<Self::Notional>;
+
+    /// Divides the notional by the total size to give the average price, returning None if it cannot be represented
+    fn average(notional: Self::Notional, size: Self) -> Option<P>;
+}
+
+/// Opts an amount into a VWAP calculated with the arithmetic of the price and amount themselves, for price and amount
+/// types which differ, where a price multiplied by an amount gives an amount and an amount divided by an amount gives a
+/// price.  The notional is accumulated in the amount type with no overflow checking, so the types should handle
+/// overflow themselves.
+///
+/// # Generic Parameters
+///
+/// * `P` - The price type the amount is multiplied by.
+///
+/// # Example
+///
+/// ```
+/// use std::ops::{Add, Div, Mul};
+/// use pricing::market_data::{UncheckedVwap, VwapAmount};
+///
+/// #[derive(Copy, Clone, PartialEq, Debug)]
+/// struct Price(f64);
+///
+/// #[derive(Copy, Clone, PartialEq, Debug, Default)]
+/// struct Amount(f64);
+///
+/// impl Mul<Amount> for Price {
+///     type Output = Amount;
+///
+///     fn mul(self, amount: Amount) -> Amount {
+///         Amount(self.0 * amount.0)
+///     }
+/// }
+///
+/// impl Add for Amount {
+///     type Output = Amount;
+///
+///     fn add(self, amount: Amount) -> Amount {
+///         Amount(self.0 + amount.0)
+///     }
+/// }
+///
+/// impl Div for Amount {
+///     type Output = Price;
+///
+///     fn div(self, amount: Amount) -> Price {
+///         Price(self.0 / amount.0)
+///     }
+/// }
+///
+/// impl UncheckedVwap<Price> for Amount {}
+///
+/// let notional = Amount::accumulate(Amount::zero_notional(), Price(10.0), Amount(1.0)).unwrap();
+/// let notional = Amount::accumulate(notional, Price(13.0), Amount(2.0)).unwrap();
+///
+/// assert_eq!(Amount::average(notional, Amount(3.0)), Some(Price(12.0)));
+/// ```
+pub trait UncheckedVwap<P>: Copy + Default + Add<Output = Self> + Div<Output = P>
+where
+    P: Mul<Self, Output = Self>,
+{
+}
+
+impl<P, A> VwapAmount<P> for A
+where
+    A: UncheckedVwap<P>,
+    P: Mul<A, Output = A>,
+{
+    type Notional = A;
+
+    fn zero_notional() -> Self::Notional {
+        A::default()
+    }
+
+    fn accumulate(notional: Self::Notional, price: P, size: Self) -> Option<Self::Notional> {
+        Some(notional + price * size)
+    }
+
+    fn average(notional: Self::Notional, size: Self) -> Option<P> {
+        Some(notional / size)
+    }
+}
+
+/// Sweep levels, best first, to find the VWAP of a size, returning None if the levels are not deep enough to fill it or
+/// the VWAP cannot be represented
+pub(crate) fn sweep<P, A, I>(size: A, levels: I) -> Option<P>
+where
+    A: VwapAmount<P> + PartialOrd + Add<Output = A> + Sub<Output = A> + Default,
+    I: Iterator<Item = (P, A)>,
+{
+    let mut current_size = A::default();
+    let mut current_notional = A::zero_notional();
+
+    for (next_price, next_size) in levels {
+        let mut incremental_size = next_size;
+
+        // Compare against the remaining size rather than summing, so the sizes themselves cannot overflow
+        if next_size > size - current_size {
+            incremental_size = size - current_size;
+        }
+
+        current_notional = A::accumulate(current_notional, next_price, incremental_size)?;
+        current_size = current_size + incremental_size;
+
+        // Should always be equal at this point but just in case of some weird rounding issues
+        if current_size >= size {
+            return A::average(current_notional, current_size);
+        }
+    }
+
+    None
+}
+
+/// A floating point sum which tracks the low order bits lost on each addition (Kahan-Babuska summation).
+///
+/// # Generic Parameters
+///
+/// * `T` - The floating point type being summed.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct CompensatedSum<T> {
+    sum: T,
+    compensation: T,
+}
+
+macro_rules! compensated_sum {
+    ($($t:ty),*) => {
+        $(
+            impl CompensatedSum<$t> {
+                /// Adds a value to the sum
+                ///
+                /// # Example
+                ///
+                /// ```
+                /// use pricing::market_data::CompensatedSum;
+                ///
+                #[doc = concat!("let mut sum = CompensatedSum::<", stringify!($t), ">::default();")]
+                ///
+                /// sum.add(1.0);
+                /// sum.add(1e30);
+                /// sum.add(1.0);
+                /// sum.add(-1e30);
+                ///
+                /// assert_eq!(sum.value(), 2.0);
+                /// ```
+                pub fn add(&mut self, value: $t) {
+                    let total = self.sum + value;
+
+                    if self.sum.abs() >= value.abs() {
+                        self.compensation += (self.sum - total) + value;
+                    } else {
+                        self.compensation += (value - total) + self.sum;
+                    }
+
+                    self.sum = total;
+                }
+
+                /// Get the current value of the sum
+                pub fn value(&self) -> $t {
+                    self.sum + self.compensation
+                }
+            }
+
+            impl VwapAmount<$t> for $t {
+                type Notional = CompensatedSum<$t>;
+
+                fn zero_notional() -> Self::Notional {
+                    CompensatedSum::default()
+                }
+
+                fn accumulate(notional: Self::Notional, price: $t, size: Self) -> Option<Self::Notional> {
+                    let mut notional = notional;
+                    notional.add(price * size);
+
+                    if notional.value().is_finite() {
+                        Some(notional)
+                    } else {
+                        None
+                    }
+                }
+
+                fn average(notional: Self::Notional, size: Self) -> Option<$t> {
+                    let average = notional.value() / size;
+
+                    if average.is_finite() {
+                        Some(average)
+                    } else {
+                        None
+                    }
+                }
+            }
+        )*
+    };
+}
+
+// Each price type is listed with the amount types it can be paired with
+macro_rules! widened_integer {
+    ($wide:ty => $($p:ty: $($a:ty),*);*) => {
+        $($(
+            impl VwapAmount<$p> for $a {
+                type Notional = $wide;
+
+                fn zero_notional() -> Self::Notional {
+                    0
+                }
+
+                fn accumulate(notional: Self::Notional, price: $p, size: Self) -> Option<Self::Notional> {
+                    <$wide>::try_from(price)
+                        .ok()?
+                        .checked_mul(<$wide>::try_from(size).ok()?)?
+                        .checked_add(notional)
+                }
+
+                fn average(notional: Self::Notional, size: Self) -> Option<$p> {
+                    <$p>::try_from(notional.checked_div(<$wide>::try_from(size).ok()?)?).ok()
+                }
+            }
+        )*)*
+    };
+}
+
+compensated_sum!(f32, f64);
+widened_integer!(
+    i128 => i8: i8, u8; i16: i16, u16; i32: i32, u32; i64: i64, u64; i128: i128, u128; isize: isize, usize
+);
+widened_integer!(u128 => u8: u8; u16: u16; u32: u32; u64: u64; u128: u128; usize: usize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vwap<A: VwapAmount<A>>(levels: &[(A, A)], size: A) -> Option<A> {
+        let mut notional = A::zero_notional();
+
+        for &(price, size) in levels {
+            notional = A::accumulate(notional, price, size)?;
+        }
+
+        A::average(notional, size)
+    }
+
+    #[test]
+    fn integer_widened() {
+        assert_eq!(
+            vwap(&[(100_000, 1_000_000), (200_000, 1_000_000)], 2_000_000),
+            Some(150_000i32)
+        );
+    }
+
+    #[test]
+    fn integer_overflow() {
+        assert_eq!(vwap(&[(i128::MAX, 2)], 2), None);
+        assert_eq!(vwap(&[(i128::MAX, 1), (1, 1)], 2), None);
+        assert_eq!(vwap(&[(u64::MAX, 1), (u64::MAX, 1)], 2), Some(u64::MAX));
+        assert_eq!(vwap(&[(10, 1)], 0), None);
+    }
+
+    #[test]
+    fn float_compensated() {
+        let levels = vec![(0.1, 1.0); 10];
+
+        assert_eq!(vwap(&levels, 10.0), Some(0.1));
+        assert_eq!(vwap(&[(f64::MAX, 2.0)], 2.0), None);
+        assert_eq!(vwap(&[(1.0, 1.0)], 0.0), None);
+    }
+
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    struct Price(i64);
+
+    #[derive(Copy, Clone, PartialEq, PartialOrd, Debug, Default)]
+    struct Amount(i64);
+
+    impl Mul<Amount> for Price {
+        type Output = Amount;
+
+        fn mul(self, amount: Amount) -> Amount {
+            Amount(self.0 * amount.0)
+        }
+    }
+
+    impl Add for Amount {
+        type Output = Amount;
+
+        fn add(self, amount: Amount) -> Amount {
+            Amount(self.0 + amount.0)
+        }
+    }
+
+    impl Sub for Amount {
+        type Output = Amount;
+
+        fn sub(self, amount: Amount) -> Amount {
+            Amount(self.0 - amount.0)
+        }
+    }
+
+    impl Div for Amount {
+        type Output = Price;
+
+        fn div(self, amount: Amount) -> Price {
+            Price(self.0 / amount.0)
+        }
+    }
+
+    impl UncheckedVwap<Price> for Amount {}
+
+    #[test]
+    fn sweep_mixed_types() {
+        let levels = [(Price(10), Amount(50)), (Price(13), Amount(100))];
+
+        assert_eq!(sweep(Amount(50), levels.into_iter()), Some(Price(10)));
+        assert_eq!(sweep(Amount(150), levels.into_iter()), Some(Price(12)));
+        assert_eq!(sweep(Amount(151), levels.into_iter()), None);
+        assert_eq!(sweep(25, [(10, 20), (16, 20)].into_iter()), Some(11));
+    }
+
+    #[test]
+    fn sweep_unsigned_amount() {
+        let levels = [(-10_i32, 50_u32), (-13, 100)];
+
+        assert_eq!(sweep(150_u32, levels.into_iter()), Some(-12));
+        assert_eq!(sweep(200_u32, levels.into_iter()), None);
+    }
+}